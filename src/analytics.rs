@@ -0,0 +1,220 @@
+use yew::prelude::*;
+
+use reqwest::Client;
+use serde::Deserialize;
+
+use crate::settings;
+use crate::utils::send_future;
+
+
+/// The size of the charts' view box, the svg scales to the panel width.
+const CHART_WIDTH: f64 = 300.0;
+const CHART_HEIGHT: f64 = 100.0;
+
+/// How many of the most queued sources are listed.
+const TOP_SOURCES: usize = 5;
+
+
+/// The stats of a single watch session of the room.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SessionStats {
+    /// The day the session started on e.g. `2021-02-14`.
+    pub date: String,
+
+    /// The most members in the room at once.
+    pub attendees: u32,
+
+    /// The total hours watched by all members.
+    pub watch_hours: f64,
+
+    /// The messages sent during the session.
+    pub messages: u32,
+}
+
+
+/// How often a source has been queued in the room.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SourceCount {
+    /// The host of the source e.g. `youtube.com`.
+    pub source: String,
+    pub count: u32,
+}
+
+
+/// The room's analytics as summarised by the api, only the owner can
+/// fetch these.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RoomAnalytics {
+    /// The sessions of the room, oldest first.
+    #[serde(default)]
+    pub sessions: Vec<SessionStats>,
+
+    /// The most queued sources, most queued first.
+    #[serde(default)]
+    pub top_sources: Vec<SourceCount>,
+}
+
+
+/// Fetches the room's analytics.
+async fn fetch_analytics(room_id: String) -> AnalyticsDashboardEvents {
+    let url = settings::get_room_analytics_url(&room_id);
+
+    let resp = Client::new()
+        .get(&url)
+        .send()
+        .await;
+
+    match resp {
+        Ok(resp) if resp.status().is_success() => {
+            match resp.json::<RoomAnalytics>().await {
+                Ok(analytics) => AnalyticsDashboardEvents::Loaded(analytics),
+                Err(_) => AnalyticsDashboardEvents::RequestError,
+            }
+        },
+        _ => AnalyticsDashboardEvents::RequestError,
+    }
+}
+
+
+/// Renders the values as a bar chart with the maximum labelled.
+fn bar_chart(title: &str, values: &[f64], colour: &str) -> Html {
+    let max = values.iter().copied().fold(0.0, f64::max);
+    let width = CHART_WIDTH / values.len().max(1) as f64;
+
+    let bars = values.iter().enumerate().map(|(i, value)| {
+        let height = if max > 0.0 { value / max * CHART_HEIGHT } else { 0.0 };
+
+        html! {
+            <rect
+                x=(i as f64 * width + 1.0).to_string()
+                y=(CHART_HEIGHT - height).to_string()
+                width=(width - 2.0).max(1.0).to_string()
+                height=height.to_string()
+                fill=colour
+            />
+        }
+    });
+
+    let view_box = format!("0 0 {} {}", CHART_WIDTH, CHART_HEIGHT);
+
+    html! {
+        <div class="mb-4">
+            <div class="flex justify-between">
+                <h2 class="text-white text-sm font-semibold">{ title }</h2>
+                <span class="text-gray-400 text-xs">{ format!("max {}", (max * 10.0).round() / 10.0) }</span>
+            </div>
+            <svg viewBox=view_box preserveAspectRatio="none" class="w-full h-24 bg-gray-900 rounded">
+                { for bars }
+            </svg>
+        </div>
+    }
+}
+
+
+#[derive(Properties, Clone)]
+pub struct AnalyticsDashboardProperties {
+    /// The room id.
+    pub room_id: String,
+}
+
+
+/// The events that can be invoked by callbacks for the AnalyticsDashboard.
+pub enum AnalyticsDashboardEvents {
+    /// The analytics have been fetched.
+    Loaded(RoomAnalytics),
+
+    /// The request failed or the user doesn't own the room.
+    RequestError,
+}
+
+
+/// The owner's summary of the room's sessions over time.
+pub struct AnalyticsDashboard {
+    analytics: Option<RoomAnalytics>,
+    failed: bool,
+}
+
+impl Component for AnalyticsDashboard {
+    type Message = AnalyticsDashboardEvents;
+    type Properties = AnalyticsDashboardProperties;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        send_future(link, fetch_analytics(props.room_id));
+
+        Self {
+            analytics: None,
+            failed: false,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            AnalyticsDashboardEvents::Loaded(analytics) => self.analytics = Some(analytics),
+            AnalyticsDashboardEvents::RequestError => self.failed = true,
+        }
+
+        true
+    }
+
+    fn change(&mut self, _props: Self::Properties) -> ShouldRender {
+        false
+    }
+
+    fn view(&self) -> Html {
+        let analytics = match (self.analytics.as_ref(), self.failed) {
+            (Some(analytics), _) => analytics,
+            (None, true) => return html! {
+                <div class="bg-discord-dark rounded-lg p-4 mt-4 text-white">
+                    { "Failed to load the room's analytics." }
+                </div>
+            },
+            (None, false) => return html! {
+                <div class="bg-discord-dark rounded-lg p-4 mt-4 text-white">{ "Loading analytics..." }</div>
+            },
+        };
+
+        if analytics.sessions.is_empty() {
+            return html! {
+                <div class="bg-discord-dark rounded-lg p-4 mt-4 text-white">
+                    { "No sessions yet, check back after your first watch party." }
+                </div>
+            };
+        }
+
+        let sessions = &analytics.sessions;
+        let attendance: Vec<f64> = sessions.iter().map(|s| f64::from(s.attendees)).collect();
+        let hours: Vec<f64> = sessions.iter().map(|s| s.watch_hours).collect();
+        let messages: Vec<f64> = sessions.iter().map(|s| f64::from(s.messages)).collect();
+
+        let total_hours: f64 = hours.iter().sum();
+        let first = &sessions[0].date;
+        let last = &sessions[sessions.len() - 1].date;
+
+        let sources = analytics.top_sources.iter().take(TOP_SOURCES).map(|source| {
+            html! {
+                <li class="flex justify-between text-white text-sm">
+                    <span>{ &source.source }</span>
+                    <span class="text-gray-400">{ source.count }</span>
+                </li>
+            }
+        });
+
+        html! {
+            <div class="bg-discord-dark rounded-lg p-4 mt-4">
+                <div class="flex justify-between items-baseline mb-4">
+                    <h1 class="text-white font-bold text-lg">{ "Room analytics" }</h1>
+                    <span class="text-gray-400 text-xs">
+                        { format!("{} sessions from {} to {}, {:.1} hours watched", sessions.len(), first, last, total_hours) }
+                    </span>
+                </div>
+                <div class="grid grid-cols-3 gap-4">
+                    { bar_chart("Attendance", &attendance, "var(--room-accent)") }
+                    { bar_chart("Watch hours", &hours, "#10b981") }
+                    { bar_chart("Chat messages", &messages, "#f59e0b") }
+                </div>
+                <h2 class="text-white text-sm font-semibold mb-1">{ "Most queued sources" }</h2>
+                <ul>{ for sources }</ul>
+            </div>
+        }
+    }
+}
@@ -0,0 +1,359 @@
+use yew::prelude::*;
+use yew::services::TimeoutService;
+use yew::services::timeout::TimeoutTask;
+
+use serde::{Serialize, Deserialize};
+use std::time::Duration;
+
+use crate::opcodes;
+use crate::settings;
+use crate::theme::is_hex_colour;
+use crate::utils::{start_future, emit_event};
+use crate::websocket::{WsHandler, WebsocketMessage, WrappingWsMessage};
+
+
+/// The overlay durations the host can pick from in seconds, 0 keeps the
+/// announcement in chat only.
+const OVERLAY_OPTIONS: [u64; 4] = [0, 5, 10, 30];
+
+/// The colours the host can pick from for an announcement.
+const COLOUR_OPTIONS: [(&str, &str); 4] = [
+    ("Red", "#ef4444"),
+    ("Yellow", "#f59e0b"),
+    ("Green", "#10b981"),
+    ("Blue", "#3b82f6"),
+];
+
+
+/// How an announcement is styled.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct AnnouncementStyle {
+    /// The colour of the announcement as a `#rrggbb` hex string, the
+    /// room's accent is used if this is missing or invalid.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub colour: Option<String>,
+
+    /// An emoji shown before the announcement.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+}
+
+
+/// A message from the host shown prominently to the whole room.
+///
+/// Only the host may send these, the gateway drops announcements from
+/// anyone else.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Announcement {
+    pub content: String,
+
+    /// The name of the host, attached by the gateway.
+    #[serde(default, skip_serializing)]
+    pub username: String,
+
+    #[serde(default)]
+    pub style: AnnouncementStyle,
+
+    /// How long the announcement is shown over the video for, None if
+    /// it is only shown in chat.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub overlay_secs: Option<u64>,
+}
+
+impl Announcement {
+    /// The colour of the announcement as a css value.
+    fn colour(&self) -> String {
+        match self.style.colour.as_ref() {
+            Some(colour) if is_hex_colour(colour) => colour.clone(),
+            _ => "var(--room-accent)".to_string(),
+        }
+    }
+
+    fn heading(&self) -> String {
+        let icon = self.style.icon.as_deref().unwrap_or("📣");
+        if self.username.is_empty() {
+            format!("{} Announcement", icon)
+        } else {
+            format!("{} Announcement from {}", icon, self.username)
+        }
+    }
+
+    /// Renders the announcement as the banner pinned to the top of chat.
+    pub fn banner_html(&self, on_dismiss: Callback<MouseEvent>) -> Html {
+        let style = format!("border-color: {};", self.colour());
+
+        html! {
+            <div class="border-l-4 bg-gray-800 rounded-lg p-3 mb-2" data-style=style>
+                <div class="flex justify-between items-center">
+                    <h1 class="text-white text-sm font-bold">{ self.heading() }</h1>
+                    <button onclick=on_dismiss class="text-white text-sm focus:outline-none">{ "✕" }</button>
+                </div>
+                <p class="text-white break-words">{ &self.content }</p>
+            </div>
+        }
+    }
+}
+
+
+#[derive(Properties, Clone)]
+pub struct AnnouncementOverlayProperties {
+    /// The room websocket handle.
+    pub ws: WsHandler,
+}
+
+
+/// The events that can be invoked by callbacks for the AnnouncementOverlay.
+pub enum AnnouncementOverlayEvents {
+    /// The host has made an announcement.
+    Announce(WebsocketMessage),
+
+    /// The announcement's overlay duration has elapsed.
+    Expire,
+}
+
+
+/// Shows announcements over the video for their overlay duration.
+pub struct AnnouncementOverlay {
+    link: ComponentLink<Self>,
+    _ws: WsHandler,
+    current: Option<Announcement>,
+    expire: Option<TimeoutTask>,
+}
+
+impl Component for AnnouncementOverlay {
+    type Message = AnnouncementOverlayEvents;
+    type Properties = AnnouncementOverlayProperties;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let ws = props.ws;
+        let cb = link.callback(AnnouncementOverlayEvents::Announce);
+        ws.subscribe_to_message(settings::ANNOUNCE_ID, opcodes::OP_ANNOUNCE, cb);
+
+        Self {
+            link,
+            _ws: ws,
+            current: None,
+            expire: None,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            AnnouncementOverlayEvents::Announce(msg) => {
+                let announcement: Announcement = match msg.unwrap_and_into() {
+                    Some(announcement) => announcement,
+                    None => return false,
+                };
+
+                let secs = match announcement.overlay_secs {
+                    Some(secs) if secs > 0 => secs,
+                    _ => return false,
+                };
+
+                let task = TimeoutService::spawn(
+                    Duration::from_secs(secs),
+                    self.link.callback(|_| AnnouncementOverlayEvents::Expire),
+                );
+                self.expire = Some(task);
+                self.current = Some(announcement);
+            },
+            AnnouncementOverlayEvents::Expire => {
+                self.expire = None;
+                self.current = None;
+            },
+        }
+
+        true
+    }
+
+    fn change(&mut self, _props: Self::Properties) -> ShouldRender {
+        false
+    }
+
+    fn view(&self) -> Html {
+        let announcement = match self.current.as_ref() {
+            Some(announcement) => announcement,
+            None => return html!{},
+        };
+
+        let style = format!("background-color: {};", announcement.colour());
+
+        html! {
+            <div class="absolute top-0 inset-x-0 flex justify-center pointer-events-none z-20 mt-8 px-8">
+                <div class="rounded-lg shadow-lg px-6 py-3 max-w-full" data-style=style>
+                    <h1 class="text-white text-sm font-bold">{ announcement.heading() }</h1>
+                    <p class="text-white text-xl font-semibold break-words">
+                        { &announcement.content }
+                    </p>
+                </div>
+            </div>
+        }
+    }
+}
+
+
+#[derive(Properties, Clone)]
+pub struct AnnouncerProperties {
+    /// The room id.
+    pub room_id: String,
+}
+
+
+/// The events that can be invoked by callbacks for the Announcer.
+pub enum AnnouncerEvents {
+    /// The form has been opened or closed.
+    Toggle,
+
+    /// The announcement text has changed.
+    Content(String),
+
+    /// A colour has been picked, None uses the room's accent.
+    Colour(Option<String>),
+
+    /// The overlay duration has been picked.
+    OverlaySecs(u64),
+
+    /// The announcement should be sent.
+    Send,
+}
+
+
+/// The host's form for making announcements, only rendered for the host.
+pub struct Announcer {
+    link: ComponentLink<Self>,
+    room_id: String,
+    open: bool,
+    content: String,
+    colour: Option<String>,
+    overlay_secs: u64,
+}
+
+impl Component for Announcer {
+    type Message = AnnouncerEvents;
+    type Properties = AnnouncerProperties;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        Self {
+            link,
+            room_id: props.room_id,
+            open: false,
+            content: String::new(),
+            colour: None,
+            overlay_secs: 0,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            AnnouncerEvents::Toggle => self.open = !self.open,
+            AnnouncerEvents::Content(content) => {
+                self.content = content;
+                return false;
+            },
+            AnnouncerEvents::Colour(colour) => self.colour = colour,
+            AnnouncerEvents::OverlaySecs(secs) => self.overlay_secs = secs,
+            AnnouncerEvents::Send => {
+                let content = self.content.trim();
+                if content.is_empty() {
+                    return false;
+                }
+
+                let announcement = Announcement {
+                    content: content.to_string(),
+                    username: String::new(),
+                    style: AnnouncementStyle {
+                        colour: self.colour.clone(),
+                        icon: None,
+                    },
+                    overlay_secs: Some(self.overlay_secs).filter(|secs| *secs > 0),
+                };
+
+                let payload = WrappingWsMessage {
+                    opcode: opcodes::OP_ANNOUNCE,
+                    payload: serde_json::to_value(announcement).ok(),
+                    mirror: false,
+                };
+                start_future(emit_event(self.room_id.clone(), payload));
+
+                self.content.clear();
+                self.open = false;
+            },
+        }
+
+        true
+    }
+
+    fn change(&mut self, _props: Self::Properties) -> ShouldRender {
+        false
+    }
+
+    fn view(&self) -> Html {
+        let toggle_cb = self.link.callback(|_| AnnouncerEvents::Toggle);
+
+        let form = if self.open {
+            let content_cb = self.link.callback(|e: InputData| AnnouncerEvents::Content(e.value));
+            let colour_cb = self.link.callback(|e: ChangeData| {
+                if let ChangeData::Select(select) = e {
+                    AnnouncerEvents::Colour(Some(select.value()).filter(|c| !c.is_empty()))
+                } else {
+                    AnnouncerEvents::Colour(None)
+                }
+            });
+            let overlay_cb = self.link.callback(|e: ChangeData| {
+                if let ChangeData::Select(select) = e {
+                    AnnouncerEvents::OverlaySecs(select.value().parse().unwrap_or(0))
+                } else {
+                    AnnouncerEvents::OverlaySecs(0)
+                }
+            });
+            let send_cb = self.link.callback(|_| AnnouncerEvents::Send);
+
+            let colours = COLOUR_OPTIONS.iter().map(|(name, colour)| {
+                let selected = self.colour.as_deref() == Some(*colour);
+                html! { <option value=*colour selected=selected>{ name }</option> }
+            });
+
+            let current = self.overlay_secs;
+            let overlays = OVERLAY_OPTIONS.iter().map(|secs| {
+                let label = if *secs == 0 {
+                    "Chat only".to_string()
+                } else {
+                    format!("Over the video for {}s", secs)
+                };
+
+                html! { <option value=secs.to_string() selected=*secs == current>{ label }</option> }
+            });
+
+            html! {
+                <div class="absolute right-0 mt-2 w-72 bg-gray-800 border-2 border-gray-700 rounded-lg p-4 z-10">
+                    <h1 class="text-white font-bold mb-2">{ "Make an announcement" }</h1>
+                    <textarea oninput=content_cb value=self.content.clone()
+                        class="bg-gray-900 text-white rounded-lg focus:outline-none w-full px-2 mb-2" />
+                    <select onchange=colour_cb class="bg-gray-900 text-white rounded-lg focus:outline-none w-full px-2 mb-2">
+                        <option value="" selected=self.colour.is_none()>{ "Room accent" }</option>
+                        { for colours }
+                    </select>
+                    <select onchange=overlay_cb class="bg-gray-900 text-white rounded-lg focus:outline-none w-full px-2 mb-2">
+                        { for overlays }
+                    </select>
+                    <div class="flex justify-end">
+                        <button onclick=send_cb class="bg-accent text-white font-semibold rounded-lg px-4 py-1 focus:outline-none">
+                            { "Announce" }
+                        </button>
+                    </div>
+                </div>
+            }
+        } else {
+            html!{}
+        };
+
+        html! {
+            <div class="relative flex items-center mx-2">
+                <button onclick=toggle_cb class="text-white text-lg focus:outline-none" title="Make an announcement">
+                    { "📣" }
+                </button>
+                { form }
+            </div>
+        }
+    }
+}
@@ -0,0 +1,258 @@
+use yew::prelude::*;
+use yew::services::{ConsoleService, TimeoutService};
+use yew::services::timeout::TimeoutTask;
+
+use serde::Deserialize;
+use serde_json::json;
+use std::time::Duration;
+
+use crate::binder;
+use crate::opcodes;
+use crate::settings;
+use crate::utils::send_future;
+use crate::websocket::{WsHandler, WebsocketMessage};
+
+
+/// The longest the user is locked out for after failed attempts.
+const MAX_LOCKOUT_SECS: u64 = 60;
+
+
+#[derive(Properties, Clone)]
+pub struct JoinCodeProperties {
+    /// The room websocket handle.
+    pub ws: WsHandler,
+}
+
+
+/// The challenge sent by the gateway when the room is password protected,
+/// no state is delivered until it has been answered correctly.
+#[derive(Deserialize)]
+struct AuthChallenge {
+    /// The nonce the join code is hashed with.
+    nonce: String,
+}
+
+
+/// The events that can be invoked by callbacks for the JoinCodePrompt.
+pub enum JoinCodeEvents {
+    /// The gateway has requested a join code.
+    Challenge(WebsocketMessage),
+
+    /// The gateway has accepted the join code.
+    Accepted,
+
+    /// The join code input has changed.
+    Input(String),
+
+    /// The join button has been pressed.
+    Submit,
+
+    /// The join code has been hashed with the nonce.
+    Hashed(Option<String>),
+
+    /// The lockout from failed attempts has expired.
+    Unlock,
+}
+
+
+/// Prompts the user for the room's join code when the gateway challenges
+/// the connection with OP_AUTH_REQUIRED.
+///
+/// The code is never sent in plain text, it is hashed with the challenge
+/// nonce and sent directly to the gateway. Each rejected attempt locks the
+/// input for an exponentially growing amount of time.
+pub struct JoinCodePrompt {
+    link: ComponentLink<Self>,
+    ws: WsHandler,
+
+    challenge: Option<AuthChallenge>,
+    code: String,
+
+    /// If a code has been sent and we are waiting on the gateway.
+    pending: bool,
+
+    /// The amount of rejected attempts.
+    failures: u32,
+
+    /// The unlock task while locked out.
+    lockout: Option<TimeoutTask>,
+}
+
+impl JoinCodePrompt {
+    /// The amount of seconds to lock the input for after the given amount
+    /// of failures.
+    fn lockout_secs(failures: u32) -> u64 {
+        2u64.saturating_pow(failures).min(MAX_LOCKOUT_SECS)
+    }
+}
+
+impl Component for JoinCodePrompt {
+    type Message = JoinCodeEvents;
+    type Properties = JoinCodeProperties;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let challenge_cb = link.callback(JoinCodeEvents::Challenge);
+        let accepted_cb = link.callback(|_| JoinCodeEvents::Accepted);
+
+        let ws = props.ws;
+        ws.subscribe_to_message(settings::AUTH_ID, opcodes::OP_AUTH_REQUIRED, challenge_cb);
+        ws.subscribe_to_message(settings::AUTH_ID, opcodes::OP_AUTH_ACCEPTED, accepted_cb);
+
+        Self {
+            link,
+            ws,
+            challenge: None,
+            code: String::new(),
+            pending: false,
+            failures: 0,
+            lockout: None,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            JoinCodeEvents::Challenge(msg) => {
+                let challenge: Option<AuthChallenge> = msg.unwrap_and_into();
+                if challenge.is_none() {
+                    ConsoleService::warn("Received auth challenge without a nonce");
+                    return false;
+                }
+
+                // A new challenge while waiting on a response means the
+                // last code was rejected.
+                if self.pending {
+                    self.pending = false;
+                    self.failures += 1;
+
+                    let secs = Self::lockout_secs(self.failures);
+                    let task = TimeoutService::spawn(
+                        Duration::from_secs(secs),
+                        self.link.callback(|_| JoinCodeEvents::Unlock),
+                    );
+                    self.lockout = Some(task);
+                }
+
+                self.code.clear();
+                self.challenge = challenge;
+            },
+            JoinCodeEvents::Accepted => {
+                self.challenge = None;
+                self.pending = false;
+                self.failures = 0;
+                self.lockout = None;
+            },
+            JoinCodeEvents::Input(code) => {
+                self.code = code;
+            },
+            JoinCodeEvents::Submit => {
+                if self.pending || self.lockout.is_some() || self.code.is_empty() {
+                    return false;
+                }
+
+                let nonce = match self.challenge.as_ref() {
+                    Some(challenge) => challenge.nonce.clone(),
+                    None => return false,
+                };
+
+                let code = self.code.clone();
+                send_future(self.link.clone(), async move {
+                    let hash = binder::hash_join_code(nonce, code).await;
+                    JoinCodeEvents::Hashed(hash.as_string())
+                });
+
+                self.pending = true;
+            },
+            JoinCodeEvents::Hashed(hash) => {
+                let hash = match hash {
+                    Some(hash) => hash,
+                    None => {
+                        ConsoleService::error("Failed to hash join code");
+                        self.pending = false;
+                        return true;
+                    }
+                };
+
+                let payload = json!({ "hash": hash });
+                if !self.ws.send(opcodes::OP_AUTH, Some(payload)) {
+                    ConsoleService::warn("Failed to send join code, socket is not open");
+                    self.pending = false;
+                }
+            },
+            JoinCodeEvents::Unlock => {
+                self.lockout = None;
+            },
+        }
+
+        true
+    }
+
+    fn change(&mut self, _props: Self::Properties) -> ShouldRender {
+        false
+    }
+
+    fn view(&self) -> Html {
+        if self.challenge.is_none() {
+            return html!{};
+        }
+
+        let input_cb = self.link.callback(
+            |e: InputData| JoinCodeEvents::Input(e.value)
+        );
+        let keypress_cb = self.link.batch_callback(|e: KeyboardEvent| {
+            if e.key() == "Enter" {
+                vec![JoinCodeEvents::Submit]
+            } else {
+                vec![]
+            }
+        });
+        let submit_cb = self.link.callback(|_| JoinCodeEvents::Submit);
+
+        let disabled = self.pending || self.lockout.is_some();
+
+        let notice = if self.lockout.is_some() {
+            let msg = format!(
+                "Incorrect join code, you can try again in {} seconds.",
+                Self::lockout_secs(self.failures),
+            );
+
+            html! {
+                <p class="text-red-400 text-sm mt-2">{ msg }</p>
+            }
+        } else {
+            html!{}
+        };
+
+        html! {
+            <div class="fixed inset-0 flex justify-center items-center bg-black bg-opacity-75">
+                <div class="bg-discord-dark rounded-lg p-8 w-1/3">
+                    <h1 class="text-white font-bold text-2xl mb-4">
+                        { "This room is password protected" }
+                    </h1>
+                    <input
+                        class="\
+                            transition duration-300 linear \
+                            border-2 border-blue-800 focus:border-blue-600 \
+                            text-white text-sm font-medium placeholder-gray-200 \
+                            rounded-lg focus:outline-none \
+                            bg-gray-800 w-full h-10 px-5"
+                        oninput=input_cb
+                        onkeypress=keypress_cb
+                        value=&self.code
+                        disabled=disabled
+                        placeholder="Enter the join code..."
+                        type="password"
+                    />
+                    { notice }
+                    <div class="flex justify-end mt-4">
+                        <button
+                            onclick=submit_cb
+                            disabled=disabled
+                            class="bg-blue-600 text-white font-semibold rounded-lg px-4 py-2 focus:outline-none disabled:opacity-50">
+                            { "Join" }
+                        </button>
+                    </div>
+                </div>
+            </div>
+        }
+    }
+}
\ No newline at end of file
@@ -1,11 +1,417 @@
 use wasm_bindgen::prelude::*;
+use yew::web_sys::Element;
 
 // wasm-bindgen will automatically take care of including this script
 #[wasm_bindgen(module = "/src/js/player.js")]
 extern "C" {
-    #[wasm_bindgen(js_name = "setPlayerListeners")]
-    pub fn set_listeners(on_error: &Closure<dyn FnMut()>, on_meta: &Closure<dyn FnMut()>) -> bool;
+    /// Fades the player's volume out over the given duration before
+    /// pausing it, a duration of 0 pauses immediately.
+    #[wasm_bindgen(js_name = "fadeOutVideo")]
+    pub fn fade_out_video(element: &Element, duration_ms: u32);
 
-    #[wasm_bindgen(js_name = "tryReloadVideo")]
-    pub fn try_reload();
+    #[wasm_bindgen(js_name = "isVideoPlaying")]
+    pub fn is_video_playing(element: &Element) -> bool;
+
+    #[wasm_bindgen(js_name = "setVideoMuted")]
+    pub fn set_video_muted(element: &Element, muted: bool);
+
+    /// The player's state as a json string, None if the player has not
+    /// been created yet.
+    #[wasm_bindgen(js_name = "getPlayerState")]
+    pub fn get_player_state(element: &Element) -> Option<String>;
+
+    #[wasm_bindgen(js_name = "playerTogglePlay")]
+    pub fn player_toggle_play(element: &Element);
+
+    #[wasm_bindgen(js_name = "playerSeek")]
+    pub fn player_seek(element: &Element, position: f64);
+
+    /// Sets the volume from 0 to 1, muting the player at 0.
+    #[wasm_bindgen(js_name = "playerSetVolume")]
+    pub fn player_set_volume(element: &Element, volume: f64);
+
+    /// Sets the playback rate of the player, 1 being normal speed.
+    #[wasm_bindgen(js_name = "playerSetRate")]
+    pub fn set_rate(element: &Element, rate: f64);
+
+    #[wasm_bindgen(js_name = "playerSetPaused")]
+    pub fn player_set_paused(element: &Element, paused: bool);
+
+    /// Toggles the container holding the player fullscreen, falling back
+    /// to the player itself if the container can't be.
+    #[wasm_bindgen(js_name = "playerToggleFullscreen")]
+    pub fn player_toggle_fullscreen(container: Option<Element>, element: &Element);
+}
+
+
+#[wasm_bindgen(module = "/src/js/activity.js")]
+extern "C" {
+    /// The milliseconds since the user last moved the mouse, pressed a key
+    /// or otherwise interacted with the page.
+    #[wasm_bindgen(js_name = "msSinceActivity")]
+    pub fn ms_since_activity() -> f64;
+
+    /// Calls the closure with the page's visibility whenever the user
+    /// switches to or away from the tab.
+    #[wasm_bindgen(js_name = "setVisibilityListener")]
+    pub fn set_visibility_listener(on_change: &Closure<dyn FnMut(bool)>);
+
+    #[wasm_bindgen(js_name = "isPageVisible")]
+    pub fn is_page_visible() -> bool;
+
+    /// Fades out everything marked `auto-hide` inside the element while
+    /// it is fullscreen and the mouse hasn't moved for the delay, showing
+    /// it again on any movement or key press. Returns false if the element
+    /// is already set up.
+    #[wasm_bindgen(js_name = "setAutoHide")]
+    pub fn set_auto_hide(element: &Element, delay_ms: u32) -> bool;
+}
+
+#[wasm_bindgen(module = "/src/js/ambient.js")]
+extern "C" {
+    /// Sets the blurred canvas behind the player that ambient mode draws
+    /// on, stopping any sampling onto the previous canvas.
+    #[wasm_bindgen(js_name = "attachAmbientCanvas")]
+    pub fn attach_ambient_canvas(canvas: &Element);
+
+    /// Starts or stops sampling the video onto the ambient canvas every
+    /// interval, sampling pauses while the tab is hidden. Returns false if
+    /// no canvas is attached.
+    #[wasm_bindgen(js_name = "setAmbientMode")]
+    pub fn set_ambient_mode(element: &Element, enabled: bool, interval_ms: u32) -> bool;
+}
+
+#[wasm_bindgen(module = "/src/js/audio.js")]
+extern "C" {
+    /// Delays the player's audio relative to its video by routing it
+    /// through Web Audio, fixing lip sync on badly remuxed streams.
+    /// Returns false if the player or Web Audio is unavailable.
+    #[wasm_bindgen(js_name = "setAudioDelay")]
+    pub fn set_audio_delay(element: &Element, delay_ms: u32) -> bool;
+
+    /// Amplifies the player's audio past its element volume through the
+    /// same Web Audio graph, 1 leaves it untouched and 2 is the most.
+    /// Returns false if the player or Web Audio is unavailable.
+    #[wasm_bindgen(js_name = "setAudioGain")]
+    pub fn set_audio_gain(element: &Element, gain: f64) -> bool;
+
+    /// Sets the measured loudness of the current stream in LUFS, with
+    /// normalization on the stream is turned up or down towards a common
+    /// loudness on top of the volume. Returns false if the player or Web
+    /// Audio is unavailable.
+    #[wasm_bindgen(js_name = "setStreamLoudness")]
+    pub fn set_stream_loudness(element: &Element, lufs: Option<f64>, enabled: bool) -> bool;
+
+    /// Turns loudness normalization on or off for the current stream.
+    #[wasm_bindgen(js_name = "setLoudnessNormalization")]
+    pub fn set_loudness_normalization(element: &Element, enabled: bool) -> bool;
+}
+
+#[wasm_bindgen(module = "/src/js/auth.js")]
+extern "C" {
+    /// Hashes the join code with the challenge nonce, resolving to the
+    /// hex encoded SHA-256 digest.
+    #[wasm_bindgen(js_name = "hashJoinCode")]
+    pub async fn hash_join_code(nonce: String, code: String) -> JsValue;
+
+    /// 128 random bits from the browser's crypto source as 32 hex
+    /// characters.
+    #[wasm_bindgen(js_name = "randomId")]
+    pub fn random_id() -> String;
+}
+
+
+#[wasm_bindgen(module = "/src/js/speech.js")]
+extern "C" {
+    /// Reads the text aloud with the voice picked by the seed, briefly
+    /// ducking the player's volume while speaking if there is one.
+    /// Returns false if speech synthesis is not supported.
+    #[wasm_bindgen(js_name = "speakAs")]
+    pub fn speak_as(element: Option<Element>, voice_seed: u32, text: String, rate: f32) -> bool;
+}
+
+
+#[wasm_bindgen(module = "/src/js/sync.js")]
+extern "C" {
+    /// Registers the callbacks for local play, pause and seek interactions,
+    /// each is given the position in seconds. `on_ready` is called once a
+    /// player has been created and can be controlled and `on_buffering`
+    /// whenever playback stalls or recovers. The player is looked up
+    /// through `player_element` as it may not have been created yet.
+    #[wasm_bindgen(js_name = "setSyncListeners")]
+    pub fn set_sync_listeners(
+        player_element: &Closure<dyn FnMut() -> Option<Element>>,
+        on_play: &Closure<dyn FnMut(f64)>,
+        on_pause: &Closure<dyn FnMut(f64)>,
+        on_seek: &Closure<dyn FnMut(f64)>,
+        on_ready: &Closure<dyn FnMut()>,
+        on_buffering: &Closure<dyn FnMut(bool)>,
+    ) -> bool;
+
+    /// Applies a room event to the player without it being reported back
+    /// to the sync listeners, action is one of `play`, `pause` or `seek`.
+    #[wasm_bindgen(js_name = "applyRemote")]
+    pub fn apply_remote(element: &Element, action: &str, position: f64);
+
+    /// Seeks the player once its metadata has loaded without the seek
+    /// being reported to the sync listeners.
+    #[wasm_bindgen(js_name = "seekLocally")]
+    pub fn seek_locally(element: &Element, position: f64);
+
+    /// Sets how far ahead of the local clock the server's clock is in
+    /// milliseconds, as measured by the time checks.
+    #[wasm_bindgen(js_name = "setClockOffset")]
+    pub fn set_clock_offset(offset_ms: f64);
+
+    /// The server's current time in milliseconds since the epoch.
+    #[wasm_bindgen(js_name = "serverNow")]
+    pub fn server_now() -> f64;
+}
+
+
+#[wasm_bindgen(module = "/src/js/cast.js")]
+extern "C" {
+    /// Watches the player's video element for remote playback devices,
+    /// reporting `unavailable`, `available`, `connecting` or `connected`.
+    /// Returns false if the player or the Remote Playback api is missing.
+    #[wasm_bindgen(js_name = "watchRemotePlayback")]
+    pub fn watch_remote_playback(element: &Element, on_state: &Closure<dyn FnMut(String)>) -> bool;
+
+    /// Opens the browser's device picker for casting the video.
+    #[wasm_bindgen(js_name = "promptRemotePlayback")]
+    pub fn prompt_remote_playback(element: &Element);
+}
+
+
+#[wasm_bindgen(module = "/src/js/effects.js")]
+extern "C" {
+    /// Starts a particle effect on the given canvas, stopping any running
+    /// effect. Returns false if the user prefers reduced motion.
+    #[wasm_bindgen(js_name = "startEffect")]
+    pub fn start_effect(canvas: &Element, name: &str, low_power: bool) -> bool;
+
+    #[wasm_bindgen(js_name = "stopEffect")]
+    pub fn stop_effect();
+
+    /// Starts raining the given emoji over the canvas, stopping any running
+    /// effect. Returns false if no emoji were given or the user prefers
+    /// reduced motion.
+    #[wasm_bindgen(js_name = "startEmojiRain")]
+    pub fn start_emoji_rain(canvas: &Element, emojis: Box<[JsValue]>, low_power: bool) -> bool;
+
+    /// Plays a short rising chime.
+    #[wasm_bindgen(js_name = "playCelebrationSound")]
+    pub fn play_celebration_sound();
+}
+
+#[wasm_bindgen(module = "/src/js/thumbnails.js")]
+extern "C" {
+    /// Captures a thumbnail of the current frame if the interval bucket
+    /// it falls in has none yet. Returns false if the frame can't be read.
+    #[wasm_bindgen(js_name = "captureThumbnail")]
+    pub fn capture_thumbnail(element: &Element, interval_secs: f64) -> bool;
+
+    /// The element's captured thumbnail closest to the position as a data
+    /// url.
+    #[wasm_bindgen(js_name = "thumbnailAt")]
+    pub fn thumbnail_at(element: &Element, position: f64, interval_secs: f64) -> Option<String>;
+}
+
+#[wasm_bindgen(module = "/src/js/subtitles.js")]
+extern "C" {
+    /// Shows the subtitle track at the index in the slot, slot 0 is the
+    /// main line and 1 the second line. A negative index empties the slot.
+    /// Returns false if there is no such track.
+    #[wasm_bindgen(js_name = "selectSubtitle")]
+    pub fn select_subtitle(element: &Element, slot: u32, index: i32) -> bool;
+
+    /// Delays the slot's subtitles by the offset, negative values show
+    /// them early.
+    #[wasm_bindgen(js_name = "setSubtitleOffset")]
+    pub fn set_subtitle_offset(element: &Element, slot: u32, offset_secs: f64);
+
+    /// The text of the slot's cues at the current position, empty if none.
+    #[wasm_bindgen(js_name = "activeCueText")]
+    pub fn active_cue_text(element: &Element, slot: u32) -> String;
+
+    /// Adds a WebVTT track from its text via a blob url, returning its
+    /// index or -1 if the player has not been created yet.
+    #[wasm_bindgen(js_name = "addSubtitleTrack")]
+    pub fn add_subtitle_track(element: &Element, label: &str, vtt: &str) -> i32;
+}
+
+#[wasm_bindgen(module = "/src/js/hls.js")]
+extern "C" {
+    /// Creates the video.js player on the element and starts loading the
+    /// stream, the MIME type picks between the HLS and DASH engines. An
+    /// existing player is switched to the new stream. `on_error` is given
+    /// the media error code, message and the http status of the failed
+    /// request or 0. Returns false if video.js hasn't loaded.
+    #[wasm_bindgen(js_name = "createStreamPlayer")]
+    pub fn create_stream_player(
+        element: &Element,
+        src: &str,
+        mime_type: &str,
+        on_error: &Closure<dyn FnMut(u32, String, u32)>,
+    ) -> bool;
+
+    /// Seeks to the position once the player's next source has loaded,
+    /// resuming playback if `play` is set.
+    #[wasm_bindgen(js_name = "resumeAt")]
+    pub fn resume_at(element: &Element, position: f64, play: bool);
+
+    /// The heights of the stream's renditions as a json array, highest
+    /// first. Empty until the stream's playlist has loaded.
+    #[wasm_bindgen(js_name = "getRenditions")]
+    pub fn get_renditions(element: &Element) -> String;
+
+    /// Limits playback to renditions of the given height, 0 lets the
+    /// player pick automatically. The choice is kept across the streams
+    /// played by the element.
+    #[wasm_bindgen(js_name = "setRendition")]
+    pub fn set_rendition(element: &Element, height: u32);
+
+    /// Plays only the lowest bandwidth rendition, audio only if the stream
+    /// has one, and shows the player's poster in place of the video.
+    #[wasm_bindgen(js_name = "setDataSaver")]
+    pub fn set_data_saver(element: &Element, enabled: bool);
+
+    /// If the browser asks for reduced data usage via the Network
+    /// Information api.
+    #[wasm_bindgen(js_name = "prefersSaveData")]
+    pub fn prefers_save_data() -> bool;
+
+    /// The player's resolution, dropped frames, bitrates, buffer and ready
+    /// state as a json string, None if the player has not been created.
+    #[wasm_bindgen(js_name = "getPlaybackStats")]
+    pub fn get_playback_stats(element: &Element) -> Option<String>;
+
+    /// Disposes of the player, removing its element from the page.
+    #[wasm_bindgen(js_name = "destroyStreamPlayer")]
+    pub fn destroy_stream_player(element: &Element);
+
+    /// Creates a grid mode player on the element, muted and without the
+    /// main player's rendition, data saver and error handling. Returns
+    /// false if video.js hasn't loaded.
+    #[wasm_bindgen(js_name = "createSecondaryPlayer")]
+    pub fn create_secondary_player(element: &Element, src: &str, mime_type: &str) -> bool;
+}
+
+#[wasm_bindgen(module = "/src/js/support.js")]
+extern "C" {
+    /// The page's most recent console output as a json array, the shim
+    /// keeps a ring buffer of it from the moment it loads.
+    #[wasm_bindgen(js_name = "recentLogs")]
+    pub fn recent_logs() -> String;
+
+    /// The browser, page and player state as a json object.
+    #[wasm_bindgen(js_name = "diagnosticSummary")]
+    pub fn diagnostic_summary(element: Option<Element>) -> String;
+
+    /// The player's current frame as a jpeg data url, None if the frame
+    /// can't be read.
+    #[wasm_bindgen(js_name = "captureFrame")]
+    pub fn capture_frame(element: &Element) -> Option<String>;
+}
+
+#[wasm_bindgen(module = "/src/js/embed.js")]
+extern "C" {
+    /// Starts the postMessage bridge between the embed and its host page,
+    /// posting to and accepting messages from the given origin only. The
+    /// player is looked up through `player_element` as it's only created
+    /// once the stream starts.
+    #[wasm_bindgen(js_name = "startEmbedBridge")]
+    pub fn start_embed_bridge(
+        target_origin: &str,
+        player_element: &Closure<dyn FnMut() -> Option<Element>>,
+    );
+}
+
+#[wasm_bindgen(module = "/src/js/perf.js")]
+extern "C" {
+    /// Marks the start of a User Timing measure.
+    #[wasm_bindgen(js_name = "perfStart")]
+    pub fn perf_start(name: &str);
+
+    /// Measures from the matching start mark and adds the duration to
+    /// the measure's running summary.
+    #[wasm_bindgen(js_name = "perfEnd")]
+    pub fn perf_end(name: &str);
+
+    /// Starts or stops recording the time between animation frames as the
+    /// `frame` measure.
+    #[wasm_bindgen(js_name = "setFrameSampling")]
+    pub fn set_frame_sampling(enabled: bool);
+
+    /// The count, average and max duration of every measure as a json
+    /// array.
+    #[wasm_bindgen(js_name = "perfSummary")]
+    pub fn perf_summary() -> String;
+
+    /// Milliseconds since the page loaded, with sub-millisecond precision.
+    #[wasm_bindgen(js_name = "perfNow")]
+    pub fn perf_now() -> f64;
+}
+
+#[wasm_bindgen(module = "/src/js/screenshot.js")]
+extern "C" {
+    /// Saves the current video frame as a png with the given file name.
+    /// Returns false if the frame can't be read.
+    #[wasm_bindgen(js_name = "downloadFrame")]
+    pub fn download_frame(element: &Element, filename: &str) -> bool;
+
+    /// Copies the current video frame to the clipboard as a png, resolving
+    /// to false if the frame can't be read or the clipboard is unavailable.
+    #[wasm_bindgen(js_name = "copyFrame")]
+    pub async fn copy_frame(element: &Element) -> JsValue;
+}
+
+#[wasm_bindgen(module = "/src/js/share.js")]
+extern "C" {
+    /// Copies the text to the clipboard, resolving to false if the
+    /// clipboard is unavailable or refused.
+    #[wasm_bindgen(js_name = "copyText")]
+    pub async fn copy_text(text: String) -> JsValue;
+}
+
+#[wasm_bindgen(module = "/src/js/transform.js")]
+extern "C" {
+    /// Applies CSS transforms to the player's video. `fit` is the css
+    /// object-fit, `aspect` the width over height ratio to stretch the
+    /// video to or 0 to keep its own, `rotation` is in degrees and `zoom`
+    /// scales the video up to crop its edges.
+    #[wasm_bindgen(js_name = "applyVideoTransform")]
+    pub fn apply_video_transform(element: &Element, fit: &str, aspect: f64, rotation: u32, zoom: f64);
+}
+
+
+#[wasm_bindgen(module = "/src/js/translate.js")]
+extern "C" {
+    /// The user's preferred language as a two letter code e.g. `en`.
+    #[wasm_bindgen(js_name = "browserLanguage")]
+    pub fn browser_language() -> String;
+}
+
+#[wasm_bindgen(module = "/src/js/miniplayer.js")]
+extern "C" {
+    /// Docks the player in the corner of the page while it is playing and
+    /// its container is scrolled out of view, the callback is told when it
+    /// docks or returns. The player is looked up through `player_element`
+    /// as it's only created once the stream starts. Returns false if the
+    /// browser can't watch scrolling.
+    #[wasm_bindgen(js_name = "watchMiniPlayer")]
+    pub fn watch_mini_player(
+        container: &Element,
+        player_element: &Closure<dyn FnMut() -> Option<Element>>,
+        on_change: &Closure<dyn FnMut(bool)>,
+    ) -> bool;
+
+    /// Returns the player to the page, scrolling back to it or leaving it
+    /// undocked until the user scrolls back themselves.
+    #[wasm_bindgen(js_name = "undockMiniPlayer")]
+    pub fn undock_mini_player(scroll_back: bool);
+
+    /// Returns the player to the page and stops watching the scroll.
+    #[wasm_bindgen(js_name = "stopMiniPlayer")]
+    pub fn stop_mini_player();
 }
\ No newline at end of file
@@ -0,0 +1,140 @@
+use yew::prelude::*;
+use yew::services::IntervalService;
+use yew::services::interval::IntervalTask;
+
+use wasm_bindgen::prelude::*;
+use std::time::Duration;
+
+use crate::video::VideoElementHandle;
+
+
+/// How often the player is checked for a new video element to watch.
+const WATCH_INTERVAL_MS: u64 = 1000;
+
+
+/// Where playback is with a cast device.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CastState {
+    /// No device can play the stream, the button is hidden.
+    Unavailable,
+
+    /// A device is around but not playing.
+    Available,
+
+    /// The device is loading the stream.
+    Connecting,
+
+    /// The stream is playing on the device.
+    Connected,
+}
+
+impl CastState {
+    fn from_js(state: &str) -> Self {
+        match state {
+            "available" => CastState::Available,
+            "connecting" => CastState::Connecting,
+            "connected" => CastState::Connected,
+            _ => CastState::Unavailable,
+        }
+    }
+}
+
+
+#[derive(Properties, Clone)]
+pub struct CastButtonProperties {
+    /// The player handed to the device.
+    pub video: VideoElementHandle,
+}
+
+
+/// The events that can be invoked by callbacks for the CastButton.
+pub enum CastButtonEvents {
+    /// Watch the player's current video element.
+    Watch,
+
+    /// The cast state has changed.
+    State(CastState),
+
+    /// The button has been pressed.
+    Prompt,
+}
+
+
+/// The cast button of the control bar, hands the stream to a Chromecast
+/// or other remote playback device picked by the browser.
+///
+/// The video element stays in control of the device, so the playback
+/// sync keeps reading from and applying room events to it as usual.
+pub struct CastButton {
+    link: ComponentLink<Self>,
+    video: VideoElementHandle,
+    _watch: IntervalTask,
+    js_state: Closure<dyn FnMut(String)>,
+    state: CastState,
+}
+
+impl Component for CastButton {
+    type Message = CastButtonEvents;
+    type Properties = CastButtonProperties;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let watch = IntervalService::spawn(
+            Duration::from_millis(WATCH_INTERVAL_MS),
+            link.callback(|_| CastButtonEvents::Watch),
+        );
+
+        let js_state = {
+            let cb = link.callback(|state: String| CastButtonEvents::State(CastState::from_js(&state)));
+            Closure::wrap(Box::new(move |state: String| cb.emit(state)) as Box<dyn FnMut(String)>)
+        };
+
+        Self {
+            link,
+            video: props.video,
+            _watch: watch,
+            js_state,
+            state: CastState::Unavailable,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            CastButtonEvents::Watch => {
+                self.video.watch_remote_playback(&self.js_state);
+                false
+            },
+            CastButtonEvents::State(state) => {
+                let changed = self.state != state;
+                self.state = state;
+                changed
+            },
+            CastButtonEvents::Prompt => {
+                self.video.prompt_remote_playback();
+                false
+            },
+        }
+    }
+
+    fn change(&mut self, _props: Self::Properties) -> ShouldRender {
+        false
+    }
+
+    fn view(&self) -> Html {
+        let (colour, title) = match self.state {
+            CastState::Unavailable => return html!{},
+            CastState::Available => ("text-white", "Cast to a device"),
+            CastState::Connecting => ("text-yellow-400", "Connecting to the device..."),
+            CastState::Connected => ("text-accent", "Casting, click to stop or switch device"),
+        };
+
+        let prompt_cb = self.link.callback(|_| CastButtonEvents::Prompt);
+
+        html! {
+            <button onclick=prompt_cb class=("w-6 h-6 mr-4 focus:outline-none", colour) title=title>
+                <svg xmlns="http://www.w3.org/2000/svg" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                    <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M3 17a4 4 0 014 4M3 13a8 8 0 018 8M3 9V6a2 2 0 012-2h14a2 2 0 012 2v12a2 2 0 01-2 2h-5" />
+                </svg>
+            </button>
+        }
+    }
+}
@@ -1,12 +1,28 @@
+use wasm_bindgen::prelude::*;
 use yew::prelude::*;
-use yew::services::ConsoleService;
+use yew::services::{ConsoleService, IntervalService};
+use yew::services::interval::IntervalTask;
+use yew::web_sys::HtmlInputElement;
 
 use reqwest::Client;
 use serde::{Serialize, Deserialize};
-
+use serde_json::json;
+use rustc_hash::FxHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+use std::time::Duration;
+
+use crate::announce::Announcement;
+use crate::binder;
+use crate::flood::{FloodDetector, FloodState};
 use crate::opcodes;
+use crate::perf::PerfSpan;
+use crate::prefs::Preferences;
 use crate::settings;
-use crate::utils::{send_future, start_future, emit_event};
+use crate::translate::{self, Translation};
+use crate::utils::{send_future, start_future, emit_event, load_local, store_local, remove_local, random_id};
+use crate::video::VideoElementHandle;
+use crate::webhook;
 use crate::websocket::{WsHandler, WebsocketMessage, WrappingWsMessage};
 
 
@@ -18,6 +34,63 @@ pub struct ChatRoomProperties {
 
     /// The room id.
     pub room_id: String,
+
+    /// The room's player, ducked while messages are read aloud.
+    pub video: VideoElementHandle,
+}
+
+
+/// The events that can be invoked by callbacks for the ChatRoom.
+pub enum ChatRoomEvents {
+    /// A message from the websocket.
+    Ws(WebsocketMessage),
+
+    /// The room config has been resolved.
+    Config(RoomConfig),
+
+    /// A member has edited one of their messages.
+    Edit(WebsocketMessage),
+
+    /// A member has deleted one of their messages.
+    Delete(WebsocketMessage),
+
+    /// The user has switched to or away from the tab.
+    Visibility(bool),
+
+    /// The host has made an announcement.
+    Announce(WebsocketMessage),
+
+    /// The pinned announcement has been dismissed.
+    DismissAnnouncement,
+
+    /// A member has changed their Discord name or avatar.
+    ProfileUpdated(WebsocketMessage),
+
+    /// Refresh relative timestamps.
+    Tick,
+
+    /// The user asked for the message with the id to be translated.
+    Translate(String),
+
+    /// A translation has come back, None if it failed.
+    Translated(String, Option<Translation>),
+
+    /// The user has shown or hidden the original of a translated message.
+    ToggleOriginal(String),
+}
+
+
+/// How often relative timestamps are refreshed.
+const TIMESTAMP_REFRESH_SECS: u64 = 30;
+
+/// The name shown for messages without a trusted author.
+const UNVERIFIED_NAME: &str = "Unknown member";
+
+
+/// The local storage key the id of the last message seen in the room is
+/// kept under.
+fn last_seen_key(room_id: &str) -> String {
+    format!("spooderfy.last_seen.{}", room_id)
 }
 
 
@@ -26,30 +99,248 @@ pub struct ChatRoomProperties {
 /// The room subscribes to the MESSAGE event from the websocket and
 /// appends the message to the list on a event, this list is never
 /// cleared.
+///
+/// The text input is only rendered once the room config is resolved as
+/// it decides how messages are delivered.
+///
+/// Repeated messages and messages from a user flooding the chat are
+/// collapsed into the previous message rather than rendered individually.
+///
+/// Messages that arrive while the tab is in the background are marked
+/// with a divider so the user can see where they left off.
 pub struct ChatRoom {
-   _ws: WsHandler,
+    link: ComponentLink<Self>,
+    ws: WsHandler,
     room_id: String,
+    video: VideoElementHandle,
     messages: Vec<Message>,
+    config: Option<RoomConfig>,
+    flood: FloodDetector,
+
+    /// The id of the last message the user has seen.
+    last_seen: Option<String>,
+
+    /// The index of the first unseen message, the divider is rendered
+    /// above it.
+    divider: Option<usize>,
+
+    visible: bool,
+
+    /// The ids of every message received, including ones collapsed into
+    /// another.
+    seen_ids: HashSet<String>,
+
+    /// The latest announcement from the host, pinned above the messages.
+    announcement: Option<Announcement>,
+
+    /// The latest profile change, passed on so the text input can pick
+    /// up the user's own.
+    profile_update: Option<ProfileUpdate>,
+
+    _timestamp_refresh: IntervalTask,
+    _js_visibility: Closure<dyn FnMut(bool)>,
 }
 
 impl ChatRoom {
     /// A simple callback that is invoked when a message is received via the
     /// websocket, the view is always re-rendered after this operation.
     pub fn on_message(&mut self, message: Message) {
+        // History replayed after a reconnect repeats what is already shown.
+        if let Some(id) = message.id.as_ref() {
+            if !self.seen_ids.insert(id.clone()) {
+                return;
+            }
+        }
+
+        // Replayed messages older than the latest are merged into place
+        // rather than appended, they have been seen or missed already so
+        // they skip the flood check and being read aloud.
+        if let Some(sent_at) = message.sent_at {
+            let position = self.messages
+                .iter()
+                .rposition(|m| m.sent_at.map_or(true, |t| t <= sent_at))
+                .map_or(0, |i| i + 1);
+
+            if position < self.messages.len() {
+                self.messages.insert(position, message);
+                if let Some(divider) = self.divider.as_mut() {
+                    if position < *divider {
+                        *divider += 1;
+                    }
+                }
+                return;
+            }
+        }
+
+        // Messages without a trusted author are never grouped or tracked
+        // as their name could be anyone's.
+        let signed_authors = self.signed_authors();
+        let username = message.identity(signed_authors).map(|(username, _)| username.to_string());
+
+        if let Some(username) = username {
+            let state = self.flood.record(&username, js_sys::Date::now());
+            if state == FloodState::Started {
+                let payload = json!({ "username": username });
+                self.ws.send(opcodes::OP_FLOOD_REPORT, Some(payload));
+            }
+
+            if let Some(last) = self.messages.last_mut() {
+                let same_user = last.identity(signed_authors).map(|(name, _)| name) == Some(username.as_str());
+                let similar = state != FloodState::Normal || last.content == message.content;
+
+                if same_user && similar {
+                    last.similar += 1;
+                    return;
+                }
+            }
+        }
+
+        let prefs = Preferences::load();
+        if prefs.tts_enabled {
+            message.speak(&self.video, prefs.tts_rate, signed_authors);
+        }
+
+        let seen_before = message.id.is_some() && message.id == self.last_seen;
+        if !self.visible && self.divider.is_none() {
+            self.divider = Some(self.messages.len());
+        }
+
+        let translate_id = message.id.clone().filter(|_| prefs.auto_translate);
         self.messages.push(message);
+
+        if let Some(id) = translate_id {
+            self.translate(id);
+        }
+
+        if seen_before {
+            self.divider = Some(self.messages.len());
+        } else if self.visible {
+            self.mark_seen();
+        }
+    }
+
+    /// If the room signs authors, until the config resolves only gateway
+    /// signed authors are trusted.
+    fn signed_authors(&self) -> bool {
+        self.config
+            .as_ref()
+            .map(|c| c.signed_authors)
+            .unwrap_or(true)
+    }
+
+    /// The message with the given id, messages from older clients without
+    /// an id can't be targeted.
+    fn message_mut(&mut self, id: &str) -> Option<&mut Message> {
+        self.messages.iter_mut().find(|m| m.id.as_deref() == Some(id))
+    }
+
+    /// Starts translating the message with the given id unless it already
+    /// is or has been.
+    fn translate(&mut self, id: String) {
+        let text = match self.message_mut(&id) {
+            Some(message) if message.translating || message.translation.is_some() => return,
+            Some(message) => {
+                message.translating = true;
+                message.content.clone()
+            },
+            None => return,
+        };
+
+        let room_id = self.room_id.clone();
+        send_future(self.link.clone(), async move {
+            let translation = translate::translate(room_id, text).await;
+            ChatRoomEvents::Translated(id, translation)
+        });
+    }
+
+    /// Marks the latest message as seen, persisting it and publishing it
+    /// to the gateway if the room asks for read state.
+    fn mark_seen(&mut self) {
+        let id = match self.messages.last().and_then(|m| m.id.clone()) {
+            Some(id) => id,
+            None => return,
+        };
+
+        if self.last_seen.as_ref() == Some(&id) {
+            return;
+        }
+
+        store_local(&last_seen_key(&self.room_id), &id);
+
+        let publish = self.config
+            .as_ref()
+            .map(|c| c.publish_read_state)
+            .unwrap_or(false);
+        if publish {
+            let payload = json!({ "message_id": id });
+            self.ws.send(opcodes::OP_READ_STATE, Some(payload));
+        }
+
+        self.last_seen = Some(id);
+    }
+
+    /// Applies an edit or deletion to the message it targets, edits that
+    /// don't come from the message's author are ignored.
+    ///
+    /// The editor is the one attached by the gateway, edits without one
+    /// can't be authorised and are dropped.
+    fn on_edit(&mut self, msg: WebsocketMessage, delete: bool) -> ShouldRender {
+        let edit: MessageEdit = match msg.unwrap_and_into() {
+            Some(edit) => edit,
+            None => return false,
+        };
+
+        let editor = match edit.editor.as_ref() {
+            Some(editor) => editor,
+            None => return false,
+        };
+
+        let signed_authors = self.signed_authors();
+        let position = self.messages.iter().position(|m| {
+            m.id.as_ref() == Some(&edit.id)
+                && m.identity(signed_authors).map(|(name, _)| name) == Some(editor.username.as_str())
+        });
+
+        let position = match position {
+            Some(position) => position,
+            None => return false,
+        };
+
+        if delete {
+            self.messages.remove(position);
+            if let Some(divider) = self.divider.as_mut() {
+                if position < *divider {
+                    *divider -= 1;
+                }
+            }
+        } else if let Some(content) = edit.content {
+            let message = &mut self.messages[position];
+            message.content = content;
+            message.edited = true;
+
+            // The old translation no longer matches the content.
+            message.translation = None;
+            message.hide_original = false;
+        }
+
+        true
     }
 }
 
 impl Component for ChatRoom {
-    type Message = WebsocketMessage;
+    type Message = ChatRoomEvents;
     type Properties = ChatRoomProperties;
 
     fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        send_future(
+            link.clone(),
+            fetch_room_config(props.room_id.clone())
+        );
 
         let messages = vec![];
 
         let ws = props.ws;
-        let ws_cb = link.callback(|msg| msg);
+        let ws_cb = link.callback(ChatRoomEvents::Ws);
 
         ws.subscribe_to_message(
             settings::CHAT_ID,
@@ -57,21 +348,112 @@ impl Component for ChatRoom {
             ws_cb
         );
 
+        let edit_cb = link.callback(ChatRoomEvents::Edit);
+        let delete_cb = link.callback(ChatRoomEvents::Delete);
+        ws.subscribe_to_message(settings::CHAT_ID, opcodes::OP_EDIT_MESSAGE, edit_cb);
+        ws.subscribe_to_message(settings::CHAT_ID, opcodes::OP_DELETE_MESSAGE, delete_cb);
+
+        let announce_cb = link.callback(ChatRoomEvents::Announce);
+        ws.subscribe_to_message(settings::CHAT_ID, opcodes::OP_ANNOUNCE, announce_cb);
+
+        let profile_cb = link.callback(ChatRoomEvents::ProfileUpdated);
+        ws.subscribe_to_message(settings::CHAT_ID, opcodes::OP_PROFILE_UPDATED, profile_cb);
+
+        let js_visibility = {
+            let cb = link.callback(ChatRoomEvents::Visibility);
+            Closure::wrap(Box::new(move |visible: bool| cb.emit(visible)) as Box<dyn FnMut(bool)>)
+        };
+        binder::set_visibility_listener(&js_visibility);
+
+        let timestamp_refresh = IntervalService::spawn(
+            Duration::from_secs(TIMESTAMP_REFRESH_SECS),
+            link.callback(|_| ChatRoomEvents::Tick),
+        );
+
         Self {
-            _ws: ws,
-            room_id: props.room_id,
+            link,
+            ws,
+            room_id: props.room_id.clone(),
+            video: props.video,
             messages,
+            config: None,
+            flood: FloodDetector::default(),
+            last_seen: load_local(&last_seen_key(&props.room_id)),
+            divider: None,
+            visible: binder::is_page_visible(),
+            seen_ids: HashSet::new(),
+            announcement: None,
+            profile_update: None,
+            _timestamp_refresh: timestamp_refresh,
+            _js_visibility: js_visibility,
         }
     }
 
     fn update(&mut self, msg: Self::Message) -> ShouldRender {
         let content = match msg {
-            WebsocketMessage::Empty => return false,
-            WebsocketMessage::Payload(value) => value,
+            ChatRoomEvents::Config(config) => {
+                self.config = Some(config);
+                return true;
+            },
+            ChatRoomEvents::Edit(msg) => return self.on_edit(msg, false),
+            ChatRoomEvents::Delete(msg) => return self.on_edit(msg, true),
+            ChatRoomEvents::Announce(msg) => {
+                self.announcement = msg.unwrap_and_into();
+                return true;
+            },
+            ChatRoomEvents::ProfileUpdated(msg) => {
+                let update: ProfileUpdate = match msg.unwrap_and_into() {
+                    Some(update) => update,
+                    None => return false,
+                };
+
+                let signed_authors = self.signed_authors();
+                for message in self.messages.iter_mut() {
+                    update.apply(message, signed_authors);
+                }
+                self.profile_update = Some(update);
+                return true;
+            },
+            ChatRoomEvents::Tick => return Preferences::load().relative_times,
+            ChatRoomEvents::Translate(id) => {
+                self.translate(id);
+                return true;
+            },
+            ChatRoomEvents::Translated(id, translation) => {
+                if let Some(message) = self.message_mut(&id) {
+                    message.translating = false;
+                    message.translation = translation;
+                }
+                return true;
+            },
+            ChatRoomEvents::ToggleOriginal(id) => {
+                if let Some(message) = self.message_mut(&id) {
+                    message.hide_original = !message.hide_original;
+                }
+                return true;
+            },
+            ChatRoomEvents::DismissAnnouncement => {
+                self.announcement = None;
+                return true;
+            },
+            ChatRoomEvents::Visibility(visible) => {
+                self.visible = visible;
+                if visible {
+                    self.mark_seen();
+                } else {
+                    self.divider = None;
+                }
+                return true;
+            },
+            ChatRoomEvents::Ws(WebsocketMessage::Empty) => return false,
+            ChatRoomEvents::Ws(WebsocketMessage::Payload(value)) => value,
         };
 
-        let msg: Message = serde_json::from_value(content)
-            .unwrap();
+        let mut msg: Message = match serde_json::from_value(content) {
+            Ok(msg) => msg,
+            Err(_) => return false,
+        };
+        msg.received_at = js_sys::Date::now();
 
         self.on_message(msg);
 
@@ -83,14 +465,53 @@ impl Component for ChatRoom {
     }
 
     fn view(&self) -> Html {
+        let _span = PerfSpan::start("chat.view");
+
+        let signed_authors = self.signed_authors();
+        let prefs = Preferences::load();
+
+        let announcement = match self.announcement.as_ref() {
+            Some(announcement) => {
+                let dismiss_cb = self.link.callback(|_| ChatRoomEvents::DismissAnnouncement);
+                announcement.banner_html(dismiss_cb)
+            },
+            None => html!{},
+        };
+
+        let input = if let Some(config) = self.config.as_ref() {
+            html! {
+                <TextInput
+                    room_id=self.room_id.clone()
+                    config=config.clone()
+                    profile_update=self.profile_update.clone()
+                />
+            }
+        } else {
+            html!{}
+        };
+
+        let new_divider = html! {
+            <div class="flex items-center my-2">
+                <div class="flex-grow border-t border-red-500"></div>
+                <span class="text-red-500 text-xs font-semibold mx-2">{ "new messages" }</span>
+                <div class="flex-grow border-t border-red-500"></div>
+            </div>
+        };
+
         html! {
             <div class="min-h-full w-1/3 p-4">
                 <div class="flex flex-col bg-discord-dark rounded-lg h-full p-4">
+                    { announcement }
                     <div class="h-full pt-1">
-                        { for self.messages.iter().map(|msg| {msg.to_html()}) }
+                        { for self.messages.iter().enumerate().map(|(i, msg)| html! {
+                            <>
+                                { if self.divider == Some(i) { new_divider.clone() } else { html!{} } }
+                                { msg.to_html(&self.link, signed_authors, &prefs) }
+                            </>
+                        }) }
                     </div>
                     <div class="self-end h-auto w-full">
-                        <TextInput room_id=self.room_id.clone()/>
+                        { input }
                     </div>
                 </div>
             </div>
@@ -104,6 +525,11 @@ impl Component for ChatRoom {
 /// to produce the P2P behaviour.
 #[derive(Serialize, Deserialize)]
 pub struct Message {
+    /// The id of the message as picked by the sender, used to target
+    /// edits and deletions. Older clients don't send one.
+    #[serde(default)]
+    id: Option<String>,
+
     /// The Discord user's display name e.g. Cf8
     username: String,
 
@@ -112,19 +538,202 @@ pub struct Message {
 
     /// The content of the message.
     content: String,
+
+    /// The author as attached and signed by the gateway, clients never
+    /// send this themselves and any client provided value is dropped
+    /// by the gateway.
+    #[serde(default, skip_serializing)]
+    author: Option<SignedAuthor>,
+
+    /// When the gateway received the message in server time ms, attached
+    /// by the gateway. Replayed history is merged in order by this.
+    #[serde(default, skip_serializing)]
+    sent_at: Option<f64>,
+
+    /// When the message was received in local time ms, this is local only.
+    #[serde(skip)]
+    received_at: f64,
+
+    /// The amount of similar messages collapsed into this one, this is
+    /// local only.
+    #[serde(skip)]
+    similar: u32,
+
+    /// If the message has been edited since it was sent, this is local only.
+    #[serde(skip)]
+    edited: bool,
+
+    /// The message translated into the user's language, this is local only.
+    #[serde(skip)]
+    translation: Option<Translation>,
+
+    #[serde(skip)]
+    translating: bool,
+
+    /// If only the translation is shown, this is local only.
+    #[serde(skip)]
+    hide_original: bool,
+}
+
+
+/// An edit or deletion of a message, deletions have no content.
+#[derive(Serialize, Deserialize)]
+struct MessageEdit {
+    /// The id of the message being edited.
+    id: String,
+
+    /// The editor as attached by the gateway from the sender's session,
+    /// it must match the author of the original message. Clients never
+    /// send this themselves.
+    #[serde(default, skip_serializing)]
+    editor: Option<SignedAuthor>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    content: Option<String>,
 }
 
 impl Message {
+    /// The username and avatar of the author, preferring the gateway
+    /// signed author over the self reported fields.
+    ///
+    /// If the room signs authors the self reported fields could be anyone,
+    /// so messages without a signed author have no identity.
+    fn identity(&self, signed_authors: bool) -> Option<(&str, &str)> {
+        match self.author.as_ref() {
+            Some(author) => Some((&author.username, &author.avatar)),
+            None if signed_authors => None,
+            None => Some((&self.username, &self.avatar)),
+        }
+    }
+
+    /// Reads the message aloud, each user gets their own voice picked
+    /// from their name so it stays the same between messages.
+    fn speak(&self, video: &VideoElementHandle, rate: f32, signed_authors: bool) {
+        let username = self.identity(signed_authors)
+            .map(|(username, _)| username)
+            .unwrap_or(UNVERIFIED_NAME);
+
+        let mut hasher = FxHasher::default();
+        username.hash(&mut hasher);
+        let seed = hasher.finish() as u32;
+
+        let text = format!("{} says {}", username, self.content);
+        if !video.speak_as(seed, text, rate) {
+            ConsoleService::warn("Speech synthesis is not supported by this browser");
+        }
+    }
+
+    /// Renders the note for any collapsed similar messages.
+    fn similar_html(&self, username: &str) -> Html {
+        if self.similar == 0 {
+            return html!{};
+        }
+
+        html! {
+            <p class="text-gray-400 text-xs italic">
+                { format!("{} sent {} similar messages", username, self.similar + 1) }
+            </p>
+        }
+    }
+
+    /// Renders the translation beneath the message, or the action to
+    /// translate it if it hasn't been.
+    fn translation_html(&self, link: &ComponentLink<ChatRoom>) -> Html {
+        let id = match self.id.clone() {
+            Some(id) => id,
+            None => return html!{},
+        };
+
+        if self.translating {
+            return html! {
+                <p class="text-gray-400 text-xs italic">{ "Translating..." }</p>
+            };
+        }
+
+        let translation = match self.translation.as_ref() {
+            Some(translation) if translation.is_needed() => translation,
+            Some(_) => return html!{},
+            None => {
+                let translate_cb = link.callback(move |_| ChatRoomEvents::Translate(id.clone()));
+                return html! {
+                    <button onclick=translate_cb class="text-gray-500 hover:text-gray-300 text-xs focus:outline-none">
+                        { "Translate" }
+                    </button>
+                };
+            },
+        };
+
+        let toggle_cb = link.callback(move |_| ChatRoomEvents::ToggleOriginal(id.clone()));
+        let source = translation.source_language
+            .as_ref()
+            .map(|language| format!("Translated from {}", language))
+            .unwrap_or_else(|| "Translated".to_string());
+        let toggle = if self.hide_original { "Show original" } else { "Hide original" };
+
+        html! {
+            <>
+                <p class="text-gray-200 italic break-words">{ &translation.text }</p>
+                <p class="text-gray-500 text-xs">
+                    { source }{ " · " }
+                    <button onclick=toggle_cb class="hover:text-gray-300 focus:outline-none">{ toggle }</button>
+                </p>
+            </>
+        }
+    }
+
     /// Renders the message to a html element.
-    fn to_html(&self) -> Html {
+    fn to_html(
+        &self,
+        link: &ComponentLink<ChatRoom>,
+        signed_authors: bool,
+        prefs: &Preferences,
+    ) -> Html {
+        let identity = self.identity(signed_authors);
+        let username = identity.map(|(username, _)| username).unwrap_or(UNVERIFIED_NAME);
+
+        // The gateway's time is preferred so replayed history shows when
+        // it was sent rather than when we caught up.
+        let at = self.sent_at.unwrap_or(self.received_at);
+        let timestamp = html! {
+            <span class="text-xs text-gray-500 font-normal ml-2" title=prefs.format_clock_time(at)>
+                { prefs.format_timestamp(at) }
+            </span>
+        };
+
+        let (name, avatar) = match identity {
+            Some((username, avatar)) => (
+                html! {
+                    <h1 class="text-accent font-semibold">{ username }{ timestamp }</h1>
+                },
+                html! {
+                    <img class="inline-block rounded-full h-12 w-12" src={avatar} alt="" />
+                },
+            ),
+            None => (
+                html! {
+                    <h1 class="text-gray-400 font-semibold" title="The gateway did not sign who sent this message">
+                        { username }
+                        <span class="text-xs text-red-400 font-normal ml-2">{ "unverified" }</span>
+                        { timestamp }
+                    </h1>
+                },
+                html! {
+                    <div class="inline-block flex-shrink-0 rounded-full h-12 w-12 bg-gray-700"></div>
+                },
+            ),
+        };
+
         html! {
             <div class="flex py-2">
-                <img class="inline-block rounded-full h-12 w-12" src={&self.avatar} alt="" />
+                { avatar }
                 <div class="inline-block px-3 w-5/6">
-                    <h1 class="text-blue-400 font-semibold">{ &self.username }</h1>
-                    <p class="text-white" style="word-wrap: break-word;">
+                    { name }
+                    <p class="text-white break-words" hidden=self.hide_original>
                         { &self.content }
+                        { if self.edited { html! { <span class="text-gray-400 text-xs ml-1">{ "(edited)" }</span> } } else { html!{} } }
                     </p>
+                    { self.translation_html(link) }
+                    { self.similar_html(username) }
                 </div>
             </div>
         }
@@ -142,15 +751,39 @@ async fn who_am_i() -> TextInputEvents {
         .send()
         .await;
 
-    if let Ok(resp) = resp {
-        let user = resp.json::<UserInfo>().await.unwrap();
-        TextInputEvents::WhoAmI(user)
-    } else {
-        TextInputEvents::RequestError
+    match resp {
+        Ok(resp) => match resp.json::<UserInfo>().await {
+            Ok(user) => TextInputEvents::WhoAmI(user),
+            Err(_) => TextInputEvents::RequestError,
+        },
+        Err(_) => TextInputEvents::RequestError,
     }
 }
 
 
+/// Fetches the room's chat config which decides how messages are
+/// delivered to Discord, falling back to the legacy defaults if
+/// the request fails.
+async fn fetch_room_config(room_id: String) -> ChatRoomEvents {
+    let url = settings::get_room_config_url(&room_id);
+
+    let resp = Client::new()
+        .get(&url)
+        .send()
+        .await;
+
+    let config = match resp {
+        Ok(resp) => resp.json::<RoomConfig>().await.unwrap_or_default(),
+        Err(_) => {
+            ConsoleService::warn("Failed to get room config, using defaults");
+            RoomConfig::default()
+        },
+    };
+
+    ChatRoomEvents::Config(config)
+}
+
+
 /// Fetches the webhook info for the message system to allow messages to
 /// discord.
 async fn acquire_webhook(room_id: String) -> TextInputEvents {
@@ -161,46 +794,94 @@ async fn acquire_webhook(room_id: String) -> TextInputEvents {
         .send()
         .await;
 
-    if let Ok(resp) = resp {
-        let wh = resp.json::<Webhook>().await.unwrap();
-        TextInputEvents::Webhook(wh)
-    } else {
-        TextInputEvents::RequestError
+    match resp {
+        Ok(resp) => match resp.json::<Webhook>().await {
+            Ok(wh) => TextInputEvents::Webhook(wh),
+            Err(_) => TextInputEvents::RequestError,
+        },
+        Err(_) => TextInputEvents::RequestError,
     }
 }
 
-/// Sends a PUT request to the api to emit a message to clients.
-async fn send_message(room_id: String, wh_url: String, msg: Message) {
-    {
-        let webhook_payload = WebhookMessage {
-            username: &msg.username,
-            avatar_url: &msg.avatar,
-            content: &msg.content,
-        };
-
-        let _ = Client::new()
-            .post(&wh_url)
-            .json(&webhook_payload)
-            .send()
-            .await;
-    }
+/// Queues the message in the room's outbox to be emitted to clients, the
+/// outbox retries it if the api can't be reached.
+///
+/// In `Delivery::Webhook` mode the message is also posted to the Discord
+/// webhook directly, otherwise the gateway is asked to mirror it instead
+/// so messages sent before the webhook is fetched, or if fetching it
+/// fails, still reach Discord.
+async fn send_message(room_id: String, delivery: Delivery, msg: Message) -> Option<String> {
+    let guidance = if let Delivery::Webhook(wh_url) = &delivery {
+        webhook::post_message(wh_url, &msg.username, &msg.avatar, &msg.content).await
+    } else {
+        None
+    };
 
 
     let msg = serde_json::to_value(msg).unwrap();
     let payload = WrappingWsMessage {
         opcode: opcodes::OP_MESSAGE,
-        payload: Some(msg)
+        payload: Some(msg),
+        mirror: !matches!(delivery, Delivery::Webhook(_)),
     };
 
     emit_event(room_id, payload).await;
+
+    guidance
 }
 
 
-#[derive(Serialize)]
-struct WebhookMessage<'a>{
-    username: &'a str,
-    avatar_url: &'a str,
-    content: &'a str,
+/// Queues each part of a split message in order, the outbox sends them one
+/// at a time so they arrive in the right order. Webhook posts are awaited
+/// part by part for the same reason.
+async fn send_parts(room_id: String, delivery: Delivery, parts: Vec<Message>) -> TextInputEvents {
+    let mut guidance = None;
+    for part in parts {
+        let notice = send_message(room_id.clone(), delivery.clone(), part).await;
+        guidance = guidance.or(notice);
+    }
+
+    TextInputEvents::Queued(guidance)
+}
+
+
+/// Splits the content into numbered parts that each fit within `limit`
+/// characters including the `(1/2) ` prefix, preferring to split on
+/// whitespace. Content that already fits is returned as is.
+fn split_message(content: &str, limit: usize) -> Vec<String> {
+    let chars: Vec<char> = content.chars().collect();
+    if chars.len() <= limit {
+        return vec![content.to_string()];
+    }
+
+    // Room for a `(NN/NN) ` prefix on every part.
+    let size = limit.saturating_sub(8).max(1);
+
+    let mut chunks = vec![];
+    let mut start = 0;
+    while start < chars.len() {
+        let mut end = (start + size).min(chars.len());
+        if end < chars.len() {
+            if let Some(space) = chars[start..end].iter().rposition(|c| c.is_whitespace()) {
+                if space > 0 {
+                    end = start + space;
+                }
+            }
+        }
+
+        let chunk: String = chars[start..end].iter().collect();
+        let chunk = chunk.trim();
+        if !chunk.is_empty() {
+            chunks.push(chunk.to_string());
+        }
+        start = end;
+    }
+
+    let total = chunks.len();
+    chunks.into_iter()
+        .enumerate()
+        .map(|(i, chunk)| format!("({}/{}) {}", i + 1, total, chunk))
+        .collect()
 }
 
 
@@ -215,6 +896,39 @@ pub struct UserInfo {
 }
 
 
+/// A member's new Discord name and avatar, sent by the gateway when it
+/// notices a change mid-session.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ProfileUpdate {
+    /// The name the member had until now, messages are matched by it.
+    previous_username: String,
+
+    username: String,
+
+    /// The user's full avatar url.
+    avatar: String,
+}
+
+impl ProfileUpdate {
+    /// Updates the message if it is from the member, the gateway is the
+    /// source of signed authors so those are updated too.
+    fn apply(&self, message: &mut Message, signed_authors: bool) {
+        let username = message.identity(signed_authors).map(|(username, _)| username);
+        if username != Some(self.previous_username.as_str()) {
+            return;
+        }
+
+        if let Some(author) = message.author.as_mut() {
+            author.username = self.username.clone();
+            author.avatar = self.avatar.clone();
+        } else {
+            message.username = self.username.clone();
+            message.avatar = self.avatar.clone();
+        }
+    }
+}
+
+
 /// The room webhook for Discord.
 #[derive(Debug, Deserialize)]
 pub struct Webhook {
@@ -222,16 +936,106 @@ pub struct Webhook {
 }
 
 
+/// The chat related config of the room.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+pub struct RoomConfig {
+    /// If the gateway mirrors messages to Discord itself, when this is
+    /// set the webhook url is never fetched by the client.
+    #[serde(default)]
+    gateway_mirroring: bool,
+
+    /// If the gateway attaches a signed author to every message, when
+    /// this is set messages without one are flagged as unverified and
+    /// their self reported name is never used.
+    #[serde(default)]
+    signed_authors: bool,
+
+    /// The most characters the server accepts in a single message, the
+    /// client default is used if the room doesn't set one.
+    #[serde(default)]
+    max_message_length: Option<usize>,
+
+    /// How long after sending a message it can still be edited or deleted
+    /// in seconds, None if there is no limit.
+    #[serde(default)]
+    edit_window_secs: Option<u64>,
+
+    /// If members publish how far they have read so hosts can see who
+    /// has seen announcements.
+    #[serde(default)]
+    publish_read_state: bool,
+}
+
+
+/// The author of a message as attached by the gateway from the
+/// sender's session rather than what the client claims to be.
+///
+/// The gateway drops any author a client sends, so one being present is
+/// what makes it trusted, its signature isn't checked by the client.
+#[derive(Debug, Deserialize)]
+pub struct SignedAuthor {
+    /// The Discord user's display name.
+    username: String,
+
+    /// The user's full avatar url.
+    avatar: String,
+}
+
+
+/// How a sent message reaches the room's Discord channel.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Delivery {
+    /// The webhook url hasn't been fetched yet or fetching it failed, the
+    /// gateway mirrors messages meanwhile.
+    Pending,
+
+    /// The client posts to the webhook itself, this is the legacy
+    /// behaviour and exposes the webhook url to every member.
+    Webhook(String),
+
+    /// The message is only sent to the gateway which mirrors it to Discord.
+    Gateway,
+}
+
+
+/// How often the user's identity is fetched again in case their Discord
+/// name or avatar changed.
+const WHO_AM_I_REFRESH_SECS: u64 = 10 * 60;
+
+/// The default most characters in a single message if the room config
+/// doesn't set one.
+const DEFAULT_MESSAGE_LENGTH: usize = 1024;
+
+/// The most parts a long message is split into, input past this is dropped.
+const MAX_MESSAGE_PARTS: usize = 4;
+
+/// How close to the limit the counter starts warning the user.
+const LENGTH_WARNING_REMAINING: usize = 100;
+
+
+/// The local storage key the unsent message of the room is kept under.
+fn draft_key(room_id: &str) -> String {
+    format!("spooderfy.draft.{}", room_id)
+}
+
+
 #[derive(Properties, Clone)]
 pub struct TextInputProperties {
     pub room_id: String,
+
+    /// The resolved room config.
+    pub config: RoomConfig,
+
+    /// The latest profile change in the room.
+    pub profile_update: Option<ProfileUpdate>,
 }
 
 /// Text input events either from a button click or text input.
 #[derive(Debug)]
 pub enum TextInputEvents {
-    /// A text input key press.
-    KeyPress(String),
+    /// The browser has updated the input's value, whether typed, pasted,
+    /// cut or composed, this is the source of truth for the message.
+    Input(String),
 
     /// The submit button has been pressed.
     Submit,
@@ -239,19 +1043,63 @@ pub enum TextInputEvents {
     /// The user identification result.
     WhoAmI(UserInfo),
 
+    /// Fetch the user's identity again in case their profile changed.
+    RefreshWhoAmI,
+
     /// The user identification result.
     Webhook(Webhook),
 
+
     /// The request lookup failed.
     RequestError,
+
+    /// The message has been queued for the room, with guidance for the
+    /// user if Discord rejected it.
+    Queued(Option<String>),
+
+    /// A key that doesn't produce a character has been pressed.
+    KeyDown(String),
+}
+
+
+/// The last message the user sent, kept for editing it.
+struct SentMessage {
+    id: String,
+    content: String,
+
+    /// When the message was sent in milliseconds.
+    sent_at: f64,
 }
 
 pub struct TextInput {
     link: ComponentLink<Self>,
     room_id: String,
     msg: Vec<String>,
+
+    /// The composer's input, its value is put back when an edit of it is
+    /// rejected.
+    input_ref: NodeRef,
     user: Option<UserInfo>,
-    webhook_url: String,
+    delivery: Delivery,
+
+    /// The most characters in a single message, longer messages are
+    /// split into parts.
+    max_length: usize,
+
+    /// How long a sent message can be edited for in milliseconds.
+    edit_window_ms: Option<f64>,
+
+    /// The last message sent, the up arrow loads it back for editing.
+    last_sent: Option<SentMessage>,
+
+    /// The id of the message being edited, submitting replaces its
+    /// content or deletes it if the composer is empty.
+    editing: Option<String>,
+
+    /// Guidance about the last sent message.
+    notice: Option<String>,
+
+    _refresh_who_am_i: IntervalTask,
 }
 
 impl Component for TextInput {
@@ -264,57 +1112,108 @@ impl Component for TextInput {
             link.clone(),
             who_am_i()
         );
-        send_future(
-            link.clone(),
-            acquire_webhook(props.room_id.clone())
+        let delivery = if props.config.gateway_mirroring {
+            Delivery::Gateway
+        } else {
+            send_future(
+                link.clone(),
+                acquire_webhook(props.room_id.clone())
+            );
+            Delivery::Pending
+        };
+
+        // Profile changes the gateway misses are caught up with eventually.
+        let refresh_who_am_i = IntervalService::spawn(
+            Duration::from_secs(WHO_AM_I_REFRESH_SECS),
+            link.callback(|_| TextInputEvents::RefreshWhoAmI),
         );
 
+        let mut msg = Vec::with_capacity(DEFAULT_MESSAGE_LENGTH);
+        if let Some(draft) = load_local::<String>(&draft_key(&props.room_id)) {
+            msg.extend(draft.chars().map(String::from));
+        }
+
         Self {
             link,
             room_id: props.room_id,
-            msg: Vec::with_capacity(1024),
+            msg,
+            input_ref: NodeRef::default(),
             user: None,
-            webhook_url: "".to_string(),
+            delivery,
+            max_length: props.config.max_message_length.unwrap_or(DEFAULT_MESSAGE_LENGTH).max(1),
+            edit_window_ms: props.config.edit_window_secs.map(|secs| secs as f64 * 1_000.0),
+            last_sent: None,
+            editing: None,
+            notice: None,
+            _refresh_who_am_i: refresh_who_am_i,
         }
     }
 
     fn update(&mut self, msg: Self::Message) -> ShouldRender {
         match msg {
             TextInputEvents::Submit => return self.submit(),
-            TextInputEvents::KeyPress(key) => {
-                if let None = self.user {
-                    return true;
-                }
-
-                if &key == "Enter" {
-                    return self.submit();
+            TextInputEvents::Input(value) => {
+                if self.user.is_none()
+                    || split_message(&value, self.max_length).len() > MAX_MESSAGE_PARTS
+                {
+                    self.reset_input();
+                    return false;
                 }
 
-                if self.msg.len() < 1024 {
-                    self.msg.push(key);
-                }
+                self.msg = value.chars().map(String::from).collect();
+                self.save_draft();
+                return true;
             },
             TextInputEvents::WhoAmI(user) => {
                 self.user = Some(user);
             },
+            TextInputEvents::RefreshWhoAmI => send_future(self.link.clone(), who_am_i()),
             TextInputEvents::Webhook(wh) => {
-                self.webhook_url = wh.url;
-            }
+                self.delivery = Delivery::Webhook(wh.url);
+            },
             TextInputEvents::RequestError => {
                 ConsoleService::error("Failed to get request");
             },
+            TextInputEvents::KeyDown(key) => return self.on_key_down(&key),
+            TextInputEvents::Queued(notice) => {
+                self.notice = notice;
+                self.save_draft();
+                return true;
+            },
         }
 
         false
     }
 
-    fn change(&mut self, _props: Self::Properties) -> ShouldRender {
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        let update = match props.profile_update {
+            Some(update) => update,
+            None => return false,
+        };
+
+        if let Some(user) = self.user.as_mut() {
+            if user.username == update.previous_username {
+                user.username = update.username;
+                user.avatar = update.avatar;
+            }
+        }
+
         false
     }
 
     fn view(&self) -> Html {
-        let typing_cb = self.link.callback(
-            |e: KeyboardEvent| TextInputEvents::KeyPress(e.key())
+        let typing_cb = self.link.batch_callback(|e: KeyboardEvent| {
+            if e.key() == "Enter" {
+                vec![TextInputEvents::Submit]
+            } else {
+                vec![]
+            }
+        });
+        let key_down_cb = self.link.callback(
+            |e: KeyboardEvent| TextInputEvents::KeyDown(e.key())
+        );
+        let input_cb = self.link.callback(
+            |e: InputData| TextInputEvents::Input(e.value)
         );
         let submit_cb = self.link.callback(
             |_| TextInputEvents::Submit
@@ -322,6 +1221,29 @@ impl Component for TextInput {
 
         let existing: String = self.msg.join("");
 
+        let placeholder = if self.editing.is_some() {
+            "Editing your message, empty it to delete, escape to cancel"
+        } else {
+            "Send something to the movie room..."
+        };
+
+        let length = self.msg.len();
+        let parts = split_message(&existing, self.max_length).len();
+        let (counter_class, counter) = if parts > 1 {
+            ("text-yellow-400", format!("{} / {} (sent as {} parts)", length, self.max_length, parts))
+        } else if length + LENGTH_WARNING_REMAINING >= self.max_length {
+            ("text-yellow-400", format!("{} / {}", length, self.max_length))
+        } else {
+            ("text-gray-400", format!("{} / {}", length, self.max_length))
+        };
+
+        let notice = match &self.notice {
+            Some(notice) => html! {
+                <p class="text-yellow-400 text-xs font-semibold mt-1 px-2">{ notice }</p>
+            },
+            None => html! {},
+        };
+
         html! {
             <div class="p-2 relative w-full">
                 <label>
@@ -333,21 +1255,112 @@ impl Component for TextInput {
                             rounded-lg focus:outline-none \
                             bg-gray-800 w-full h-10 px-5 pr-16"
                         onkeypress=typing_cb
+                        onkeydown=key_down_cb
+                        oninput=input_cb
+                        ref=self.input_ref.clone()
                         value=existing
                         name="message"
-                        placeholder="Send something to the movie room..."
+                        placeholder=placeholder
                         type="text"
                     />
                </label>
                <button onclick=submit_cb class="absolute right-0 top-0 my-4 mr-4 focus:outline-none"
                        type="submit">
                </button>
+               <div class="flex justify-end px-2">
+                   <span class=("text-xs font-semibold", counter_class)>{ counter }</span>
+               </div>
+               { notice }
             </div>
         }
     }
 }
 
 impl TextInput {
+    /// Handles the keys that don't produce a character, these aren't
+    /// given to the key press handler.
+    fn on_key_down(&mut self, key: &str) -> ShouldRender {
+        match key {
+            "Escape" if self.editing.is_some() => {
+                self.editing = None;
+                self.msg.clear();
+                self.save_draft();
+            },
+            "ArrowUp" if self.msg.is_empty() && self.editing.is_none() => {
+                let last = match self.last_sent.as_ref() {
+                    Some(last) => last,
+                    None => return false,
+                };
+
+                let within_window = self.edit_window_ms
+                    .map(|window| js_sys::Date::now() - last.sent_at <= window)
+                    .unwrap_or(true);
+
+                if !within_window {
+                    self.notice = Some("Your last message can no longer be edited.".to_string());
+                    return true;
+                }
+
+                self.msg.extend(last.content.chars().map(String::from));
+                self.editing = Some(last.id.clone());
+            },
+            _ => return false,
+        }
+
+        true
+    }
+
+    /// Emits an edit of the user's message to the room, empty content
+    /// deletes the message instead.
+    fn send_edit(&mut self, id: String, content: String) {
+        if self.user.is_none() {
+            return;
+        }
+
+        let (opcode, content) = if content.is_empty() {
+            self.last_sent = None;
+            (opcodes::OP_DELETE_MESSAGE, None)
+        } else {
+            if let Some(last) = self.last_sent.as_mut() {
+                last.content = content.clone();
+            }
+            (opcodes::OP_EDIT_MESSAGE, Some(content))
+        };
+
+        let edit = MessageEdit {
+            id,
+            editor: None,
+            content,
+        };
+
+        let payload = WrappingWsMessage {
+            opcode,
+            payload: serde_json::to_value(edit).ok(),
+            mirror: !matches!(self.delivery, Delivery::Webhook(_)),
+        };
+
+        start_future(emit_event(self.room_id.clone(), payload));
+    }
+
+    /// Puts the message back into the input, the rendered value hasn't
+    /// changed so rendering wouldn't undo the rejected edit.
+    fn reset_input(&self) {
+        if let Some(input) = self.input_ref.cast::<HtmlInputElement>() {
+            input.set_value(&self.msg.join(""));
+        }
+    }
+
+    /// Persists the unsent message so it survives a reload, the draft is
+    /// removed once there is nothing left unsent.
+    fn save_draft(&self) {
+        let key = draft_key(&self.room_id);
+        if self.msg.is_empty() {
+            remove_local(&key);
+        } else {
+            store_local(&key, &self.msg.join(""));
+        }
+    }
+
     /// Joins the characters of the message together, clears the vector
     /// and sends the message to the gateway if the `user` field is not
     /// None, in the case that it is None; nothing happens.
@@ -356,15 +1369,40 @@ impl TextInput {
             let complete_msg: String = self.msg.join("");
             self.msg.clear();
 
-            let msg = Message {
-                username: user.username.clone(),
-                avatar: user.avatar.clone(),
-                content: complete_msg,
-            };
+            if let Some(id) = self.editing.take() {
+                self.send_edit(id, complete_msg);
+                self.save_draft();
+                return true;
+            }
 
-            start_future(send_message(
+            let parts: Vec<Message> = split_message(&complete_msg, self.max_length)
+                .into_iter()
+                .map(|content| Message {
+                    id: Some(random_id()),
+                    username: user.username.clone(),
+                    avatar: user.avatar.clone(),
+                    content,
+                    author: None,
+                    sent_at: None,
+                    received_at: 0.0,
+                    similar: 0,
+                    edited: false,
+                    translation: None,
+                    translating: false,
+                    hide_original: false,
+                })
+                .collect();
+
+            self.last_sent = parts.last().map(|msg| SentMessage {
+                id: msg.id.clone().unwrap_or_default(),
+                content: msg.content.clone(),
+                sent_at: js_sys::Date::now(),
+            });
+
+            self.notice = None;
+            send_future(self.link.clone(), send_parts(
                 self.room_id.clone(),
-                self.webhook_url.clone(), msg));
+                self.delivery.clone(), parts));
 
 
             true
@@ -375,4 +1413,42 @@ impl TextInput {
 }
 
 
+#[cfg(test)]
+mod tests {
+    use super::split_message;
+
+    #[test]
+    fn short_messages_are_not_split() {
+        assert_eq!(split_message("hello", 10), vec!["hello".to_string()]);
+    }
+
+    #[test]
+    fn parts_fit_within_the_limit_including_the_prefix() {
+        let content = "a".repeat(1020);
+        let parts = split_message(&content, 1024);
+
+        assert_eq!(parts.len(), 1);
+
+        let content = "a ".repeat(1024);
+        let parts = split_message(&content, 1024);
+
+        assert_eq!(parts.len(), 3);
+        assert!(parts.iter().all(|part| part.chars().count() <= 1024));
+        assert!(parts[0].starts_with("(1/3) "));
+    }
+
+    #[test]
+    fn messages_near_the_limit_are_split_where_the_counter_says() {
+        // The prefix leaves 1016 characters per part.
+        let content = "a".repeat(1030);
+        assert_eq!(split_message(&content, 1024).len(), 2);
+    }
+
+    #[test]
+    fn whitespace_runs_produce_no_empty_parts() {
+        let content = format!("{}{}{}", "a".repeat(8), " ".repeat(20), "b".repeat(8));
+        let parts = split_message(&content, 10);
 
+        assert!(parts.iter().all(|part| !part.ends_with(") ")));
+    }
+}
@@ -0,0 +1,156 @@
+use yew::prelude::*;
+use yew::services::IntervalService;
+use yew::services::interval::IntervalTask;
+
+use serde::Deserialize;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use crate::opcodes;
+use crate::prefs::{OverlayCorner, Preferences};
+use crate::settings;
+use crate::websocket::{WsHandler, WebsocketMessage};
+
+
+/// The most messages shown at once.
+const MAX_MESSAGES: usize = 5;
+
+/// How long a message stays on screen in milliseconds.
+const MESSAGE_LIFETIME_MS: f64 = 10_000.0;
+
+/// How long before leaving a message starts fading out.
+const FADE_MS: f64 = 2_000.0;
+
+/// How often the messages are aged.
+const TICK_MS: u64 = 250;
+
+
+/// The parts of a chat message the overlay shows.
+#[derive(Deserialize)]
+struct OverlayMessage {
+    username: String,
+    content: String,
+
+    /// When the message arrived, local only.
+    #[serde(skip)]
+    received_at: f64,
+}
+
+
+#[derive(Properties, Clone)]
+pub struct ChatOverlayProperties {
+    /// The room websocket handle.
+    pub ws: WsHandler,
+}
+
+
+/// The events that can be invoked by callbacks for the ChatOverlay.
+pub enum ChatOverlayEvents {
+    /// A chat message has been sent in the room.
+    Message(WebsocketMessage),
+
+    /// Age the shown messages.
+    Tick,
+}
+
+
+/// Shows the latest chat messages over the video while in fullscreen so
+/// nobody misses the conversation, each fading out after a few seconds.
+///
+/// The overlay never takes clicks so the player underneath stays usable,
+/// its opacity and corner are set in the preferences.
+pub struct ChatOverlay {
+    _ws: WsHandler,
+    _tick: IntervalTask,
+    messages: VecDeque<OverlayMessage>,
+}
+
+impl Component for ChatOverlay {
+    type Message = ChatOverlayEvents;
+    type Properties = ChatOverlayProperties;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let ws = props.ws;
+        let cb = link.callback(ChatOverlayEvents::Message);
+        ws.subscribe_to_message(settings::CHAT_OVERLAY_ID, opcodes::OP_MESSAGE, cb);
+
+        let tick = IntervalService::spawn(
+            Duration::from_millis(TICK_MS),
+            link.callback(|_| ChatOverlayEvents::Tick),
+        );
+
+        Self {
+            _ws: ws,
+            _tick: tick,
+            messages: VecDeque::new(),
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            ChatOverlayEvents::Message(msg) => {
+                if !Preferences::load().chat_overlay {
+                    return false;
+                }
+
+                if let Some(mut message) = msg.unwrap_and_into::<OverlayMessage>() {
+                    message.received_at = js_sys::Date::now();
+                    self.messages.push_back(message);
+
+                    while self.messages.len() > MAX_MESSAGES {
+                        self.messages.pop_front();
+                    }
+                }
+            },
+            ChatOverlayEvents::Tick => {
+                if self.messages.is_empty() {
+                    return false;
+                }
+
+                let now = js_sys::Date::now();
+                self.messages.retain(|message| now - message.received_at < MESSAGE_LIFETIME_MS);
+            },
+        }
+
+        true
+    }
+
+    fn change(&mut self, _props: Self::Properties) -> ShouldRender {
+        false
+    }
+
+    fn view(&self) -> Html {
+        if self.messages.is_empty() {
+            return html!{};
+        }
+
+        let prefs = Preferences::load();
+        let now = js_sys::Date::now();
+
+        let messages = self.messages.iter().map(|message| {
+            let left = MESSAGE_LIFETIME_MS - (now - message.received_at);
+            let fade = (left / FADE_MS).clamp(0.0, 1.0);
+            let style = format!("opacity: {};", prefs.chat_overlay_opacity * fade);
+
+            html! {
+                <p class="bg-black bg-opacity-50 rounded text-white text-sm px-2 py-1 mt-1 break-words" data-style=style>
+                    <span class="font-bold mr-1">{ &message.username }</span>
+                    { &message.content }
+                </p>
+            }
+        });
+
+        let corner = match prefs.chat_overlay_corner {
+            OverlayCorner::TopLeft => "top-0 left-0 items-start",
+            OverlayCorner::TopRight => "top-0 right-0 items-end",
+            OverlayCorner::BottomLeft => "bottom-0 left-0 items-start mb-16",
+            OverlayCorner::BottomRight => "bottom-0 right-0 items-end mb-16",
+        };
+
+        html! {
+            <div class=("fullscreen-only absolute flex flex-col w-1/3 m-4 pointer-events-none z-20", corner)>
+                { for messages }
+            </div>
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use yew::prelude::*;
+
+use reqwest::{Client, StatusCode};
+use serde::Deserialize;
+
+use crate::settings;
+
+
+/// Why a room was closed, sent with `OP_ROOM_CLOSED` or as the body of
+/// a 410 from the api.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct RoomClosed {
+    /// The reason given by the owner or the moderators.
+    #[serde(default)]
+    pub reason: Option<String>,
+
+    /// A page listing the owner's other rooms.
+    #[serde(default)]
+    pub owner_rooms_url: Option<String>,
+}
+
+
+/// Checks if the room still exists, returning why it was closed if the
+/// api answers with 410 Gone. Any other failure is left to the components
+/// that need the api to report.
+pub async fn check_room_closed(room_id: String) -> Option<RoomClosed> {
+    let url = settings::get_room_config_url(&room_id);
+
+    let resp = Client::new()
+        .get(&url)
+        .send()
+        .await
+        .ok()?;
+
+    if resp.status() != StatusCode::GONE {
+        return None;
+    }
+
+    Some(resp.json::<RoomClosed>().await.unwrap_or_default())
+}
+
+
+#[derive(Properties, Clone)]
+pub struct ClosedScreenProperties {
+    pub closed: RoomClosed,
+}
+
+
+/// Shown in place of the room once it has been closed or archived.
+pub struct ClosedScreen {
+    closed: RoomClosed,
+}
+
+impl Component for ClosedScreen {
+    type Message = ();
+    type Properties = ClosedScreenProperties;
+
+    fn create(props: Self::Properties, _link: ComponentLink<Self>) -> Self {
+        Self {
+            closed: props.closed,
+        }
+    }
+
+    fn update(&mut self, _msg: Self::Message) -> ShouldRender {
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        let changed = self.closed != props.closed;
+        self.closed = props.closed;
+        changed
+    }
+
+    fn view(&self) -> Html {
+        let reason = match self.closed.reason.as_deref() {
+            Some(reason) if !reason.trim().is_empty() => html! {
+                <p class="text-gray-300 text-lg text-center mb-6 break-words">{ reason }</p>
+            },
+            _ => html! {
+                <p class="text-gray-300 text-lg text-center mb-6">{ "The owner has closed this room." }</p>
+            },
+        };
+
+        let owner_rooms = match self.closed.owner_rooms_url.as_ref() {
+            Some(url) => html! {
+                <a href=url.clone() class="bg-accent text-white font-semibold rounded-lg px-4 py-2 mx-2">
+                    { "The owner's other rooms" }
+                </a>
+            },
+            None => html!{},
+        };
+
+        html! {
+            <div class="flex justify-center items-center w-full h-screen p-8">
+                <div class="bg-discord-dark rounded-lg shadow-lg max-w-lg p-8">
+                    <h1 class="text-white font-bold text-3xl text-center mb-4">{ "This room has closed" }</h1>
+                    { reason }
+                    <div class="flex justify-center">
+                        { owner_rooms }
+                        <a href=settings::get_home_url() class="text-white font-semibold border-2 rounded-lg px-4 py-2 mx-2">
+                            { "Back to Spooderfy" }
+                        </a>
+                    </div>
+                </div>
+            </div>
+        }
+    }
+}
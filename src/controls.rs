@@ -0,0 +1,696 @@
+use wasm_bindgen::JsCast;
+use yew::prelude::*;
+use yew::web_sys::Element;
+use yew::services::IntervalService;
+use yew::services::interval::IntervalTask;
+
+use serde::Deserialize;
+use std::time::Duration;
+
+use crate::binder;
+use crate::opcodes;
+use crate::prefs::{Preferences, MAX_VOLUME_BOOST};
+use crate::settings;
+use crate::cast::CastButton;
+use crate::screenshot::ScreenshotButton;
+use crate::share::ShareButton;
+use crate::subtitles::{SubtitleMenu, SubtitleTrack};
+use crate::timefmt::{format_clock, parse_clock};
+use crate::sync::CountdownEvent;
+use crate::utils::{start_future, emit_event};
+use crate::video::VideoElementHandle;
+use crate::websocket::{WsHandler, WebsocketMessage, WrappingWsMessage};
+
+
+/// How often the player state is polled to keep the bar up to date.
+const POLL_INTERVAL_MS: u64 = 250;
+
+/// The spacing of the captured seek preview thumbnails in seconds.
+const THUMBNAIL_INTERVAL_SECS: f64 = 10.0;
+
+/// How far ahead a countdown start is scheduled, this leaves time for
+/// the event to reach every member before the count begins.
+const COUNTDOWN_START_MS: f64 = 3_500.0;
+
+/// How far the rewind button jumps back in seconds.
+const REWIND_SECS: f64 = 10.0;
+
+/// How far behind the live edge a live stream can be and still count as
+/// live, HLS players always sit a few segments back.
+const LIVE_EDGE_SECS: f64 = 15.0;
+
+/// The playback rates the host can pick from.
+const RATE_OPTIONS: [f64; 6] = [0.5, 0.75, 1.0, 1.25, 1.5, 2.0];
+
+
+/// A snapshot of the player's state as reported by the js player.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PlayerState {
+    pub paused: bool,
+
+    /// The position in seconds.
+    pub position: f64,
+
+    /// The duration in seconds, for live streams this is the end of
+    /// the seekable range.
+    pub duration: f64,
+
+    /// The volume from 0 to 1.
+    pub volume: f64,
+
+    pub muted: bool,
+
+    /// The playback rate, 1 being normal speed.
+    #[serde(default = "default_rate")]
+    pub rate: f64,
+
+    /// If playback has stalled waiting for data.
+    #[serde(default)]
+    pub buffering: bool,
+
+    /// If the stream is live, `duration` is then the live edge.
+    #[serde(default)]
+    pub live: bool,
+
+    /// The earliest position that can be seeked to in a live stream's
+    /// buffer.
+    #[serde(default)]
+    pub seekable_start: f64,
+}
+
+impl PlayerState {
+    /// How far behind the live edge playback is in seconds.
+    pub fn behind_live(&self) -> f64 {
+        (self.duration - self.position).max(0.0)
+    }
+}
+
+
+fn default_rate() -> f64 {
+    1.0
+}
+
+impl Default for PlayerState {
+    fn default() -> Self {
+        Self {
+            paused: true,
+            position: 0.0,
+            duration: 0.0,
+            volume: 1.0,
+            muted: false,
+            rate: default_rate(),
+            buffering: false,
+            live: false,
+            seekable_start: 0.0,
+        }
+    }
+}
+
+impl PlayerState {
+    /// Reads the current state from the player, None if the player has
+    /// not been created yet.
+    pub fn read(video: &VideoElementHandle) -> Option<Self> {
+        let state = video.state()?;
+        serde_json::from_str(&state).ok()
+    }
+}
+
+
+/// A named point in the stream e.g. the end of an intro.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Chapter {
+    pub name: String,
+
+    /// The position the chapter starts at in seconds.
+    pub position: f64,
+}
+
+
+/// The chapter markers of the current stream, each event replaces the
+/// previous markers.
+#[derive(Deserialize)]
+struct ChaptersEvent {
+    chapters: Vec<Chapter>,
+}
+
+
+#[derive(Properties, Clone)]
+pub struct ControlBarProperties {
+    /// The room websocket handle.
+    pub ws: WsHandler,
+
+    /// The room id.
+    pub room_id: String,
+
+    /// If the active user controls playback for the room, other members
+    /// can only change their volume and fullscreen.
+    pub is_leader: bool,
+
+    /// The subtitle tracks of the stream.
+    pub subtitles: Vec<SubtitleTrack>,
+
+    /// The title of the stream, screenshots are named after it.
+    pub title: String,
+
+    /// The player being controlled.
+    pub video: VideoElementHandle,
+}
+
+
+/// The events that can be invoked by callbacks for the ControlBar.
+pub enum ControlBarEvents {
+    /// Poll the player state.
+    Tick,
+
+    /// The play / pause button has been pressed.
+    TogglePlay,
+
+    /// The seek slider has been moved to the given position.
+    Seek(f64),
+
+    /// The volume slider has been moved.
+    Volume(f64),
+
+    /// The mute button has been pressed.
+    ToggleMute,
+
+    /// The volume boost button has been pressed.
+    ToggleBoost,
+
+    /// The fullscreen button has been pressed.
+    Fullscreen,
+
+    /// The host has picked a playback rate for the room.
+    Rate(f64),
+
+    /// The mouse is over the seek bar at the given fraction of its width,
+    /// None once it leaves.
+    Hover(Option<f64>),
+
+    /// The chapter markers of the stream have been set.
+    Chapters(WebsocketMessage),
+
+    /// The host has asked to start playback with a countdown.
+    CountdownStart,
+
+    /// Seek back into a live stream's buffer.
+    Rewind,
+
+    /// Jump to the live edge of a live stream.
+    GoLive,
+
+    /// The host has clicked the time to type one to go to.
+    EditTime,
+
+    /// The host has typed a time to go to e.g. `1:23:45`.
+    GoTo(String),
+
+    /// The audio only button has been pressed.
+    ToggleAudioOnly,
+}
+
+
+/// The player's control bar, rendered by us rather than the browser so
+/// every interaction goes through the binder.
+///
+/// Interactions are applied to the player directly, the playback sync
+/// picks them up from the player's events and emits them to the room.
+pub struct ControlBar {
+    link: ComponentLink<Self>,
+    _ws: WsHandler,
+    room_id: String,
+    _poll: IntervalTask,
+    state: PlayerState,
+    is_leader: bool,
+    subtitles: Vec<SubtitleTrack>,
+    title: String,
+    video: VideoElementHandle,
+
+    /// The fraction of the seek bar being hovered.
+    hover: Option<f64>,
+
+    /// The chapter markers of the stream, ordered by position.
+    chapters: Vec<Chapter>,
+
+    /// If the host is typing a time to go to, and if what they typed
+    /// couldn't be read as one.
+    editing_time: bool,
+    time_invalid: bool,
+
+    /// If the video is hidden and only the audio is being played,
+    /// this is the data saver preference.
+    audio_only: bool,
+
+    /// The volume picked by the user, over 1 when boosted.
+    volume: f64,
+
+    /// If the volume can be boosted past 100%.
+    boost: bool,
+}
+
+impl ControlBar {
+    /// Renders the playback time, the host can click it to type a time
+    /// to go to.
+    fn time_html(&self, time: String) -> Html {
+        if !self.is_leader {
+            return html! { <span class="text-white text-sm font-semibold mr-4">{ time }</span> };
+        }
+
+        if self.editing_time {
+            let goto_cb = self.link.callback(|e: ChangeData| match e {
+                ChangeData::Value(value) => ControlBarEvents::GoTo(value),
+                _ => ControlBarEvents::EditTime,
+            });
+            let border = if self.time_invalid { "border-red-500" } else { "border-gray-600" };
+
+            return html! {
+                <input
+                    type="text"
+                    placeholder="1:23:45"
+                    title="Type a time and press enter to go to it"
+                    onchange=goto_cb
+                    class=("bg-gray-800 text-white text-sm border-2 rounded-lg focus:outline-none w-24 px-1 mr-4", border)
+                />
+            };
+        }
+
+        let edit_cb = self.link.callback(|_| ControlBarEvents::EditTime);
+        html! {
+            <button onclick=edit_cb title="Go to a time" class="text-white text-sm font-semibold focus:outline-none mr-4">
+                { time }
+            </button>
+        }
+    }
+
+    /// Renders the rewind button and the live badge of a live stream, the
+    /// badge shows how far behind the edge playback is and jumps back to
+    /// it when clicked.
+    fn live_controls(&self) -> Html {
+        if !self.state.live {
+            return html!{};
+        }
+
+        let rewind_cb = self.link.callback(|_| ControlBarEvents::Rewind);
+        let live_cb = self.link.callback(|_| ControlBarEvents::GoLive);
+
+        let behind = self.state.behind_live();
+        let (badge, colour) = if behind < LIVE_EDGE_SECS {
+            ("LIVE".to_string(), "bg-red-600")
+        } else {
+            (format!("-{}", format_clock(behind)), "bg-gray-600")
+        };
+
+        let title = if self.is_leader { "Jump to live" } else { "Only the host can control playback" };
+
+        html! {
+            <>
+                <button
+                    onclick=rewind_cb
+                    disabled=!self.is_leader
+                    title=format!("Back {} seconds", REWIND_SECS)
+                    class="text-white text-xs font-semibold focus:outline-none mr-2">
+                    { format!("-{}s", REWIND_SECS) }
+                </button>
+                <button
+                    onclick=live_cb
+                    disabled=!self.is_leader
+                    title=title
+                    class=("text-white text-xs font-bold rounded px-1 mr-4 focus:outline-none", colour)>
+                    { badge }
+                </button>
+            </>
+        }
+    }
+
+    /// The chapter the position falls in, if any.
+    fn chapter_at(&self, position: f64) -> Option<&Chapter> {
+        self.chapters
+            .iter()
+            .rev()
+            .find(|chapter| chapter.position <= position)
+    }
+
+    /// Renders a tick on the seek bar for each chapter, clicking one jumps
+    /// to it.
+    fn chapter_ticks(&self) -> Html {
+        let duration = self.state.duration;
+        if duration <= 0.0 {
+            return html!{};
+        }
+
+        let ticks = self.chapters
+            .iter()
+            .filter(|chapter| chapter.position > 0.0 && chapter.position < duration)
+            .map(|chapter| {
+                let position = chapter.position;
+                let jump_cb = self.link.callback(move |_| ControlBarEvents::Seek(position));
+                let style = format!("left: {}%;", position / duration * 100.0);
+
+                html! {
+                    <button
+                        onclick=jump_cb
+                        disabled=!self.is_leader
+                        title=chapter.name.clone()
+                        class="absolute top-0 w-1 h-full bg-white bg-opacity-75 rounded focus:outline-none"
+                        data-style=style
+                    />
+                }
+            });
+
+        html! { <>{ for ticks }</> }
+    }
+
+    /// Renders the preview tooltip above the hovered part of the seek bar.
+    fn preview_html(&self) -> Html {
+        let fraction = match self.hover {
+            Some(fraction) => fraction,
+            None => return html!{},
+        };
+
+        let start = self.state.seekable_start;
+        let position = start + fraction * (self.state.duration - start);
+        let chapter = match self.chapter_at(position) {
+            Some(chapter) => html! { <span class="text-white text-xs font-bold">{ &chapter.name }</span> },
+            None => html!{},
+        };
+        let thumbnail = match self.video.thumbnail_at(position, THUMBNAIL_INTERVAL_SECS) {
+            Some(src) => html! { <img class="w-40 h-24 object-cover rounded" src=src alt="" /> },
+            None => html!{},
+        };
+
+        let style = format!("left: {}%; transform: translateX(-50%);", fraction * 100.0);
+        html! {
+            <div class="absolute bottom-0 mb-6 flex flex-col items-center bg-gray-800 rounded-lg p-1 pointer-events-none" data-style=style>
+                { thumbnail }
+                { chapter }
+                <span class="text-white text-xs font-semibold">{ format_clock(position) }</span>
+            </div>
+        }
+    }
+}
+
+impl Component for ControlBar {
+    type Message = ControlBarEvents;
+    type Properties = ControlBarProperties;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let prefs = Preferences::load();
+        let poll = IntervalService::spawn(
+            Duration::from_millis(POLL_INTERVAL_MS),
+            link.callback(|_| ControlBarEvents::Tick),
+        );
+
+        let ws = props.ws;
+        let chapters_cb = link.callback(ControlBarEvents::Chapters);
+        ws.subscribe_to_message(settings::CONTROLS_ID, opcodes::OP_CHAPTERS, chapters_cb);
+
+        Self {
+            link,
+            _ws: ws,
+            room_id: props.room_id,
+            _poll: poll,
+            state: PlayerState::default(),
+            is_leader: props.is_leader,
+            subtitles: props.subtitles,
+            title: props.title,
+            video: props.video,
+            hover: None,
+            chapters: vec![],
+            editing_time: false,
+            time_invalid: false,
+            audio_only: prefs.data_saver(),
+            volume: prefs.volume,
+            boost: prefs.volume_boost,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            ControlBarEvents::Tick => {
+                if !self.state.paused {
+                    self.video.capture_thumbnail(THUMBNAIL_INTERVAL_SECS);
+                }
+            },
+            ControlBarEvents::Hover(fraction) => self.hover = fraction,
+            ControlBarEvents::Chapters(msg) => {
+                if let Some(event) = msg.unwrap_and_into::<ChaptersEvent>() {
+                    self.chapters = event.chapters;
+                    self.chapters.sort_by(|a, b| a.position.total_cmp(&b.position));
+                }
+            },
+            ControlBarEvents::TogglePlay if self.is_leader => self.video.toggle_play(),
+            ControlBarEvents::Seek(position) if self.is_leader => self.video.seek(position),
+            ControlBarEvents::Rewind if self.is_leader => {
+                let position = (self.state.position - REWIND_SECS).max(self.state.seekable_start);
+                self.video.seek(position);
+            },
+            ControlBarEvents::GoLive if self.is_leader => self.video.seek(self.state.duration),
+            ControlBarEvents::EditTime if self.is_leader => {
+                self.editing_time = !self.editing_time;
+                self.time_invalid = false;
+            },
+            ControlBarEvents::GoTo(input) if self.is_leader => match parse_clock(&input) {
+                Some(position) => {
+                    let position = position.clamp(self.state.seekable_start, self.state.duration);
+                    self.video.seek(position);
+                    self.editing_time = false;
+                    self.time_invalid = false;
+                },
+                None => self.time_invalid = true,
+            },
+            ControlBarEvents::Rate(rate) if self.is_leader => {
+                // The rate is applied when the event comes back from the
+                // room so every member changes speed together.
+                let payload = WrappingWsMessage {
+                    opcode: opcodes::OP_SET_RATE,
+                    payload: Some(serde_json::json!({ "rate": rate })),
+                    mirror: false,
+                };
+                start_future(emit_event(self.room_id.clone(), payload));
+            },
+            ControlBarEvents::CountdownStart if self.is_leader && self.state.paused => {
+                let event = CountdownEvent {
+                    start_at: binder::server_now() + COUNTDOWN_START_MS,
+                    position: self.state.position,
+                };
+                let payload = WrappingWsMessage {
+                    opcode: opcodes::OP_COUNTDOWN,
+                    payload: serde_json::to_value(event).ok(),
+                    mirror: false,
+                };
+                start_future(emit_event(self.room_id.clone(), payload));
+            },
+            ControlBarEvents::TogglePlay
+            | ControlBarEvents::Seek(_)
+            | ControlBarEvents::Rate(_)
+            | ControlBarEvents::CountdownStart
+            | ControlBarEvents::Rewind
+            | ControlBarEvents::GoLive
+            | ControlBarEvents::EditTime
+            | ControlBarEvents::GoTo(_) => {},
+            ControlBarEvents::Volume(volume) => {
+                let mut prefs = Preferences::load();
+                self.volume = volume.clamp(0.0, prefs.max_volume());
+                prefs.volume = self.volume;
+                prefs.muted = false;
+                prefs.save();
+                prefs.apply_volume(&self.video);
+            },
+            ControlBarEvents::ToggleMute => {
+                let mut prefs = Preferences::load();
+                prefs.muted = !self.state.muted;
+                prefs.save();
+                self.video.set_muted(prefs.muted);
+            },
+            ControlBarEvents::ToggleBoost => {
+                let mut prefs = Preferences::load();
+                self.boost = !prefs.volume_boost;
+                prefs.volume_boost = self.boost;
+                self.volume = self.volume.min(prefs.max_volume());
+                prefs.volume = self.volume;
+                prefs.save();
+                prefs.apply_volume(&self.video);
+            },
+            ControlBarEvents::Fullscreen => self.video.toggle_fullscreen(),
+            ControlBarEvents::ToggleAudioOnly => {
+                let mut prefs = Preferences::load();
+                self.audio_only = !prefs.data_saver();
+                prefs.data_saver = Some(self.audio_only);
+                prefs.save();
+
+                self.video.set_data_saver(self.audio_only);
+            },
+        }
+
+        if let Some(state) = PlayerState::read(&self.video) {
+            self.state = state;
+        }
+
+        true
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        let changed = self.is_leader != props.is_leader
+            || self.subtitles != props.subtitles
+            || self.title != props.title;
+        self.is_leader = props.is_leader;
+        self.subtitles = props.subtitles;
+        self.title = props.title;
+        changed
+    }
+
+    fn view(&self) -> Html {
+        let play_cb = self.link.callback(|_| ControlBarEvents::TogglePlay);
+        let seek_cb = self.link.callback(
+            |e: InputData| ControlBarEvents::Seek(e.value.parse().unwrap_or(0.0))
+        );
+        let volume_cb = self.link.callback(
+            |e: InputData| ControlBarEvents::Volume(e.value.parse().unwrap_or(0.0))
+        );
+        let rate_cb = self.link.callback(|e: ChangeData| {
+            if let ChangeData::Select(select) = e {
+                ControlBarEvents::Rate(select.value().parse().unwrap_or(1.0))
+            } else {
+                ControlBarEvents::Rate(1.0)
+            }
+        });
+
+        // Drift correction nudges the rate slightly so show the closest option.
+        let current_rate = RATE_OPTIONS
+            .iter()
+            .copied()
+            .min_by(|a, b| (a - self.state.rate).abs().total_cmp(&(b - self.state.rate).abs()))
+            .unwrap_or(1.0);
+        let rate_options = RATE_OPTIONS.iter().map(|rate| {
+            let selected = (*rate - current_rate).abs() < f64::EPSILON;
+            html! { <option value=rate.to_string() selected=selected>{ format!("{}x", rate) }</option> }
+        });
+
+        let hover_cb = self.link.callback(|e: MouseEvent| {
+            let width = e.target()
+                .and_then(|target| target.dyn_into::<Element>().ok())
+                .map(|el| f64::from(el.client_width()))
+                .unwrap_or(0.0);
+
+            if width > 0.0 {
+                let fraction = f64::from(e.offset_x()) / width;
+                ControlBarEvents::Hover(Some(fraction.clamp(0.0, 1.0)))
+            } else {
+                ControlBarEvents::Hover(None)
+            }
+        });
+        let leave_cb = self.link.callback(|_| ControlBarEvents::Hover(None));
+        let countdown_cb = self.link.callback(|_| ControlBarEvents::CountdownStart);
+        let mute_cb = self.link.callback(|_| ControlBarEvents::ToggleMute);
+        let fullscreen_cb = self.link.callback(|_| ControlBarEvents::Fullscreen);
+        let audio_only_cb = self.link.callback(|_| ControlBarEvents::ToggleAudioOnly);
+
+        let state = &self.state;
+        let play_icon = if state.paused {
+            html! { <path d="M6 4l10 6-10 6V4z" /> }
+        } else {
+            html! { <path d="M5 4h3v12H5V4zm7 0h3v12h-3V4z" /> }
+        };
+
+        let locked = if self.is_leader { "" } else { "opacity-50 cursor-not-allowed" };
+        // The element stops at 1, past that the boost makes up the rest.
+        let volume = if state.muted {
+            0.0
+        } else if self.volume > 1.0 && state.volume >= 1.0 {
+            self.volume
+        } else {
+            state.volume
+        };
+        let boost_cb = self.link.callback(|_| ControlBarEvents::ToggleBoost);
+        let max_volume = if self.boost { MAX_VOLUME_BOOST } else { 1.0 };
+        let time = if state.live {
+            format_clock(state.position)
+        } else {
+            format!("{} / {}", format_clock(state.position), format_clock(state.duration))
+        };
+
+        html! {
+            <div class="auto-hide flex items-center w-full bg-gray-900 rounded-b-lg px-4 py-2">
+                <button
+                    onclick=play_cb
+                    disabled=!self.is_leader
+                    title=if self.is_leader { "" } else { "Only the host can control playback" }
+                    class=("w-6 h-6 text-white focus:outline-none", locked)>
+                    <svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 20 20" fill="currentColor">
+                        { play_icon }
+                    </svg>
+                </button>
+                {
+                    if self.is_leader && state.paused {
+                        html! {
+                            <button onclick=countdown_cb class="text-white text-xs font-semibold border-2 rounded-lg px-1 ml-2 focus:outline-none" title="Start playback for everyone with a countdown">
+                                { "3-2-1" }
+                            </button>
+                        }
+                    } else {
+                        html!{}
+                    }
+                }
+                <div class="relative flex-grow flex mx-4">
+                    { self.preview_html() }
+                    <input
+                        type="range"
+                        class=("slider w-full", locked)
+                        min=state.seekable_start.to_string()
+                        max=state.duration.to_string()
+                        step="0.1"
+                        value=state.position.to_string()
+                        disabled=!self.is_leader
+                        oninput=seek_cb
+                        onmousemove=hover_cb
+                        onmouseleave=leave_cb
+                    />
+                    { self.chapter_ticks() }
+                </div>
+                { if state.buffering { html! { <span class="text-yellow-400 text-xs font-semibold mr-2">{ "Buffering..." }</span> } } else { html!{} } }
+                { self.time_html(time) }
+                { self.live_controls() }
+                <button onclick=mute_cb class="text-white text-sm font-semibold focus:outline-none mr-2">
+                    { if state.muted { "Unmute" } else { "Mute" } }
+                </button>
+                <input
+                    type="range"
+                    class="slider w-24 mr-2"
+                    min="0"
+                    max=max_volume.to_string()
+                    step="0.01"
+                    value=volume.to_string()
+                    oninput=volume_cb
+                    title=format!("{:.0}%", volume * 100.0)
+                />
+                <button
+                    onclick=boost_cb
+                    title="Allow the volume up to 200% for quiet videos"
+                    class=("text-xs font-semibold focus:outline-none mr-4", if self.boost { "text-blue-400" } else { "text-gray-400" })>
+                    { "Boost" }
+                </button>
+                <select
+                    onchange=rate_cb
+                    disabled=!self.is_leader
+                    class=("bg-gray-800 text-white text-sm rounded-lg focus:outline-none px-1 mr-4", locked)>
+                    { for rate_options }
+                </select>
+                <button
+                    onclick=audio_only_cb
+                    title="Hide the video and only play the audio to save bandwidth"
+                    class=("text-sm font-semibold focus:outline-none mr-2", if self.audio_only { "text-blue-400" } else { "text-white" })>
+                    { "Audio only" }
+                </button>
+                <ShareButton room_id=self.room_id.clone() video=self.video.clone() />
+                <ScreenshotButton title=self.title.clone() video=self.video.clone() />
+                <CastButton video=self.video.clone() />
+                <SubtitleMenu tracks=self.subtitles.clone() video=self.video.clone() />
+                <button onclick=fullscreen_cb class="w-6 h-6 text-white focus:outline-none">
+                    <svg xmlns="http://www.w3.org/2000/svg" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                        <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M4 8V4m0 0h4M4 4l5 5m11-1V4m0 0h-4m4 0l-5 5M4 16v4m0 0h4m-4 0l5-5m11 5l-5-5m5 5v-4m0 4h-4" />
+                    </svg>
+                </button>
+            </div>
+        }
+    }
+}
@@ -0,0 +1,305 @@
+use yew::prelude::*;
+use yew::services::IntervalService;
+use yew::services::interval::IntervalTask;
+
+use serde::{Serialize, Deserialize};
+use std::time::Duration;
+
+use crate::controls::PlayerState;
+use crate::opcodes;
+use crate::prefs::Preferences;
+use crate::settings;
+use crate::utils::{load_local, store_local};
+use crate::video::VideoElementHandle;
+use crate::websocket::{WsHandler, WebsocketMessage};
+
+
+/// How often comments are launched and cleared.
+const TICK_MS: u64 = 100;
+
+/// The most comments stored per room for replaying.
+const MAX_STORED_COMMENTS: usize = 500;
+
+/// Roughly how many characters span the width of the player, used to
+/// estimate how long a comment takes to clear its lane's entry.
+const CHARS_PER_WIDTH: f64 = 60.0;
+
+/// The gap kept between comments sharing a lane in milliseconds.
+const LANE_GAP_MS: f64 = 250.0;
+
+
+/// The local storage key the room's comments are stored under.
+fn danmaku_key(room_id: &str) -> String {
+    format!("spooderfy.danmaku.{}", room_id)
+}
+
+
+/// A chat message stored against the position it was sent at.
+#[derive(Clone, Serialize, Deserialize)]
+struct TimedComment {
+    position: f64,
+    content: String,
+}
+
+
+/// The comments stored for the room's stream.
+#[derive(Default, Serialize, Deserialize)]
+struct StoredComments {
+    stream_url: String,
+    comments: Vec<TimedComment>,
+}
+
+
+/// The parts of a chat message danmaku cares about.
+#[derive(Deserialize)]
+struct DanmakuMessage {
+    content: String,
+}
+
+
+/// A comment currently scrolling across the video.
+struct ActiveComment {
+    id: u64,
+    content: String,
+    lane: usize,
+    started_at: f64,
+    duration_ms: f64,
+}
+
+
+#[derive(Properties, Clone)]
+pub struct DanmakuOverlayProperties {
+    /// The room websocket handle.
+    pub ws: WsHandler,
+
+    /// The room id.
+    pub room_id: String,
+
+    /// The url of the stream being played.
+    pub stream_url: String,
+
+    /// The player the comments are timed against.
+    pub video: VideoElementHandle,
+}
+
+
+/// The events that can be invoked by callbacks for the DanmakuOverlay.
+pub enum DanmakuOverlayEvents {
+    /// A chat message has been sent in the room.
+    Message(WebsocketMessage),
+
+    /// Launch due comments and clear finished ones.
+    Tick,
+}
+
+
+/// Niconico style bullet comments, chat messages scroll across the video
+/// in lanes picked so they don't overlap.
+///
+/// Messages are stored against the playback position they were sent at
+/// so rewatching the stream replays them at the same moments. Speed,
+/// density and opacity are set in the preferences.
+pub struct DanmakuOverlay {
+    _ws: WsHandler,
+    _tick: IntervalTask,
+    video: VideoElementHandle,
+    room_id: String,
+    stored: StoredComments,
+
+    /// The stored comments from earlier watches, ordered by position.
+    replay: Vec<TimedComment>,
+
+    /// The position replaying was last checked at.
+    last_position: Option<f64>,
+
+    active: Vec<ActiveComment>,
+
+    /// When each lane's entry is free for another comment.
+    lanes_free_at: Vec<f64>,
+    next_id: u64,
+}
+
+impl DanmakuOverlay {
+    fn load(room_id: &str, stream_url: &str) -> StoredComments {
+        load_local::<StoredComments>(&danmaku_key(room_id))
+            .filter(|stored| stored.stream_url == stream_url)
+            .unwrap_or_else(|| StoredComments {
+                stream_url: stream_url.to_string(),
+                comments: vec![],
+            })
+    }
+
+    fn reset(&mut self, stream_url: &str) {
+        self.stored = Self::load(&self.room_id, stream_url);
+        self.replay = self.stored.comments.clone();
+        self.replay.sort_by(|a, b| a.position.total_cmp(&b.position));
+        self.last_position = None;
+        self.active.clear();
+    }
+
+    /// Starts scrolling the comment in the first lane free for it, the
+    /// comment is dropped if every lane is busy.
+    fn launch(&mut self, content: String, prefs: &Preferences) {
+        let lanes = prefs.danmaku_lanes.max(1) as usize;
+        self.lanes_free_at.resize(lanes, 0.0);
+
+        let now = js_sys::Date::now();
+        let lane = match self.lanes_free_at.iter().position(|free_at| *free_at <= now) {
+            Some(lane) => lane,
+            None => return,
+        };
+
+        let duration_ms = prefs.danmaku_speed_secs * 1_000.0;
+        let width = (content.chars().count() as f64 / CHARS_PER_WIDTH).min(1.0);
+        self.lanes_free_at[lane] = now + duration_ms * width + LANE_GAP_MS;
+
+        self.next_id += 1;
+        self.active.push(ActiveComment {
+            id: self.next_id,
+            content,
+            lane,
+            started_at: now,
+            duration_ms,
+        });
+    }
+
+    /// Launches the stored comments the playhead has passed since the
+    /// last tick.
+    fn replay_due(&mut self, prefs: &Preferences) {
+        let position = match PlayerState::read(&self.video) {
+            Some(state) if !state.paused => state.position,
+            _ => return,
+        };
+
+        let last = match self.last_position.replace(position) {
+            Some(last) if position >= last && position - last < 2.0 => last,
+            _ => return,
+        };
+
+        let due: Vec<String> = self.replay
+            .iter()
+            .filter(|comment| comment.position > last && comment.position <= position)
+            .map(|comment| comment.content.clone())
+            .collect();
+
+        for content in due {
+            self.launch(content, prefs);
+        }
+    }
+}
+
+impl Component for DanmakuOverlay {
+    type Message = DanmakuOverlayEvents;
+    type Properties = DanmakuOverlayProperties;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let ws = props.ws;
+        let cb = link.callback(DanmakuOverlayEvents::Message);
+        ws.subscribe_to_message(settings::DANMAKU_ID, opcodes::OP_MESSAGE, cb);
+
+        let tick = IntervalService::spawn(
+            Duration::from_millis(TICK_MS),
+            link.callback(|_| DanmakuOverlayEvents::Tick),
+        );
+
+        let mut overlay = Self {
+            _ws: ws,
+            _tick: tick,
+            video: props.video,
+            room_id: props.room_id,
+            stored: StoredComments::default(),
+            replay: vec![],
+            last_position: None,
+            active: vec![],
+            lanes_free_at: vec![],
+            next_id: 0,
+        };
+
+        overlay.reset(&props.stream_url);
+        overlay
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        let prefs = Preferences::load();
+        if !prefs.danmaku {
+            let shown = !self.active.is_empty();
+            self.active.clear();
+            return shown;
+        }
+
+        match msg {
+            DanmakuOverlayEvents::Message(msg) => {
+                let message = match msg.unwrap_and_into::<DanmakuMessage>() {
+                    Some(message) => message,
+                    None => return false,
+                };
+
+                if let Some(state) = PlayerState::read(&self.video) {
+                    self.stored.comments.push(TimedComment {
+                        position: state.position,
+                        content: message.content.clone(),
+                    });
+
+                    let excess = self.stored.comments.len().saturating_sub(MAX_STORED_COMMENTS);
+                    self.stored.comments.drain(..excess);
+                    store_local(&danmaku_key(&self.room_id), &self.stored);
+                }
+
+                self.launch(message.content, &prefs);
+            },
+            DanmakuOverlayEvents::Tick => {
+                let before = self.active.len();
+                self.replay_due(&prefs);
+
+                let now = js_sys::Date::now();
+                self.active.retain(|comment| now - comment.started_at < comment.duration_ms);
+
+                if before == 0 && self.active.is_empty() {
+                    return false;
+                }
+            },
+        }
+
+        true
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.stored.stream_url != props.stream_url {
+            self.room_id = props.room_id;
+            self.reset(&props.stream_url);
+            return true;
+        }
+
+        false
+    }
+
+    fn view(&self) -> Html {
+        if self.active.is_empty() {
+            return html!{};
+        }
+
+        let prefs = Preferences::load();
+        let lane_height = 100.0 / f64::from(prefs.danmaku_lanes.max(1));
+
+        let comments = self.active.iter().map(|comment| {
+            let style = format!(
+                "top: {}%; opacity: {}; animation-duration: {}ms; text-shadow: 1px 1px 2px black;",
+                comment.lane as f64 * lane_height,
+                prefs.danmaku_opacity,
+                comment.duration_ms,
+            );
+
+            html! {
+                <span key=comment.id.to_string() class="danmaku absolute whitespace-nowrap text-white text-lg font-bold" data-style=style>
+                    { &comment.content }
+                </span>
+            }
+        });
+
+        html! {
+            <div class="absolute inset-0 overflow-hidden pointer-events-none z-10">
+                { for comments }
+            </div>
+        }
+    }
+}
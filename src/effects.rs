@@ -0,0 +1,404 @@
+use yew::prelude::*;
+use yew::services::{ConsoleService, TimeoutService};
+use yew::services::timeout::TimeoutTask;
+
+use serde::{Serialize, Deserialize};
+use std::time::Duration;
+use wasm_bindgen::JsValue;
+use yew::web_sys::Element;
+
+use crate::binder;
+use crate::controls::PlayerState;
+use crate::opcodes;
+use crate::prefs::Preferences;
+use crate::settings;
+use crate::utils::{start_future, emit_event};
+use crate::video::VideoElementHandle;
+use crate::websocket::{WsHandler, WebsocketMessage, WrappingWsMessage};
+
+
+/// How long the confetti runs for when the room is boosted.
+const BOOST_CONFETTI_SECS: u64 = 5;
+
+/// How long a celebration runs for when the host doesn't say.
+const CELEBRATION_SECS: u64 = 5;
+
+/// The emoji sets the host can celebrate with.
+const CELEBRATION_OPTIONS: [(&str, &[&str]); 4] = [
+    ("Party", &["🎉", "🥳", "🎊"]),
+    ("Love", &["❤️", "😍", "💖"]),
+    ("Laughs", &["😂", "🤣", "😹"]),
+    ("Victory", &["🏆", "⭐", "🔥"]),
+];
+
+
+#[derive(Properties, Clone)]
+pub struct EffectsOverlayProperties {
+    /// The room websocket handle.
+    pub ws: WsHandler,
+
+    /// The player the effects are shown over.
+    pub video: VideoElementHandle,
+}
+
+
+/// The overlay effects that can be shown over the player.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Effect {
+    /// Stops any running effect.
+    None,
+
+    /// Falling snow particles.
+    Snow,
+
+    /// A burst of confetti.
+    Confetti,
+
+    /// Dims the edges of the player like cinema lights.
+    Vignette,
+
+    /// Emoji raining down for a celebration, only started by OP_CELEBRATE.
+    #[serde(skip_deserializing)]
+    Celebration,
+}
+
+impl Effect {
+    /// The name the js effects engine knows the effect by, None if the
+    /// effect is not drawn on the canvas.
+    fn canvas_name(self) -> Option<&'static str> {
+        match self {
+            Effect::Snow => Some("snow"),
+            Effect::Confetti => Some("confetti"),
+            Effect::None | Effect::Vignette | Effect::Celebration => None,
+        }
+    }
+}
+
+
+/// An effect triggered by the host or the server.
+#[derive(Deserialize)]
+struct EffectEvent {
+    effect: Effect,
+
+    /// How long the effect runs for, None runs it until the next event.
+    #[serde(default)]
+    duration_secs: Option<u64>,
+}
+
+
+/// A celebration triggered by the host for finales and milestones.
+#[derive(Serialize, Deserialize)]
+struct CelebrationEvent {
+    /// The emoji rained over the player.
+    emojis: Vec<String>,
+
+    /// How long the celebration runs for.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    duration_secs: Option<u64>,
+}
+
+
+/// The events that can be invoked by callbacks for the EffectsOverlay.
+pub enum EffectsOverlayEvents {
+    /// An effect has been triggered.
+    Effect(WebsocketMessage),
+
+    /// The host has started a celebration.
+    Celebrate(WebsocketMessage),
+
+    /// The room has been boosted, a milestone worth some confetti.
+    Boost,
+
+    /// The running effect's duration has elapsed.
+    Stop,
+}
+
+
+/// Renders host and server triggered effects on a canvas layered above
+/// the player.
+///
+/// Nothing is drawn if the user has disabled effects, performance mode
+/// draws fewer particles at a lower frame rate and users who prefer
+/// reduced motion never see particle effects at all.
+///
+/// Celebrations rain emoji with a chime, the chime is skipped while the
+/// player is muted and users who prefer reduced motion get the emoji as
+/// a still banner instead.
+pub struct EffectsOverlay {
+    link: ComponentLink<Self>,
+    _ws: WsHandler,
+    video: VideoElementHandle,
+
+    /// The canvas the particle effects are drawn on.
+    canvas: NodeRef,
+
+    effect: Effect,
+    stop_task: Option<TimeoutTask>,
+
+    /// The emoji shown still when the celebration can't be animated.
+    still_emojis: Option<String>,
+}
+
+impl EffectsOverlay {
+    fn start(&mut self, effect: Effect, duration_secs: Option<u64>) {
+        binder::stop_effect();
+        self.effect = Effect::None;
+        self.stop_task = None;
+        self.still_emojis = None;
+
+        let prefs = Preferences::load();
+        if !prefs.effects_enabled || effect == Effect::None {
+            return;
+        }
+
+        if let Some(name) = effect.canvas_name() {
+            let canvas = match self.canvas.cast::<Element>() {
+                Some(canvas) => canvas,
+                None => return,
+            };
+
+            if !binder::start_effect(&canvas, name, prefs.performance_mode) {
+                return;
+            }
+        }
+
+        self.effect = effect;
+        self.stop_task = duration_secs.map(|secs| {
+            TimeoutService::spawn(
+                Duration::from_secs(secs),
+                self.link.callback(|_| EffectsOverlayEvents::Stop),
+            )
+        });
+    }
+
+    fn celebrate(&mut self, event: CelebrationEvent) {
+        self.start(Effect::None, None);
+
+        let prefs = Preferences::load();
+        if !prefs.effects_enabled || event.emojis.is_empty() {
+            return;
+        }
+
+        let emojis: Box<[JsValue]> = event.emojis
+            .iter()
+            .map(|emoji| JsValue::from_str(emoji))
+            .collect();
+
+        let animated = match self.canvas.cast::<Element>() {
+            Some(canvas) => binder::start_emoji_rain(&canvas, emojis, prefs.performance_mode),
+            None => false,
+        };
+
+        if !animated {
+            self.still_emojis = Some(event.emojis.join(" "));
+        }
+
+        let audible = PlayerState::read(&self.video).is_some_and(|state| !state.muted && state.volume > 0.0);
+        if audible {
+            binder::play_celebration_sound();
+        }
+
+        let secs = event.duration_secs.unwrap_or(CELEBRATION_SECS);
+        self.effect = Effect::Celebration;
+        self.stop_task = Some(TimeoutService::spawn(
+            Duration::from_secs(secs),
+            self.link.callback(|_| EffectsOverlayEvents::Stop),
+        ));
+    }
+}
+
+impl Component for EffectsOverlay {
+    type Message = EffectsOverlayEvents;
+    type Properties = EffectsOverlayProperties;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let effect_cb = link.callback(EffectsOverlayEvents::Effect);
+        let celebrate_cb = link.callback(EffectsOverlayEvents::Celebrate);
+        let boost_cb = link.callback(|_| EffectsOverlayEvents::Boost);
+
+        let ws = props.ws;
+        ws.subscribe_to_message(settings::EFFECTS_ID, opcodes::OP_EFFECT, effect_cb);
+        ws.subscribe_to_message(settings::EFFECTS_ID, opcodes::OP_CELEBRATE, celebrate_cb);
+        ws.subscribe_to_message(settings::EFFECTS_ID, opcodes::OP_BOOST, boost_cb);
+
+        Self {
+            link,
+            _ws: ws,
+            video: props.video,
+            canvas: NodeRef::default(),
+            effect: Effect::None,
+            stop_task: None,
+            still_emojis: None,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            EffectsOverlayEvents::Effect(msg) => {
+                if let Some(event) = msg.unwrap_and_into::<EffectEvent>() {
+                    self.start(event.effect, event.duration_secs);
+                } else {
+                    ConsoleService::warn("Received effect event without a valid payload");
+                    return false;
+                }
+            },
+            EffectsOverlayEvents::Celebrate(msg) => {
+                if let Some(event) = msg.unwrap_and_into::<CelebrationEvent>() {
+                    self.celebrate(event);
+                } else {
+                    ConsoleService::warn("Received celebration event without a valid payload");
+                    return false;
+                }
+            },
+            EffectsOverlayEvents::Boost => {
+                // Don't interrupt a longer running effect for a milestone.
+                if self.effect == Effect::None {
+                    self.start(Effect::Confetti, Some(BOOST_CONFETTI_SECS));
+                }
+            },
+            EffectsOverlayEvents::Stop => {
+                self.start(Effect::None, None);
+            },
+        }
+
+        true
+    }
+
+    fn change(&mut self, _props: Self::Properties) -> ShouldRender {
+        false
+    }
+
+    fn view(&self) -> Html {
+        let vignette = if self.effect == Effect::Vignette {
+            html! {
+                <div
+                    class="vignette absolute inset-0 rounded-lg">
+                </div>
+            }
+        } else {
+            html!{}
+        };
+
+        let still = match self.still_emojis.as_ref() {
+            Some(emojis) => html! {
+                <div class="absolute inset-0 flex items-center justify-center">
+                    <span class="text-6xl">{ emojis }</span>
+                </div>
+            },
+            None => html!{},
+        };
+
+        html! {
+            <div class="absolute inset-0 pointer-events-none z-10">
+                <canvas ref=self.canvas.clone() class="w-full h-full"></canvas>
+                { vignette }
+                { still }
+            </div>
+        }
+    }
+}
+
+
+#[derive(Properties, Clone)]
+pub struct CelebratorProperties {
+    /// The room id.
+    pub room_id: String,
+}
+
+
+/// The events that can be invoked by callbacks for the Celebrator.
+pub enum CelebratorEvents {
+    /// The emoji picker has been opened or closed.
+    Toggle,
+
+    /// The host has picked one of the celebration emoji sets.
+    Celebrate(usize),
+}
+
+
+/// The host's button for starting a celebration over every member's
+/// screen, only rendered for the host.
+pub struct Celebrator {
+    link: ComponentLink<Self>,
+    room_id: String,
+    open: bool,
+}
+
+impl Component for Celebrator {
+    type Message = CelebratorEvents;
+    type Properties = CelebratorProperties;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        Self {
+            link,
+            room_id: props.room_id,
+            open: false,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            CelebratorEvents::Toggle => self.open = !self.open,
+            CelebratorEvents::Celebrate(index) => {
+                let (_, emojis) = match CELEBRATION_OPTIONS.get(index) {
+                    Some(option) => option,
+                    None => return false,
+                };
+
+                let event = CelebrationEvent {
+                    emojis: emojis.iter().map(|emoji| emoji.to_string()).collect(),
+                    duration_secs: None,
+                };
+
+                let payload = WrappingWsMessage {
+                    opcode: opcodes::OP_CELEBRATE,
+                    payload: serde_json::to_value(event).ok(),
+                    mirror: false,
+                };
+                start_future(emit_event(self.room_id.clone(), payload));
+
+                self.open = false;
+            },
+        }
+
+        true
+    }
+
+    fn change(&mut self, _props: Self::Properties) -> ShouldRender {
+        false
+    }
+
+    fn view(&self) -> Html {
+        let toggle_cb = self.link.callback(|_| CelebratorEvents::Toggle);
+
+        let picker = if self.open {
+            let options = CELEBRATION_OPTIONS.iter().enumerate().map(|(i, (name, emojis))| {
+                let celebrate_cb = self.link.callback(move |_| CelebratorEvents::Celebrate(i));
+
+                html! {
+                    <button onclick=celebrate_cb class="block w-full text-left text-white text-sm px-2 py-1 hover:bg-gray-700 focus:outline-none">
+                        { format!("{} {}", emojis.join(""), name) }
+                    </button>
+                }
+            });
+
+            html! {
+                <div class="absolute right-0 mt-2 w-40 bg-gray-800 border-2 border-gray-700 rounded-lg py-1 z-10">
+                    { for options }
+                </div>
+            }
+        } else {
+            html!{}
+        };
+
+        html! {
+            <div class="relative flex items-center mx-2">
+                <button onclick=toggle_cb class="text-white text-lg focus:outline-none" title="Celebrate">
+                    { "🎉" }
+                </button>
+                { picker }
+            </div>
+        }
+    }
+}
@@ -0,0 +1,227 @@
+use yew::prelude::*;
+use yew::web_sys::Element;
+use wasm_bindgen::closure::Closure;
+
+use crate::auth::JoinCodePrompt;
+use crate::binder;
+use crate::chat::ChatRoom;
+use crate::closed::{self, ClosedScreen, RoomClosed};
+use crate::opcodes;
+use crate::player::MediaPlayer;
+use crate::rules::{self, RulesScreen};
+use crate::settings;
+use crate::utils::{query_params, send_future, start_at_param};
+use crate::video::VideoElementHandle;
+use crate::websocket::{WsHandler, WebsocketMessage};
+
+
+/// The embed's options, read from the query string of the page e.g.
+/// `/static/embed.html?room=1234&chat=0&origin=https://partner.example`.
+pub struct EmbedConfig {
+    /// The room to join.
+    pub room_id: String,
+
+    /// If chat is shown next to the player.
+    pub chat: bool,
+
+    /// The origin of the host page, the bridge only accepts control
+    /// messages from and posts events to it. None runs the embed read
+    /// only without a bridge, as there is no one to trust.
+    pub origin: Option<String>,
+
+    /// Where to start playback in seconds, from `t` as in room links.
+    pub start_at: Option<f64>,
+}
+
+impl EmbedConfig {
+    /// Reads the config from the page's query string, None if no room is
+    /// given.
+    pub fn from_query() -> Option<Self> {
+        let mut room_id = None;
+        let mut chat = true;
+        let mut origin = None;
+
+        for (key, value) in query_params() {
+            match key.as_str() {
+                "room" if !value.is_empty() => room_id = Some(value),
+                "chat" => chat = value != "0" && value != "false",
+                // A wildcard would let any page drive the room.
+                "origin" if !value.is_empty() && value != "*" => origin = Some(value),
+                _ => {},
+            }
+        }
+
+        Some(Self {
+            room_id: room_id?,
+            chat,
+            origin,
+            start_at: start_at_param(),
+        })
+    }
+}
+
+
+/// The events that can be invoked by callbacks for the EmbedRoom.
+pub enum EmbedRoomEvents {
+    /// The room was closed while we were in it.
+    RoomClosed(WebsocketMessage),
+
+    /// The result of checking the room still exists on load.
+    Checked(Option<RoomClosed>),
+
+    /// The room's rules have been fetched, None if it has none.
+    Rules(Option<String>),
+
+    /// The user has acknowledged the room's rules.
+    AcceptRules,
+}
+
+
+/// A minimal player and chat for partners to iframe on their own sites.
+///
+/// The host page controls the player and hears about playback and size
+/// changes through the postMessage bridge, see `src/js/embed.js`.
+///
+/// Like the room page, the embed asks for the join code of protected
+/// rooms, shows the rules before the player loads and replaces itself
+/// with the closed screen if the room closes.
+pub struct EmbedRoom {
+    link: ComponentLink<Self>,
+    ws: WsHandler,
+    config: EmbedConfig,
+    closed: Option<RoomClosed>,
+    video: VideoElementHandle,
+
+    /// Gives the bridge the player's element once it's on the page.
+    js_player: Closure<dyn FnMut() -> Option<Element>>,
+
+    /// If the room's rules are still being fetched.
+    rules_pending: bool,
+
+    /// The rules the user is yet to acknowledge.
+    rules: Option<String>,
+}
+
+impl Component for EmbedRoom {
+    type Message = EmbedRoomEvents;
+    type Properties = ();
+
+    fn create(_props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        // The entry point only mounts the embed once a room is given.
+        let config = EmbedConfig::from_query().expect("embed mounted without a room");
+        let ws = WsHandler::connect(settings::get_ws_url(&config.room_id));
+
+        let cb = link.callback(EmbedRoomEvents::RoomClosed);
+        ws.subscribe_to_message(settings::ROOM_ID, opcodes::OP_ROOM_CLOSED, cb);
+
+        let room_id = config.room_id.clone();
+        send_future(link.clone(), async move {
+            EmbedRoomEvents::Checked(closed::check_room_closed(room_id).await)
+        });
+
+        let room_id = config.room_id.clone();
+        send_future(link.clone(), async move {
+            EmbedRoomEvents::Rules(rules::fetch_rules(room_id).await)
+        });
+
+        let video = VideoElementHandle::default();
+        let js_player = video.element_getter();
+
+        Self {
+            link,
+            ws,
+            config,
+            closed: None,
+            video,
+            js_player,
+            rules_pending: true,
+            rules: None,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        let closed = match msg {
+            EmbedRoomEvents::RoomClosed(msg) => msg
+                .unwrap_and_into::<RoomClosed>()
+                .unwrap_or_default(),
+            EmbedRoomEvents::Checked(Some(closed)) => closed,
+            EmbedRoomEvents::Checked(None) => return false,
+            EmbedRoomEvents::Rules(rules) => {
+                self.rules_pending = false;
+                let room_id = &self.config.room_id;
+                self.rules = rules.filter(|rules| !rules::is_acknowledged(room_id, rules));
+                return true;
+            },
+            EmbedRoomEvents::AcceptRules => {
+                if let Some(rules) = self.rules.take() {
+                    rules::acknowledge(&self.config.room_id, &rules);
+                }
+                return true;
+            },
+        };
+
+        if self.closed.is_some() {
+            return false;
+        }
+
+        self.ws.close();
+        self.closed = Some(closed);
+
+        true
+    }
+
+    fn change(&mut self, _props: Self::Properties) -> ShouldRender {
+        false
+    }
+
+    fn rendered(&mut self, first_render: bool) {
+        if !first_render {
+            return;
+        }
+
+        if let Some(origin) = self.config.origin.as_deref() {
+            binder::start_embed_bridge(origin, &self.js_player);
+        }
+    }
+
+    fn view(&self) -> Html {
+        if let Some(closed) = self.closed.as_ref() {
+            return html! {
+                <ClosedScreen closed=closed.clone() />
+            };
+        }
+
+        let player = if self.rules_pending {
+            html!{}
+        } else if let Some(rules) = self.rules.as_ref() {
+            let accept_cb = self.link.callback(|_| EmbedRoomEvents::AcceptRules);
+            html! {
+                <RulesScreen rules=rules.clone() on_accept=accept_cb />
+            }
+        } else {
+            html! {
+                <MediaPlayer
+                    ws=self.ws.clone()
+                    room_id=self.config.room_id.clone()
+                    start_at=self.config.start_at
+                    minimal=true
+                    video=self.video.clone()
+                />
+            }
+        };
+
+        let chat = if self.config.chat {
+            html! { <ChatRoom ws=self.ws.clone() room_id=self.config.room_id.clone() video=self.video.clone() /> }
+        } else {
+            html!{}
+        };
+
+        html! {
+            <div class="flex justify-around">
+                { player }
+                { chat }
+                <JoinCodePrompt ws=self.ws.clone() />
+            </div>
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::collections::VecDeque;
+
+
+/// The window messages are counted over in milliseconds.
+const WINDOW_MS: f64 = 1_000.0;
+
+/// The most messages a user can send within the window before they are
+/// considered to be flooding.
+const MAX_PER_WINDOW: usize = 3;
+
+/// How long a user stays marked as flooding after their last message.
+const COOLDOWN_MS: f64 = 10_000.0;
+
+
+/// Tracks the rate of messages per user to detect chat floods.
+///
+/// This is purely a client side defence to keep raids from destroying the
+/// chat render loop, the gateway is still responsible for actually
+/// moderating users.
+#[derive(Default)]
+pub struct FloodDetector {
+    /// The timestamps of each user's recent messages.
+    recent: FxHashMap<String, VecDeque<f64>>,
+
+    /// The users currently considered to be flooding.
+    flooding: FxHashSet<String>,
+}
+
+impl FloodDetector {
+    /// Records a message from the user at the given time, returning the
+    /// state of the user after the message.
+    pub fn record(&mut self, user: &str, now_ms: f64) -> FloodState {
+        let times = self.recent
+            .entry(user.to_string())
+            .or_default();
+
+        let last = times.back().copied();
+        times.push_back(now_ms);
+
+        while let Some(oldest) = times.front() {
+            if now_ms - oldest > WINDOW_MS {
+                times.pop_front();
+            } else {
+                break;
+            }
+        }
+
+        let over_limit = times.len() > MAX_PER_WINDOW;
+        let cooled_down = last.map(|t| now_ms - t > COOLDOWN_MS).unwrap_or(true);
+
+        if over_limit {
+            if self.flooding.insert(user.to_string()) {
+                return FloodState::Started;
+            }
+            return FloodState::Flooding;
+        }
+
+        if self.flooding.contains(user) {
+            if cooled_down {
+                self.flooding.remove(user);
+                return FloodState::Normal;
+            }
+            return FloodState::Flooding;
+        }
+
+        FloodState::Normal
+    }
+}
+
+
+/// The state of a user after recording a message.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FloodState {
+    /// The user is chatting normally.
+    Normal,
+
+    /// The user has just started flooding.
+    Started,
+
+    /// The user is still flooding.
+    Flooding,
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{FloodDetector, FloodState, COOLDOWN_MS, MAX_PER_WINDOW, WINDOW_MS};
+
+    /// Records `count` messages from the user spaced `gap_ms` apart from
+    /// `start_ms`, returning the state after each one.
+    fn burst(
+        detector: &mut FloodDetector,
+        user: &str,
+        start_ms: f64,
+        gap_ms: f64,
+        count: usize,
+    ) -> Vec<FloodState> {
+        (0..count)
+            .map(|i| detector.record(user, start_ms + i as f64 * gap_ms))
+            .collect()
+    }
+
+    #[test]
+    fn messages_up_to_the_limit_are_normal() {
+        let mut detector = FloodDetector::default();
+        let states = burst(&mut detector, "raider", 0.0, 10.0, MAX_PER_WINDOW);
+        assert!(states.iter().all(|state| *state == FloodState::Normal));
+    }
+
+    #[test]
+    fn exceeding_the_limit_starts_flooding_once() {
+        let mut detector = FloodDetector::default();
+        let states = burst(&mut detector, "raider", 0.0, 10.0, MAX_PER_WINDOW + 3);
+
+        assert_eq!(states[MAX_PER_WINDOW], FloodState::Started);
+        assert!(states[MAX_PER_WINDOW + 1..].iter().all(|state| *state == FloodState::Flooding));
+    }
+
+    #[test]
+    fn messages_spread_past_the_window_are_normal() {
+        let mut detector = FloodDetector::default();
+        let gap = WINDOW_MS / MAX_PER_WINDOW as f64 + 1.0;
+        let states = burst(&mut detector, "chatty", 0.0, gap, MAX_PER_WINDOW * 4);
+        assert!(states.iter().all(|state| *state == FloodState::Normal));
+    }
+
+    #[test]
+    fn flooding_lasts_until_the_user_cools_down() {
+        let mut detector = FloodDetector::default();
+        burst(&mut detector, "raider", 0.0, 10.0, MAX_PER_WINDOW + 1);
+        let last = 10.0 * MAX_PER_WINDOW as f64;
+
+        // Slow messages don't clear the flag while inside the cooldown.
+        let slow = last + WINDOW_MS * 2.0;
+        assert_eq!(detector.record("raider", slow), FloodState::Flooding);
+
+        assert_eq!(detector.record("raider", slow + COOLDOWN_MS + 1.0), FloodState::Normal);
+        assert_eq!(detector.record("raider", slow + COOLDOWN_MS * 2.0 + 2.0), FloodState::Normal);
+    }
+
+    #[test]
+    fn users_are_tracked_separately() {
+        let mut detector = FloodDetector::default();
+        burst(&mut detector, "raider", 0.0, 10.0, MAX_PER_WINDOW + 1);
+        assert_eq!(detector.record("bystander", 50.0), FloodState::Normal);
+        assert_eq!(detector.record("raider", 60.0), FloodState::Flooding);
+    }
+}
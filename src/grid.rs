@@ -0,0 +1,184 @@
+use yew::prelude::*;
+use yew::services::IntervalService;
+use yew::services::interval::IntervalTask;
+
+use serde::Deserialize;
+use std::time::Duration;
+
+use crate::controls::PlayerState;
+use crate::streaming::StreamKind;
+use crate::video::VideoElementHandle;
+
+
+/// How often the secondary video follows the main player's play state.
+const FOLLOW_INTERVAL_MS: u64 = 1000;
+
+
+/// A second stream watched next to the main one in grid mode, e.g. the
+/// host's reaction cam.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SecondaryStream {
+    pub url: String,
+
+    /// The MIME type of the stream, if unset it is guessed from the url.
+    #[serde(default)]
+    pub content_type: Option<String>,
+
+    /// What the stream is, shown above the video.
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+
+#[derive(Properties, Clone)]
+pub struct SecondaryVideoProperties {
+    pub stream: SecondaryStream,
+
+    /// The main player, followed for its play state.
+    pub main: VideoElementHandle,
+}
+
+/// The events that can be invoked by callbacks for the SecondaryVideo.
+pub enum SecondaryVideoEvents {
+    /// Check the main player's state to follow it.
+    Follow,
+
+    /// The volume slider has been moved.
+    Volume(f64),
+}
+
+
+/// A video playing next to the main player in grid mode.
+///
+/// It has its own player and volume, but plays and pauses along with the
+/// main player so the room stays in sync. It never reports anything to the
+/// room itself, only the main player does that.
+pub struct SecondaryVideo {
+    link: ComponentLink<Self>,
+    _follow: IntervalTask,
+    stream: SecondaryStream,
+    main: VideoElementHandle,
+
+    /// This stream's own player, separate from the main one.
+    video: VideoElementHandle,
+
+    /// The url the player was last created for.
+    player_src: Option<String>,
+
+    /// If the main player was paused when last checked.
+    paused: Option<bool>,
+
+    /// The volume from 0 to 1, this starts muted so the main stream's
+    /// audio isn't drowned out.
+    volume: f64,
+}
+
+impl Component for SecondaryVideo {
+    type Message = SecondaryVideoEvents;
+    type Properties = SecondaryVideoProperties;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let follow = IntervalService::spawn(
+            Duration::from_millis(FOLLOW_INTERVAL_MS),
+            link.callback(|_| SecondaryVideoEvents::Follow),
+        );
+
+        Self {
+            link,
+            _follow: follow,
+            stream: props.stream,
+            main: props.main,
+            video: VideoElementHandle::default(),
+            player_src: None,
+            paused: None,
+            volume: 0.0,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        if self.player_src.is_none() {
+            return false;
+        }
+
+        match msg {
+            SecondaryVideoEvents::Follow => {
+                let paused = PlayerState::read(&self.main).map(|state| state.paused);
+                if paused.is_some() && paused != self.paused {
+                    self.paused = paused;
+                    self.video.set_paused(paused.unwrap_or(true));
+                }
+                false
+            },
+            SecondaryVideoEvents::Volume(volume) => {
+                self.volume = volume;
+                self.video.set_volume(volume);
+                true
+            },
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.stream == props.stream {
+            return false;
+        }
+
+        self.stream = props.stream;
+        true
+    }
+
+    fn rendered(&mut self, _first_render: bool) {
+        if self.player_src.as_ref() == Some(&self.stream.url) {
+            return;
+        }
+
+        let kind = StreamKind::detect(&self.stream.url, self.stream.content_type.as_deref());
+        if self.video.create_secondary(&self.stream.url, kind.mime_type()) {
+            self.video.set_volume(self.volume);
+            self.player_src = Some(self.stream.url.clone());
+            self.paused = None;
+        }
+    }
+
+    fn destroy(&mut self) {
+        if self.player_src.take().is_some() {
+            self.video.destroy();
+        }
+    }
+
+    fn view(&self) -> Html {
+        let volume_cb = self.link.callback(|e: InputData| {
+            SecondaryVideoEvents::Volume(e.value.parse().unwrap_or(0.0))
+        });
+
+        let label = self.stream.label.as_deref().unwrap_or("Second stream");
+
+        // The player is disposed of along with its element, the wrapper
+        // keeps Yew's own node around to detach.
+        html! {
+            <div class="flex flex-col w-1/3 ml-2">
+                <div class="flex justify-between items-center mb-1">
+                    <span class="text-white text-sm font-semibold truncate">{ label }</span>
+                    <input
+                        type="range"
+                        class="slider w-20"
+                        min="0"
+                        max="1"
+                        step="0.01"
+                        value=self.volume.to_string()
+                        oninput=volume_cb
+                        title="Volume of this stream only"
+                    />
+                </div>
+                <div>
+                    <video-js
+                        ref=self.video.element_ref()
+                        class="bg-gray-900 video-js vjs-live w-full rounded-lg"
+                        controls=false
+                        preload="auto"
+                        width="100%">
+                    </video-js>
+                </div>
+            </div>
+        }
+    }
+}
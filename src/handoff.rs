@@ -0,0 +1,337 @@
+use yew::prelude::*;
+use yew::services::ConsoleService;
+
+use reqwest::Client;
+use serde::{Serialize, Deserialize};
+use serde_json::json;
+
+use crate::controls::PlayerState;
+use crate::opcodes;
+use crate::prefs::Preferences;
+use crate::resume;
+use crate::settings;
+use crate::utils::send_future;
+use crate::video::VideoElementHandle;
+use crate::websocket::WsHandler;
+
+
+/// What the new device receives when claiming a handoff code.
+#[derive(Serialize, Deserialize)]
+pub struct Handoff {
+    room_id: String,
+
+    /// The url of the stream being played, empty if there is none.
+    #[serde(default)]
+    stream_url: String,
+
+    /// The position of the player in seconds.
+    position: f64,
+
+    /// The personal preferences of the old device.
+    preferences: Preferences,
+}
+
+
+#[derive(Deserialize)]
+struct HandoffCode {
+    code: String,
+}
+
+
+/// Creates a handoff code for the session.
+async fn create_code(room_id: String, handoff: Handoff) -> HandoffEvents {
+    let url = settings::get_handoff_url(&room_id);
+
+    let resp = Client::new()
+        .post(&url)
+        .json(&handoff)
+        .send()
+        .await;
+
+    let code = match resp {
+        Ok(resp) if resp.status().is_success() => resp.json::<HandoffCode>().await.ok(),
+        _ => None,
+    };
+
+    HandoffEvents::Created(code.map(|code| code.code))
+}
+
+
+/// Claims the handoff code, the device that created it is told by the
+/// gateway.
+async fn claim_code(code: String) -> HandoffEvents {
+    let url = settings::get_handoff_claim_url(&code);
+
+    let resp = Client::new()
+        .post(&url)
+        .send()
+        .await;
+
+    let handoff = match resp {
+        Ok(resp) if resp.status().is_success() => resp.json::<Handoff>().await.ok(),
+        _ => None,
+    };
+
+    HandoffEvents::Claimed(handoff)
+}
+
+
+#[derive(Properties, Clone)]
+pub struct HandoffButtonProperties {
+    /// The room websocket handle.
+    pub ws: WsHandler,
+
+    /// The room id.
+    pub room_id: String,
+
+    /// The url of the stream being played.
+    pub stream_url: String,
+
+    /// The player stopped once playback is handed off.
+    pub video: VideoElementHandle,
+}
+
+
+/// The events that can be invoked by callbacks for the HandoffButton.
+pub enum HandoffEvents {
+    /// The dialog has been opened or closed.
+    Toggle,
+
+    /// The user has asked for a code to continue elsewhere.
+    CreateCode,
+
+    /// The code has been created, None if the request failed.
+    Created(Option<String>),
+
+    /// The code being typed in on the new device has changed.
+    CodeInput(String),
+
+    /// The user has submitted a code from another device.
+    Claim,
+
+    /// The code has been claimed, None if it was invalid or expired.
+    Claimed(Option<Handoff>),
+
+    /// Another device has claimed our code.
+    HandedOff,
+
+    /// The user wants to keep watching here after handing off.
+    Reclaim,
+}
+
+
+/// The "continue on another device" flow.
+///
+/// The old device creates a short code carrying the room, position and
+/// preferences, the new device claims it and is taken to the room with
+/// the position offered to resume from. Once claimed the old device is
+/// muted, paused and marked idle so it quietly becomes a spectator.
+pub struct HandoffButton {
+    link: ComponentLink<Self>,
+    ws: WsHandler,
+    room_id: String,
+    stream_url: String,
+    video: VideoElementHandle,
+    open: bool,
+
+    /// Our code waiting to be claimed.
+    code: Option<String>,
+
+    /// The code typed in from another device.
+    code_input: String,
+
+    /// If this session has been continued elsewhere.
+    handed_off: bool,
+    error: Option<&'static str>,
+}
+
+impl HandoffButton {
+    /// Applies a claimed handoff and moves to its room.
+    fn apply(&self, handoff: Handoff) {
+        handoff.preferences.save();
+        if !handoff.stream_url.is_empty() {
+            resume::store_position(&handoff.room_id, &handoff.stream_url, handoff.position);
+        }
+
+        let url = format!("{}/{}", settings::get_room_url(), handoff.room_id);
+        if yew::utils::window().location().set_href(&url).is_err() {
+            ConsoleService::error("Failed to move to the handed off room");
+        }
+    }
+
+    fn view_dialog(&self) -> Html {
+        let close_cb = self.link.callback(|_| HandoffEvents::Toggle);
+        let create_cb = self.link.callback(|_| HandoffEvents::CreateCode);
+        let input_cb = self.link.callback(|e: InputData| HandoffEvents::CodeInput(e.value));
+        let claim_cb = self.link.callback(|_| HandoffEvents::Claim);
+
+        let code = match self.code.as_ref() {
+            Some(code) => html! {
+                <p class="text-gray-300 text-sm mb-4">
+                    { "Enter this code on your other device: " }
+                    <span class="text-white font-mono font-bold text-lg">{ code }</span>
+                </p>
+            },
+            None => html! {
+                <button onclick=create_cb class="bg-accent text-white font-semibold rounded-lg px-4 py-1 mb-4 focus:outline-none">
+                    { "Get a code" }
+                </button>
+            },
+        };
+
+        let error = match self.error {
+            Some(error) => html! { <p class="text-red-400 text-xs mb-2">{ error }</p> },
+            None => html!{},
+        };
+
+        html! {
+            <div class="fixed inset-0 flex justify-center items-center bg-black bg-opacity-50 z-40">
+                <div class="bg-discord-dark rounded-lg p-6 w-96">
+                    <h1 class="text-white font-bold text-lg mb-2">{ "Continue on another device" }</h1>
+                    { code }
+                    <h2 class="text-white text-sm font-bold mb-1">{ "Have a code from another device?" }</h2>
+                    <div class="flex mb-2">
+                        <input
+                            type="text"
+                            value=self.code_input.clone()
+                            oninput=input_cb
+                            placeholder="Code"
+                            class="bg-gray-900 text-white font-mono rounded-lg focus:outline-none flex-grow px-2 mr-2"
+                        />
+                        <button onclick=claim_cb class="text-white border-2 rounded-lg px-4 py-1 focus:outline-none">
+                            { "Continue" }
+                        </button>
+                    </div>
+                    { error }
+                    <div class="flex justify-end">
+                        <button onclick=close_cb class="text-white border-2 rounded-lg px-4 py-1 focus:outline-none">
+                            { "Close" }
+                        </button>
+                    </div>
+                </div>
+            </div>
+        }
+    }
+}
+
+impl Component for HandoffButton {
+    type Message = HandoffEvents;
+    type Properties = HandoffButtonProperties;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let ws = props.ws;
+        let cb = link.callback(|_| HandoffEvents::HandedOff);
+        ws.subscribe_to_message(settings::HANDOFF_ID, opcodes::OP_HANDOFF_CLAIMED, cb);
+
+        Self {
+            link,
+            ws,
+            room_id: props.room_id,
+            stream_url: props.stream_url,
+            video: props.video,
+            open: false,
+            code: None,
+            code_input: String::new(),
+            handed_off: false,
+            error: None,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            HandoffEvents::Toggle => {
+                self.open = !self.open;
+                self.error = None;
+            },
+            HandoffEvents::CreateCode => {
+                let handoff = Handoff {
+                    room_id: self.room_id.clone(),
+                    stream_url: self.stream_url.clone(),
+                    position: PlayerState::read(&self.video).map(|state| state.position).unwrap_or(0.0),
+                    preferences: Preferences::load(),
+                };
+                send_future(self.link.clone(), create_code(self.room_id.clone(), handoff));
+                return false;
+            },
+            HandoffEvents::Created(code) => {
+                if code.is_none() {
+                    self.error = Some("Failed to create a code, please try again later.");
+                }
+                self.code = code;
+            },
+            HandoffEvents::CodeInput(code) => {
+                self.code_input = code;
+                return false;
+            },
+            HandoffEvents::Claim => {
+                let code = self.code_input.trim().to_uppercase();
+                if code.is_empty() {
+                    return false;
+                }
+                send_future(self.link.clone(), claim_code(code));
+                return false;
+            },
+            HandoffEvents::Claimed(Some(handoff)) => self.apply(handoff),
+            HandoffEvents::Claimed(None) => {
+                self.error = Some("That code is invalid or has expired.");
+            },
+            HandoffEvents::HandedOff => {
+                self.handed_off = true;
+                self.open = false;
+                self.code = None;
+
+                self.video.fade_out(0);
+                self.video.set_muted(true);
+                self.ws.send(opcodes::OP_PRESENCE, Some(json!({ "idle": true })));
+            },
+            HandoffEvents::Reclaim => {
+                self.handed_off = false;
+                self.video.set_muted(false);
+                self.ws.send(opcodes::OP_PRESENCE, Some(json!({ "idle": false })));
+            },
+        }
+
+        true
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        self.room_id = props.room_id;
+        self.stream_url = props.stream_url;
+        false
+    }
+
+    fn view(&self) -> Html {
+        let toggle_cb = self.link.callback(|_| HandoffEvents::Toggle);
+        let dialog = if self.open { self.view_dialog() } else { html!{} };
+
+        let handed_off = if self.handed_off {
+            let reclaim_cb = self.link.callback(|_| HandoffEvents::Reclaim);
+
+            html! {
+                <div class="fixed inset-0 flex justify-center items-center bg-black bg-opacity-75 z-40">
+                    <div class="bg-discord-dark rounded-lg p-6 w-96 text-center">
+                        <h1 class="text-white font-bold text-lg mb-2">{ "Continued on another device" }</h1>
+                        <p class="text-gray-300 text-sm mb-4">
+                            { "This session was picked up on another device, playback here has been paused." }
+                        </p>
+                        <button onclick=reclaim_cb class="text-white border-2 rounded-lg px-4 py-1 focus:outline-none">
+                            { "Keep watching here" }
+                        </button>
+                    </div>
+                </div>
+            }
+        } else {
+            html!{}
+        };
+
+        html! {
+            <div class="flex items-center mx-2">
+                <button onclick=toggle_cb class="text-white text-sm border-2 rounded-lg px-2 focus:outline-none" title="Continue on another device">
+                    { "Handoff" }
+                </button>
+                { dialog }
+                { handed_off }
+            </div>
+        }
+    }
+}
@@ -0,0 +1,245 @@
+use yew::prelude::*;
+use yew::services::TimeoutService;
+use yew::services::timeout::TimeoutTask;
+
+use serde::{Serialize, Deserialize};
+use std::time::Duration;
+
+use crate::controls::PlayerState;
+use crate::opcodes;
+use crate::settings;
+use crate::utils::{start_future, emit_event};
+use crate::video::VideoElementHandle;
+use crate::websocket::{WsHandler, WebsocketMessage, WrappingWsMessage};
+
+
+/// How long after asking for a hold the button can be pressed again.
+const HOLD_COOLDOWN_SECS: u64 = 30;
+
+/// How long the notice of who asked for the hold is shown.
+const NOTICE_SECS: u64 = 10;
+
+
+/// Who can ask the room to hold on, set by the host in the room settings.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HoldPolicy {
+    /// No one, the button is hidden.
+    Disabled,
+
+    /// Every member.
+    #[default]
+    Everyone,
+
+    /// Only members with one of the Discord role ids.
+    Roles(Vec<String>),
+}
+
+impl HoldPolicy {
+    /// If a member with the given role ids can ask for a hold, the gateway
+    /// drops holds from anyone else regardless.
+    pub fn allows(&self, roles: &[String]) -> bool {
+        match self {
+            HoldPolicy::Disabled => false,
+            HoldPolicy::Everyone => true,
+            HoldPolicy::Roles(allowed) => roles.iter().any(|role| allowed.contains(role)),
+        }
+    }
+}
+
+
+/// A member asking the room to pause.
+#[derive(Serialize, Deserialize)]
+struct HoldEvent {
+    /// The position to pause at in seconds.
+    position: f64,
+
+    /// The name of the member, attached by the gateway.
+    #[serde(default, skip_serializing)]
+    username: Option<String>,
+}
+
+
+#[derive(Properties, Clone)]
+pub struct HoldButtonProperties {
+    /// The room websocket handle.
+    pub ws: WsHandler,
+
+    /// The room id.
+    pub room_id: String,
+
+    /// If the active user is allowed to ask for a hold.
+    pub allowed: bool,
+
+    /// If the user is watching privately, holds don't pause their player.
+    pub detached: bool,
+
+    /// The player paused for a hold.
+    pub video: VideoElementHandle,
+}
+
+
+/// The events that can be invoked by callbacks for the HoldButton.
+pub enum HoldButtonEvents {
+    /// The user has pressed the hold on button.
+    Press,
+
+    /// The user can ask for a hold again.
+    CooldownOver,
+
+    /// A member has asked the room to hold on.
+    Hold(WebsocketMessage),
+
+    /// The notice has been dismissed or its time is up.
+    HideNotice,
+}
+
+
+/// The "hold on!" button, any allowed member can pause the room for
+/// everyone, the room is shown who asked.
+///
+/// Pressing it is rate limited locally, the gateway applies its own limit.
+pub struct HoldButton {
+    link: ComponentLink<Self>,
+    _ws: WsHandler,
+    room_id: String,
+    allowed: bool,
+    detached: bool,
+    video: VideoElementHandle,
+
+    /// Set while the button can't be pressed again.
+    cooldown: Option<TimeoutTask>,
+
+    /// The name of the member who asked for the latest hold and the task
+    /// hiding the notice.
+    notice: Option<(String, TimeoutTask)>,
+}
+
+impl Component for HoldButton {
+    type Message = HoldButtonEvents;
+    type Properties = HoldButtonProperties;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let ws = props.ws;
+        let cb = link.callback(HoldButtonEvents::Hold);
+        ws.subscribe_to_message(settings::HOLD_ID, opcodes::OP_HOLD, cb);
+
+        Self {
+            link,
+            _ws: ws,
+            room_id: props.room_id,
+            allowed: props.allowed,
+            detached: props.detached,
+            video: props.video,
+            cooldown: None,
+            notice: None,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            HoldButtonEvents::Press => {
+                if !self.allowed || self.cooldown.is_some() {
+                    return false;
+                }
+
+                let position = PlayerState::read(&self.video).map(|s| s.position).unwrap_or(0.0);
+                let event = HoldEvent { position, username: None };
+                let msg = WrappingWsMessage {
+                    opcode: opcodes::OP_HOLD,
+                    payload: serde_json::to_value(event).ok(),
+                    mirror: false,
+                };
+                start_future(emit_event(self.room_id.clone(), msg));
+
+                if !self.detached {
+                    self.video.apply_remote("pause", position);
+                }
+
+                let task = TimeoutService::spawn(
+                    Duration::from_secs(HOLD_COOLDOWN_SECS),
+                    self.link.callback(|_| HoldButtonEvents::CooldownOver),
+                );
+                self.cooldown = Some(task);
+            },
+            HoldButtonEvents::CooldownOver => {
+                self.cooldown = None;
+            },
+            HoldButtonEvents::Hold(msg) => {
+                let event: HoldEvent = match msg.unwrap_and_into() {
+                    Some(event) => event,
+                    None => return false,
+                };
+
+                if !self.detached {
+                    self.video.apply_remote("pause", event.position);
+                }
+
+                let task = TimeoutService::spawn(
+                    Duration::from_secs(NOTICE_SECS),
+                    self.link.callback(|_| HoldButtonEvents::HideNotice),
+                );
+                let username = event.username.unwrap_or_else(|| "A member".to_string());
+                self.notice = Some((username, task));
+            },
+            HoldButtonEvents::HideNotice => {
+                self.notice = None;
+            },
+        }
+
+        true
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        let changed = self.allowed != props.allowed;
+        self.allowed = props.allowed;
+        self.detached = props.detached;
+        changed
+    }
+
+    fn view(&self) -> Html {
+        let notice = match self.notice.as_ref() {
+            Some((username, _)) => {
+                let dismiss_cb = self.link.callback(|_| HoldButtonEvents::HideNotice);
+
+                html! {
+                    <div class="animate-slide fixed top-0 flex justify-center w-full p-4">
+                        <div class="bg-discord-dark border-2 border-gray-200 rounded-lg py-4 px-8 flex items-center">
+                            <h1 class="text-white font-bold mr-4">
+                                { format!("{} asked everyone to hold on", username) }
+                            </h1>
+                            <button onclick=dismiss_cb class="text-white border-2 rounded-lg px-4 py-2 mx-2 focus:outline-none">
+                                { "Dismiss" }
+                            </button>
+                        </div>
+                    </div>
+                }
+            },
+            None => html!{},
+        };
+
+        let button = if self.allowed {
+            let press_cb = self.link.callback(|_| HoldButtonEvents::Press);
+            let cooling_down = self.cooldown.is_some();
+
+            html! {
+                <button
+                    onclick=press_cb
+                    disabled=cooling_down
+                    class=("bg-red-600 text-white font-semibold rounded-lg px-3 py-1 focus:outline-none", if cooling_down { "opacity-50" } else { "" })
+                    title="Pause the room for everyone">
+                    { "Hold on!" }
+                </button>
+            }
+        } else {
+            html!{}
+        };
+
+        html! {
+            <div class="flex justify-center items-center mx-2">
+                { button }
+                { notice }
+            </div>
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use yew::prelude::*;
+use yew::services::{IntervalService, TimeoutService, ConsoleService};
+use yew::services::interval::IntervalTask;
+use yew::services::timeout::TimeoutTask;
+
+use serde_json::json;
+use std::time::Duration;
+
+use crate::binder;
+use crate::opcodes;
+use crate::prefs::Preferences;
+use crate::video::VideoElementHandle;
+use crate::websocket::WsHandler;
+
+
+/// How often the playback and activity state is checked.
+const CHECK_INTERVAL_SECS: u64 = 60;
+
+/// How long the prompt waits for an answer before marking the user idle.
+const RESPONSE_SECS: u64 = 60;
+
+
+#[derive(Properties, Clone)]
+pub struct IdleCheckProperties {
+    /// The room websocket handle.
+    pub ws: WsHandler,
+
+    /// The room's player.
+    pub video: VideoElementHandle,
+}
+
+
+/// The events that can be invoked by callbacks for the IdleCheck.
+pub enum IdleCheckEvents {
+    /// The periodic activity check.
+    Tick,
+
+    /// The prompt went unanswered.
+    NoResponse,
+
+    /// The user confirmed they are still watching.
+    StillHere,
+}
+
+
+/// Asks "are you still watching?" after a long stretch of playback with no
+/// interaction on the page.
+///
+/// If the prompt goes unanswered the player is muted locally and the user
+/// is marked as idle in the room's presence, the room itself is never
+/// paused. The interval is configured in the preferences.
+pub struct IdleCheck {
+    link: ComponentLink<Self>,
+    ws: WsHandler,
+    video: VideoElementHandle,
+
+    _interval: IntervalTask,
+
+    /// The response timeout while the prompt is showing.
+    prompt: Option<TimeoutTask>,
+
+    /// If the user has been marked idle and muted.
+    idle: bool,
+}
+
+impl IdleCheck {
+    fn set_presence(&self, idle: bool) {
+        let payload = json!({ "idle": idle });
+        if !self.ws.send(opcodes::OP_PRESENCE, Some(payload)) {
+            ConsoleService::warn("Failed to send presence, socket is not open");
+        }
+    }
+}
+
+impl Component for IdleCheck {
+    type Message = IdleCheckEvents;
+    type Properties = IdleCheckProperties;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let interval = IntervalService::spawn(
+            Duration::from_secs(CHECK_INTERVAL_SECS),
+            link.callback(|_| IdleCheckEvents::Tick),
+        );
+
+        Self {
+            link,
+            ws: props.ws,
+            video: props.video,
+            _interval: interval,
+            prompt: None,
+            idle: false,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            IdleCheckEvents::Tick => {
+                if self.prompt.is_some() || self.idle {
+                    return false;
+                }
+
+                let hours = Preferences::load().idle_check_hours;
+                if hours == 0 || !self.video.is_playing() {
+                    return false;
+                }
+
+                let limit_ms = f64::from(hours) * 60.0 * 60.0 * 1000.0;
+                if binder::ms_since_activity() < limit_ms {
+                    return false;
+                }
+
+                let task = TimeoutService::spawn(
+                    Duration::from_secs(RESPONSE_SECS),
+                    self.link.callback(|_| IdleCheckEvents::NoResponse),
+                );
+                self.prompt = Some(task);
+            },
+            IdleCheckEvents::NoResponse => {
+                self.prompt = None;
+                self.idle = true;
+
+                self.video.set_muted(true);
+                self.set_presence(true);
+            },
+            IdleCheckEvents::StillHere => {
+                self.prompt = None;
+
+                if self.idle {
+                    self.idle = false;
+                    self.video.set_muted(false);
+                    self.set_presence(false);
+                }
+            },
+        }
+
+        true
+    }
+
+    fn change(&mut self, _props: Self::Properties) -> ShouldRender {
+        false
+    }
+
+    fn view(&self) -> Html {
+        if self.prompt.is_none() && !self.idle {
+            return html!{};
+        }
+
+        let here_cb = self.link.callback(|_| IdleCheckEvents::StillHere);
+
+        let msg = if self.idle {
+            "You've been muted while you were away."
+        } else {
+            "Are you still watching?"
+        };
+
+        html! {
+            <div class="fixed inset-0 flex justify-center items-center bg-black bg-opacity-75">
+                <div class="bg-discord-dark rounded-lg p-8 flex flex-col items-center">
+                    <h1 class="text-white font-bold text-2xl mb-4">{ msg }</h1>
+                    <button onclick=here_cb class="bg-blue-600 text-white font-semibold rounded-lg px-4 py-2 focus:outline-none">
+                        { "I'm still here" }
+                    </button>
+                </div>
+            </div>
+        }
+    }
+}
@@ -0,0 +1,45 @@
+let lastActivity = Date.now();
+
+["mousemove", "keydown", "pointerdown", "wheel", "touchstart"].forEach((name) => {
+    window.addEventListener(name, () => { lastActivity = Date.now() }, { passive: true });
+});
+
+
+export function msSinceActivity() {
+    return Date.now() - lastActivity
+}
+
+
+export function setAutoHide(element, delayMs) {
+    if (element.__autoHide) { return false }
+    element.__autoHide = true;
+
+    let timeout = null;
+    let show = () => {
+        element.classList.remove("chrome-hidden");
+        clearTimeout(timeout);
+
+        timeout = setTimeout(() => {
+            if (document.fullscreenElement === element) {
+                element.classList.add("chrome-hidden");
+            }
+        }, delayMs);
+    };
+
+    ["mousemove", "keydown", "pointerdown", "touchstart"].forEach((name) => {
+        element.addEventListener(name, show, { passive: true });
+    });
+    document.addEventListener("fullscreenchange", show);
+
+    return true
+}
+
+
+export function setVisibilityListener(onChange) {
+    document.addEventListener("visibilitychange", () => onChange(!document.hidden));
+}
+
+
+export function isPageVisible() {
+    return !document.hidden
+}
\ No newline at end of file
@@ -0,0 +1,68 @@
+const SAMPLE_WIDTH = 64;
+const SAMPLE_HEIGHT = 36;
+
+let ambient = null;
+let attached = null;
+
+
+function sample() {
+    if (ambient === null || document.hidden) { return }
+
+    let video = ambient.element.querySelector("video");
+    if (!video || video.readyState < 2) { return }
+
+    ambient.ctx.drawImage(video, 0, 0, SAMPLE_WIDTH, SAMPLE_HEIGHT);
+}
+
+
+function onVisibilityChange() {
+    if (ambient === null) { return }
+
+    // Stop sampling entirely while the tab is in the background.
+    clearInterval(ambient.interval);
+    ambient.interval = document.hidden ? null : setInterval(sample, ambient.intervalMs);
+}
+
+
+function stop() {
+    if (ambient === null) { return }
+
+    clearInterval(ambient.interval);
+    document.removeEventListener("visibilitychange", onVisibilityChange);
+    ambient.canvas.style.display = "none";
+    ambient = null;
+}
+
+
+export function attachAmbientCanvas(canvas) {
+    if (attached === canvas) { return }
+
+    stop();
+    attached = canvas;
+}
+
+
+export function setAmbientMode(element, enabled, intervalMs) {
+    stop();
+
+    if (!enabled) { return true }
+
+    let canvas = attached;
+    if (canvas === null) { return false }
+
+    canvas.width = SAMPLE_WIDTH;
+    canvas.height = SAMPLE_HEIGHT;
+    canvas.style.display = "block";
+
+    ambient = {
+        element,
+        canvas,
+        ctx: canvas.getContext("2d"),
+        intervalMs,
+        interval: setInterval(sample, intervalMs),
+    };
+    document.addEventListener("visibilitychange", onVisibilityChange);
+    sample();
+
+    return true
+}
\ No newline at end of file
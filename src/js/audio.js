@@ -0,0 +1,110 @@
+const MAX_DELAY_SECS = 2;
+const MAX_GAIN = 2;
+
+// The loudness streams are normalized to and the most they are turned
+// down or up by to get there.
+const TARGET_LUFS = -14;
+const MIN_NORMALIZE_GAIN = 0.25;
+const MAX_NORMALIZE_GAIN = 2;
+
+// The audio settings and graph of each player's element.
+let audioByElement = new WeakMap();
+
+
+function getVideoElement(element) {
+    return element.querySelector("video")
+}
+
+
+function audioOf(element) {
+    if (!audioByElement.has(element)) {
+        audioByElement.set(element, {
+            graph: null,
+            delaySecs: 0,
+            gainValue: 1,
+            loudness: null,
+            normalize: false,
+        });
+    }
+    return audioByElement.get(element)
+}
+
+
+// Routes the video's audio through a delay and gain node, once routed the
+// element can't be disconnected again so the graph is only built when needed.
+function buildGraph(video) {
+    let AudioContext = window.AudioContext || window.webkitAudioContext;
+    if (!AudioContext) { return null }
+
+    let context = new AudioContext();
+    let source = context.createMediaElementSource(video);
+    let delay = context.createDelay(MAX_DELAY_SECS);
+    let gain = context.createGain();
+
+    source.connect(delay);
+    delay.connect(gain);
+    gain.connect(context.destination);
+
+    // Autoplay policies start the context suspended until the page has
+    // been interacted with.
+    video.addEventListener("play", () => context.resume());
+
+    return { video, context, delay, gain }
+}
+
+
+function normalizeGain({ normalize, loudness }) {
+    if (!normalize || loudness === null) { return 1 }
+
+    let gain = Math.pow(10, (TARGET_LUFS - loudness) / 20);
+    return Math.min(Math.max(gain, MIN_NORMALIZE_GAIN), MAX_NORMALIZE_GAIN)
+}
+
+
+function applyGraph(element) {
+    let video = getVideoElement(element);
+    if (!video) { return false }
+
+    let audio = audioOf(element);
+    if (audio.graph === null || audio.graph.video !== video) {
+        if (audio.delaySecs <= 0 && audio.gainValue === 1 && normalizeGain(audio) === 1) { return true }
+
+        try {
+            audio.graph = buildGraph(video);
+        } catch (e) {
+            audio.graph = null;
+        }
+        if (audio.graph === null) { return false }
+    }
+
+    audio.graph.delay.delayTime.value = audio.delaySecs;
+    audio.graph.gain.gain.value = audio.gainValue * normalizeGain(audio);
+    audio.graph.context.resume();
+    return true
+}
+
+
+export function setAudioDelay(element, delayMs) {
+    audioOf(element).delaySecs = Math.min(delayMs / 1000, MAX_DELAY_SECS);
+    return applyGraph(element)
+}
+
+
+export function setAudioGain(element, gain) {
+    audioOf(element).gainValue = Math.min(Math.max(gain, 0), MAX_GAIN);
+    return applyGraph(element)
+}
+
+
+export function setStreamLoudness(element, lufs, enabled) {
+    let audio = audioOf(element);
+    audio.loudness = (lufs === undefined || !isFinite(lufs)) ? null : lufs;
+    audio.normalize = enabled;
+    return applyGraph(element)
+}
+
+
+export function setLoudnessNormalization(element, enabled) {
+    audioOf(element).normalize = enabled;
+    return applyGraph(element)
+}
\ No newline at end of file
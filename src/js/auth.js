@@ -0,0 +1,16 @@
+export async function hashJoinCode(nonce, code) {
+    let data = new TextEncoder().encode(nonce + ":" + code);
+    let digest = await crypto.subtle.digest("SHA-256", data);
+
+    return Array.from(new Uint8Array(digest))
+        .map((b) => b.toString(16).padStart(2, "0"))
+        .join("")
+}
+
+export function randomId() {
+    let bytes = crypto.getRandomValues(new Uint8Array(16));
+
+    return Array.from(bytes)
+        .map((b) => b.toString(16).padStart(2, "0"))
+        .join("")
+}
\ No newline at end of file
@@ -0,0 +1,48 @@
+// The video and availability watch id of each player's element.
+let watchedByElement = new WeakMap();
+
+
+function getVideoElement(element) {
+    return element.querySelector("video")
+}
+
+
+export function watchRemotePlayback(element, onState) {
+    let video = getVideoElement(element);
+    if (!video || !video.remote) { return false }
+
+    let watched = watchedByElement.get(element);
+    if (watched !== undefined && watched.video === video) { return true }
+
+    if (watched !== undefined) {
+        watched.video.remote.cancelWatchAvailability(watched.id).catch(() => {});
+    }
+
+    let report = () => onState(video.remote.state === "disconnected" ? "available" : video.remote.state);
+
+    watched = { video, id: null };
+    watchedByElement.set(element, watched);
+    video.remote.onconnecting = report;
+    video.remote.onconnect = report;
+    video.remote.ondisconnect = report;
+
+    video.remote.watchAvailability((available) => {
+        if (available) { report() } else { onState("unavailable") }
+    }).then((id) => {
+        watched.id = id;
+    }).catch(() => {
+        // The browser can't tell us when a device is around, e.g. while
+        // saving data, so leave it to the device picker.
+        report();
+    });
+
+    return true
+}
+
+
+export function promptRemotePlayback(element) {
+    let video = getVideoElement(element);
+    if (!video || !video.remote) { return }
+
+    video.remote.prompt().catch(() => {});
+}
\ No newline at end of file
@@ -0,0 +1,171 @@
+const CONFETTI_COLOURS = ["#EF4444", "#F59E0B", "#10B981", "#3B82F6", "#8B5CF6", "#EC4899"];
+
+let running = null;
+
+
+function makeParticle(name, width, height, fromTop) {
+    let particle = {
+        x: Math.random() * width,
+        y: fromTop ? -10 : Math.random() * height,
+        size: name === "snow" ? 1 + Math.random() * 3 : 4 + Math.random() * 4,
+        speed: name === "snow" ? 0.5 + Math.random() : 2 + Math.random() * 3,
+        drift: (Math.random() - 0.5) * 1.5,
+        spin: Math.random() * Math.PI,
+    };
+    particle.colour = name === "snow"
+        ? "rgba(255, 255, 255, 0.8)"
+        : CONFETTI_COLOURS[Math.floor(Math.random() * CONFETTI_COLOURS.length)];
+
+    return particle
+}
+
+
+export function startEffect(canvas, name, lowPower) {
+    stopEffect();
+
+    let reducedMotion = window.matchMedia("(prefers-reduced-motion: reduce)").matches;
+    if (reducedMotion) { return false }
+
+    canvas.width = canvas.clientWidth;
+    canvas.height = canvas.clientHeight;
+    let ctx = canvas.getContext("2d");
+
+    let count = name === "snow" ? 150 : 200;
+    if (lowPower) { count = Math.floor(count / 4) }
+
+    let particles = [];
+    for (let i = 0; i < count; i++) {
+        particles.push(makeParticle(name, canvas.width, canvas.height, name === "confetti"));
+    }
+
+    let frameSkip = lowPower ? 2 : 1;
+    let frame = 0;
+    let state = { handle: null, canvas };
+
+    let draw = () => {
+        state.handle = requestAnimationFrame(draw);
+
+        frame += 1;
+        if (frame % frameSkip !== 0) { return }
+
+        ctx.clearRect(0, 0, canvas.width, canvas.height);
+        for (let p of particles) {
+            p.y += p.speed * frameSkip;
+            p.x += p.drift * frameSkip;
+            p.spin += 0.1 * frameSkip;
+
+            if (p.y > canvas.height) {
+                Object.assign(p, makeParticle(name, canvas.width, canvas.height, true));
+            }
+
+            ctx.fillStyle = p.colour;
+            if (name === "snow") {
+                ctx.beginPath();
+                ctx.arc(p.x, p.y, p.size, 0, Math.PI * 2);
+                ctx.fill();
+            } else {
+                ctx.fillRect(p.x, p.y, p.size, p.size * Math.abs(Math.cos(p.spin)));
+            }
+        }
+    };
+
+    state.handle = requestAnimationFrame(draw);
+    running = state;
+    return true
+}
+
+
+export function stopEffect() {
+    if (running === null) { return }
+
+    cancelAnimationFrame(running.handle);
+    let ctx = running.canvas.getContext("2d");
+    ctx.clearRect(0, 0, running.canvas.width, running.canvas.height);
+    running = null;
+}
+
+
+export function startEmojiRain(canvas, emojis, lowPower) {
+    stopEffect();
+
+    if (emojis.length === 0) { return false }
+
+    let reducedMotion = window.matchMedia("(prefers-reduced-motion: reduce)").matches;
+    if (reducedMotion) { return false }
+
+    canvas.width = canvas.clientWidth;
+    canvas.height = canvas.clientHeight;
+    let ctx = canvas.getContext("2d");
+    ctx.textAlign = "center";
+    ctx.textBaseline = "middle";
+
+    let makeEmoji = (fromTop) => ({
+        x: Math.random() * canvas.width,
+        y: fromTop ? -40 - Math.random() * canvas.height : Math.random() * canvas.height,
+        size: 20 + Math.random() * 24,
+        speed: 2 + Math.random() * 3,
+        sway: Math.random() * Math.PI * 2,
+        emoji: emojis[Math.floor(Math.random() * emojis.length)],
+    });
+
+    let count = lowPower ? 20 : 60;
+    let particles = [];
+    for (let i = 0; i < count; i++) {
+        particles.push(makeEmoji(true));
+    }
+
+    let frameSkip = lowPower ? 2 : 1;
+    let frame = 0;
+    let state = { handle: null, canvas };
+
+    let draw = () => {
+        state.handle = requestAnimationFrame(draw);
+
+        frame += 1;
+        if (frame % frameSkip !== 0) { return }
+
+        ctx.clearRect(0, 0, canvas.width, canvas.height);
+        for (let p of particles) {
+            p.y += p.speed * frameSkip;
+            p.sway += 0.05 * frameSkip;
+
+            if (p.y > canvas.height + 40) {
+                Object.assign(p, makeEmoji(true));
+            }
+
+            ctx.font = `${p.size}px sans-serif`;
+            ctx.fillText(p.emoji, p.x + Math.sin(p.sway) * 10, p.y);
+        }
+    };
+
+    state.handle = requestAnimationFrame(draw);
+    running = state;
+    return true
+}
+
+
+export function playCelebrationSound() {
+    let AudioContext = window.AudioContext || window.webkitAudioContext;
+    if (!AudioContext) { return }
+
+    let ctx = new AudioContext();
+    let notes = [523.25, 659.25, 783.99, 1046.5];
+
+    notes.forEach((frequency, i) => {
+        let start = ctx.currentTime + i * 0.12;
+        let osc = ctx.createOscillator();
+        let gain = ctx.createGain();
+
+        osc.type = "triangle";
+        osc.frequency.value = frequency;
+        gain.gain.setValueAtTime(0.2, start);
+        gain.gain.exponentialRampToValueAtTime(0.001, start + 0.4);
+
+        osc.connect(gain);
+        gain.connect(ctx.destination);
+        osc.start(start);
+        osc.stop(start + 0.4);
+    });
+
+    setTimeout(() => ctx.close(), 1500);
+}
\ No newline at end of file
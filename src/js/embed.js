@@ -0,0 +1,72 @@
+let targetOrigin = null;
+
+
+let playerElement = () => null;
+
+
+function getPlayer() {
+    let element = playerElement();
+    return window.videojs && element && window.videojs.getPlayer(element)
+}
+
+
+function post(message) {
+    if (window.parent === window || targetOrigin === null) { return }
+    window.parent.postMessage(Object.assign({ source: "spooderfy" }, message), targetOrigin);
+}
+
+
+function onHostMessage(event) {
+    if (event.source !== window.parent) { return }
+    if (targetOrigin === null || event.origin !== targetOrigin) { return }
+
+    let message = event.data || {};
+    let player = getPlayer();
+    if (!player) { return }
+
+    switch (message.type) {
+        case "play": player.play(); break;
+        case "pause": player.pause(); break;
+        case "seek": player.currentTime(Number(message.position) || 0); break;
+        case "volume": player.volume(Math.min(Math.max(Number(message.volume) || 0, 0), 1)); break;
+        case "mute": player.muted(Boolean(message.muted)); break;
+    }
+}
+
+
+function attachPlayer(player) {
+    if (getPlayer() !== player) { return }
+
+    let state = (type) => () => post({ type, position: player.currentTime() || 0 });
+    player.on("play", state("play"));
+    player.on("pause", state("pause"));
+    player.on("seeked", state("seeked"));
+}
+
+
+export function startEmbedBridge(origin, getPlayerElement) {
+    // Without a specific origin the bridge would talk to any embedder.
+    if (targetOrigin !== null || !origin || origin === "*") { return }
+    targetOrigin = origin;
+    playerElement = getPlayerElement;
+
+    window.addEventListener("message", onHostMessage);
+
+    if (window.videojs) {
+        window.videojs.hook("setup", attachPlayer);
+        let player = getPlayer();
+        if (player) { attachPlayer(player) }
+    }
+
+    let lastHeight = 0;
+    let observer = new ResizeObserver(() => {
+        let height = document.documentElement.scrollHeight;
+        if (height === lastHeight) { return }
+
+        lastHeight = height;
+        post({ type: "resize", height });
+    });
+    observer.observe(document.body);
+
+    post({ type: "ready" });
+}
\ No newline at end of file
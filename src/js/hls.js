@@ -0,0 +1,189 @@
+// The rendition choices of each player's element, kept across the
+// streams it plays.
+let choices = new WeakMap();
+
+
+function choicesOf(element) {
+    if (!choices.has(element)) {
+        choices.set(element, { preferredHeight: 0, dataSaver: false });
+    }
+    return choices.get(element)
+}
+
+
+// The http status of a failed request, 0 if the error has none.
+function errorStatus(error) {
+    if (!error) { return 0 }
+    if (error.status) { return error.status }
+    if (error.metadata && error.metadata.status) { return error.metadata.status }
+
+    let match = /\b(4\d\d|5\d\d)\b/.exec(error.message || "");
+    return match ? parseInt(match[1], 10) : 0
+}
+
+
+function getPlayer(element) {
+    return window.videojs && element && window.videojs.getPlayer(element)
+}
+
+
+function representations(player) {
+    if (!player) { return [] }
+
+    let vhs = player.tech({ IWillNotUseThisInPlugins: true }).vhs;
+    return vhs && vhs.representations ? vhs.representations() : []
+}
+
+
+function applyRendition(element, player) {
+    let { preferredHeight, dataSaver } = choicesOf(element);
+    let reps = representations(player);
+
+    // Audio only renditions have the lowest bandwidth so they win here.
+    if (dataSaver && reps.length > 0) {
+        let lowest = reps.reduce((a, b) => (a.bandwidth <= b.bandwidth ? a : b));
+        reps.forEach((rep) => rep.enabled(rep === lowest));
+        return
+    }
+
+    let available = reps.some((rep) => rep.height === preferredHeight);
+
+    reps.forEach((rep) => {
+        rep.enabled(preferredHeight === 0 || !available || rep.height === preferredHeight);
+    });
+}
+
+
+function applyDataSaver(element, player) {
+    if (!player) { return }
+
+    let { dataSaver } = choicesOf(element);
+
+    // The video is hidden rather than removed so the audio keeps playing
+    // and the sync listeners keep working.
+    let video = player.tech({ IWillNotUseThisInPlugins: true }).el();
+    video.style.visibility = dataSaver ? "hidden" : "";
+
+    let el = player.el();
+    let poster = player.poster();
+    el.style.backgroundImage = dataSaver && poster ? `url("${poster}")` : "";
+    el.style.backgroundSize = "contain";
+    el.style.backgroundPosition = "center";
+    el.style.backgroundRepeat = "no-repeat";
+}
+
+
+export function createStreamPlayer(element, src, type, onError) {
+    if (window.videojs === undefined) { return false }
+
+    // Yew keeps the element around so changing streams reuses the player.
+    let existing = getPlayer(element);
+    if (existing && !existing.isDisposed()) {
+        existing.src({ src, type });
+        return true
+    }
+
+    let player = window.videojs(element, { liveui: true });
+    player.src({ src, type });
+    player.on("loadedmetadata", () => applyRendition(element, player));
+    player.ready(() => applyDataSaver(element, player));
+    player.on("error", () => {
+        let error = player.error();
+        onError(
+            error ? error.code : 0,
+            error && error.message ? error.message : "unknown error",
+            errorStatus(error),
+        );
+    });
+
+    return true
+}
+
+
+export function getRenditions(element) {
+    let heights = representations(getPlayer(element)).map((rep) => rep.height).filter((height) => height);
+    return JSON.stringify(Array.from(new Set(heights)).sort((a, b) => b - a))
+}
+
+
+export function setRendition(element, height) {
+    choicesOf(element).preferredHeight = height;
+    applyRendition(element, getPlayer(element));
+}
+
+
+export function resumeAt(element, position, play) {
+    let player = getPlayer(element);
+    if (!player) { return }
+
+    player.one("loadedmetadata", () => {
+        player.currentTime(position);
+        if (play) { player.play() }
+    });
+}
+
+
+export function getPlaybackStats(element) {
+    let player = getPlayer(element);
+    if (!player || player.isDisposed()) { return null }
+
+    let video = player.tech({ IWillNotUseThisInPlugins: true }).el();
+    let quality = video.getVideoPlaybackQuality ? video.getVideoPlaybackQuality() : null;
+
+    let vhs = player.tech({ IWillNotUseThisInPlugins: true }).vhs;
+    let media = vhs && vhs.playlists ? vhs.playlists.media() : null;
+    let bitrate = media && media.attributes ? media.attributes.BANDWIDTH || 0 : 0;
+
+    let buffered = player.buffered();
+    let position = player.currentTime();
+    let bufferSecs = 0;
+    for (let i = 0; i < buffered.length; i++) {
+        if (buffered.start(i) <= position && position <= buffered.end(i)) {
+            bufferSecs = buffered.end(i) - position;
+        }
+    }
+
+    return JSON.stringify({
+        width: video.videoWidth,
+        height: video.videoHeight,
+        dropped_frames: quality ? quality.droppedVideoFrames : 0,
+        total_frames: quality ? quality.totalVideoFrames : 0,
+        bitrate,
+        bandwidth: vhs ? vhs.systemBandwidth || 0 : 0,
+        buffer_secs: bufferSecs,
+        ready_state: video.readyState,
+    })
+}
+
+
+export function setDataSaver(element, enabled) {
+    let player = getPlayer(element);
+
+    choicesOf(element).dataSaver = enabled;
+    applyRendition(element, player);
+    applyDataSaver(element, player);
+}
+
+
+export function prefersSaveData() {
+    return !!(navigator.connection && navigator.connection.saveData)
+}
+
+
+export function destroyStreamPlayer(element) {
+    let player = getPlayer(element);
+    if (player) { player.dispose() }
+}
+
+
+// Grid mode players get none of the rendition, data saver or error
+// handling of the main player, they start muted and follow it instead.
+export function createSecondaryPlayer(element, src, type) {
+    if (window.videojs === undefined) { return false }
+
+    let player = getPlayer(element) || window.videojs(element, { liveui: true });
+    player.src({ src, type });
+    player.muted(true);
+    player.play();
+    return true
+}
\ No newline at end of file
@@ -0,0 +1,77 @@
+let observer = null;
+let docked = false;
+let dismissed = false;
+let listener = null;
+
+// Gives the player's element, and the container keeping its place on
+// the page.
+let playerElement = () => null;
+let container = null;
+
+
+function getPlayer() {
+    let element = playerElement();
+    return window.videojs && element && window.videojs.getPlayer(element)
+}
+
+
+function setDocked(value) {
+    let player = getPlayer();
+    if (!player || !container || docked === value) { return }
+
+    docked = value;
+    if (value) {
+        // Keep the page's layout while the player is out of it.
+        container.style.minHeight = container.clientHeight + "px";
+        player.addClass("mini-player");
+    } else {
+        player.removeClass("mini-player");
+        container.style.minHeight = "";
+    }
+
+    if (listener) { listener(value) }
+}
+
+
+export function watchMiniPlayer(playerContainer, getPlayerElement, onChange) {
+    if (!window.IntersectionObserver) { return false }
+
+    if (observer !== null) { observer.disconnect() }
+    container = playerContainer;
+    playerElement = getPlayerElement;
+    listener = onChange;
+    dismissed = false;
+
+    observer = new IntersectionObserver((entries) => {
+        let visible = entries[0].intersectionRatio >= 0.25;
+        let player = getPlayer();
+
+        if (visible) {
+            dismissed = false;
+            setDocked(false);
+        } else if (!dismissed && player && !player.paused()) {
+            setDocked(true);
+        }
+    }, { threshold: [0, 0.25, 0.5] });
+    observer.observe(container);
+
+    return true
+}
+
+
+export function undockMiniPlayer(scrollBack) {
+    if (scrollBack && container) {
+        container.scrollIntoView({ behavior: "smooth", block: "center" });
+    } else {
+        dismissed = true;
+    }
+    setDocked(false);
+}
+
+
+export function stopMiniPlayer() {
+    setDocked(false);
+    if (observer !== null) { observer.disconnect() }
+    observer = null;
+    listener = null;
+}
\ No newline at end of file
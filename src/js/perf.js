@@ -0,0 +1,68 @@
+let measures = new Map();
+let frameHandle = null;
+let lastFrame = null;
+
+
+function record(name, duration) {
+    let entry = measures.get(name);
+    if (entry === undefined) {
+        entry = { name, count: 0, total_ms: 0, max_ms: 0 };
+        measures.set(name, entry);
+    }
+
+    entry.count += 1;
+    entry.total_ms += duration;
+    entry.max_ms = Math.max(entry.max_ms, duration);
+}
+
+
+export function perfStart(name) {
+    performance.mark(`${name}:start`);
+}
+
+
+export function perfEnd(name) {
+    let start = `${name}:start`;
+    if (performance.getEntriesByName(start, "mark").length === 0) { return }
+
+    performance.measure(name, start);
+    let entries = performance.getEntriesByName(name, "measure");
+    record(name, entries[entries.length - 1].duration);
+
+    // The measure stays in any recorded profile, we only drop our copy
+    // so the timeline buffer doesn't grow for the life of the page.
+    performance.clearMarks(start);
+    performance.clearMeasures(name);
+}
+
+
+export function setFrameSampling(enabled) {
+    if (frameHandle !== null) {
+        cancelAnimationFrame(frameHandle);
+        frameHandle = null;
+        lastFrame = null;
+    }
+
+    if (!enabled) { return }
+
+    let sample = (now) => {
+        if (lastFrame !== null) { record("frame", now - lastFrame) }
+        lastFrame = now;
+        frameHandle = requestAnimationFrame(sample);
+    };
+    frameHandle = requestAnimationFrame(sample);
+}
+
+
+export function perfSummary() {
+    return JSON.stringify(Array.from(measures.values()).map((entry) => ({
+        name: entry.name,
+        count: entry.count,
+        avg_ms: entry.total_ms / entry.count,
+        max_ms: entry.max_ms,
+    })))
+}
+
+export function perfNow() {
+    return performance.now()
+}
\ No newline at end of file
@@ -1,19 +1,134 @@
-export function setPlayerListeners(onerror, onmeta) {
-    let flvPlayer = window.flvPlayer;
-    if (flvPlayer === undefined) { return false }
+function getPlayer(element) {
+    return window.videojs && window.videojs.getPlayer(element)
+}
+
+
+export function fadeOutVideo(element, durationMs) {
+    let player = getPlayer(element);
+    if (!player) { return }
+
+    if (durationMs <= 0) {
+        player.pause();
+        return
+    }
+
+    let startVolume = player.volume();
+    let steps = 20;
+    let step = 0;
 
-    flvPlayer.on('error', function (e) {
-        onerror();
-    });
-    flvPlayer.on('METADATA_ARRIVED', onmeta);
+    let interval = setInterval(() => {
+        step += 1;
+        player.volume(startVolume * (1 - step / steps));
 
-    return true
+        if (step >= steps) {
+            clearInterval(interval);
+            player.pause();
+            player.volume(startVolume);
+        }
+    }, durationMs / steps);
 }
 
+export function isVideoPlaying(element) {
+    let player = getPlayer(element);
+    if (!player) { return false }
 
-export function tryReloadVideo() {
-    let flvPlayer = window.flvPlayer;
-    if (flvPlayer === null) { return }
-    flvPlayer.unload();
-    flvPlayer.load();
+    return !player.paused()
 }
+
+
+export function setVideoMuted(element, muted) {
+    let player = getPlayer(element);
+    if (!player) { return }
+
+    player.muted(muted);
+}
+
+export function getPlayerState(element) {
+    let player = getPlayer(element);
+    if (!player) { return null }
+
+    let duration = player.duration();
+    let live = !isFinite(duration);
+    let seekable = player.seekable();
+    if (live) {
+        duration = seekable.length > 0 ? seekable.end(seekable.length - 1) : 0;
+    }
+
+    return JSON.stringify({
+        paused: player.paused(),
+        position: player.currentTime() || 0,
+        duration: duration || 0,
+        volume: player.volume(),
+        muted: player.muted(),
+        rate: player.playbackRate(),
+        buffering: !player.paused() && player.readyState() < 3,
+        live,
+        seekable_start: live && seekable.length > 0 ? seekable.start(0) : 0,
+    })
+}
+
+
+export function playerTogglePlay(element) {
+    let player = getPlayer(element);
+    if (!player) { return }
+
+    if (player.paused()) {
+        player.play();
+    } else {
+        player.pause();
+    }
+}
+
+
+export function playerSeek(element, position) {
+    let player = getPlayer(element);
+    if (!player) { return }
+
+    player.currentTime(position);
+}
+
+
+export function playerSetVolume(element, volume) {
+    let player = getPlayer(element);
+    if (!player) { return }
+
+    player.volume(volume);
+    player.muted(volume === 0);
+}
+
+
+export function playerSetRate(element, rate) {
+    let player = getPlayer(element);
+    if (!player) { return }
+
+    player.playbackRate(rate);
+}
+
+
+export function playerSetPaused(element, paused) {
+    let player = getPlayer(element);
+    if (!player) { return }
+
+    if (paused) {
+        player.pause();
+    } else {
+        player.play();
+    }
+}
+
+
+export function playerToggleFullscreen(container, element) {
+    if (document.fullscreenElement) {
+        document.exitFullscreen();
+        return
+    }
+
+    // The container holds our controls and overlays as well as the video.
+    if (container && container.requestFullscreen) {
+        container.requestFullscreen();
+        return
+    }
+
+    let player = getPlayer(element);
+    if (player) { player.requestFullscreen() }
+}
\ No newline at end of file
@@ -0,0 +1,47 @@
+function frameCanvas(element) {
+    let video = element.querySelector("video");
+    if (!video || video.readyState < 2) { return null }
+
+    let canvas = document.createElement("canvas");
+    canvas.width = video.videoWidth;
+    canvas.height = video.videoHeight;
+
+    try {
+        canvas.getContext("2d").drawImage(video, 0, 0);
+        // Reading the pixels throws if the stream is cross origin without CORS headers.
+        canvas.getContext("2d").getImageData(0, 0, 1, 1);
+    } catch (e) {
+        return null
+    }
+
+    return canvas
+}
+
+
+export function downloadFrame(element, filename) {
+    let canvas = frameCanvas(element);
+    if (canvas === null) { return false }
+
+    let link = document.createElement("a");
+    link.download = filename;
+    link.href = canvas.toDataURL("image/png");
+    link.click();
+
+    return true
+}
+
+
+export async function copyFrame(element) {
+    let canvas = frameCanvas(element);
+    if (canvas === null || !navigator.clipboard || !window.ClipboardItem) { return false }
+
+    let blob = await new Promise((resolve) => canvas.toBlob(resolve, "image/png"));
+    if (!blob) { return false }
+
+    try {
+        await navigator.clipboard.write([new ClipboardItem({ "image/png": blob })]);
+        return true
+    } catch (e) {
+        return false
+    }
+}
\ No newline at end of file
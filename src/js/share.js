@@ -0,0 +1,10 @@
+export async function copyText(text) {
+    if (!navigator.clipboard) { return false }
+
+    try {
+        await navigator.clipboard.writeText(text);
+        return true
+    } catch (e) {
+        return false
+    }
+}
\ No newline at end of file
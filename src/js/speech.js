@@ -0,0 +1,51 @@
+// How many utterances are ducking each player and the volume to give
+// it back once they're done.
+let duckingByPlayer = new WeakMap();
+
+
+function duck(player) {
+    if (!player) { return }
+
+    let ducking = duckingByPlayer.get(player) || { count: 0, restoreVolume: null };
+    if (ducking.count === 0) {
+        ducking.restoreVolume = player.volume();
+        player.volume(ducking.restoreVolume * 0.3);
+    }
+    ducking.count += 1;
+    duckingByPlayer.set(player, ducking);
+}
+
+
+function unduck(player) {
+    let ducking = player && duckingByPlayer.get(player);
+    if (!ducking || ducking.count === 0) { return }
+
+    ducking.count -= 1;
+    if (ducking.count === 0 && ducking.restoreVolume !== null) {
+        player.volume(ducking.restoreVolume);
+        ducking.restoreVolume = null;
+    }
+}
+
+
+export function speakAs(element, voiceSeed, text, rate) {
+    let synth = window.speechSynthesis;
+    if (synth === undefined) { return false }
+
+    let utterance = new SpeechSynthesisUtterance(text);
+    utterance.rate = rate;
+
+    let voices = synth.getVoices();
+    if (voices.length > 0) {
+        utterance.voice = voices[voiceSeed % voices.length];
+    }
+
+    // Without a player the text is still read, just without ducking.
+    let player = element && window.videojs && window.videojs.getPlayer(element);
+    utterance.onstart = () => duck(player);
+    utterance.onend = () => unduck(player);
+    utterance.onerror = () => unduck(player);
+
+    synth.speak(utterance);
+    return true
+}
\ No newline at end of file
@@ -0,0 +1,79 @@
+// The tracks shown in each subtitle slot of each player's element, slot 0
+// is the main line and slot 1 the second line. Both are rendered by us
+// rather than video.js as it only ever shows a single track.
+let slotsByElement = new WeakMap();
+
+
+function slotsOf(element) {
+    if (!slotsByElement.has(element)) {
+        slotsByElement.set(element, [{ index: -1, offset: 0 }, { index: -1, offset: 0 }]);
+    }
+    return slotsByElement.get(element)
+}
+
+
+function getPlayer(element) {
+    return window.videojs && window.videojs.getPlayer(element)
+}
+
+
+function subtitleTracks(player) {
+    return Array.from(player.textTracks()).filter((track) => track.kind === "subtitles")
+}
+
+
+function applyModes(player, slots) {
+    subtitleTracks(player).forEach((track, i) => {
+        let mode = slots.some((slot) => slot.index === i) ? "hidden" : "disabled";
+        if (track.mode !== mode) { track.mode = mode }
+    });
+}
+
+
+export function addSubtitleTrack(element, label, vtt) {
+    let player = getPlayer(element);
+    if (!player) { return -1 }
+
+    let blob = new Blob([vtt], { type: "text/vtt" });
+    let src = URL.createObjectURL(blob);
+    player.addRemoteTextTrack({ kind: "subtitles", src, label, srclang: "" }, false);
+
+    return subtitleTracks(player).length - 1
+}
+
+
+export function selectSubtitle(element, slot, index) {
+    let slots = slotsOf(element);
+    slots[slot].index = index;
+
+    let player = getPlayer(element);
+    if (!player) { return false }
+
+    applyModes(player, slots);
+    return index < subtitleTracks(player).length
+}
+
+
+export function setSubtitleOffset(element, slot, offsetSecs) {
+    slotsOf(element)[slot].offset = offsetSecs;
+}
+
+
+export function activeCueText(element, slot) {
+    let player = getPlayer(element);
+    let slots = slotsOf(element);
+    let { index, offset } = slots[slot];
+    if (!player || index < 0) { return "" }
+
+    // Keeps the modes right as video.js shows default tracks by itself.
+    applyModes(player, slots);
+
+    let track = subtitleTracks(player)[index];
+    if (!track || !track.cues) { return "" }
+
+    let time = player.currentTime() - offset;
+    return Array.from(track.cues)
+        .filter((cue) => cue.startTime <= time && time < cue.endTime)
+        .map((cue) => cue.text)
+        .join("\n")
+}
\ No newline at end of file
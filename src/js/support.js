@@ -0,0 +1,62 @@
+const MAX_LOGS = 200;
+
+let logs = [];
+
+["log", "info", "warn", "error"].forEach((level) => {
+    let original = console[level];
+
+    console[level] = (...args) => {
+        let message = args.map((arg) => {
+            if (typeof arg === "string") { return arg }
+            try { return JSON.stringify(arg) } catch (e) { return String(arg) }
+        }).join(" ");
+
+        logs.push({ level, message, at: Date.now() });
+        if (logs.length > MAX_LOGS) { logs.shift() }
+
+        original.apply(console, args);
+    };
+});
+
+
+export function recentLogs() {
+    return JSON.stringify(logs)
+}
+
+
+export function diagnosticSummary(element) {
+    let connection = navigator.connection || {};
+    let video = element ? element.querySelector("video") : null;
+
+    return JSON.stringify({
+        user_agent: navigator.userAgent,
+        url: window.location.href,
+        viewport: `${window.innerWidth}x${window.innerHeight}`,
+        connection: connection.effectiveType || null,
+        video: video ? {
+            ready_state: video.readyState,
+            network_state: video.networkState,
+            current_time: video.currentTime,
+            paused: video.paused,
+            error: video.error ? video.error.code : null,
+        } : null,
+    })
+}
+
+
+export function captureFrame(element) {
+    let video = element.querySelector("video");
+    if (!video || video.readyState < 2) { return null }
+
+    let canvas = document.createElement("canvas");
+    canvas.width = video.videoWidth;
+    canvas.height = video.videoHeight;
+
+    try {
+        canvas.getContext("2d").drawImage(video, 0, 0);
+        return canvas.toDataURL("image/jpeg", 0.8)
+    } catch (e) {
+        // The stream is cross origin without CORS headers so the canvas is tainted.
+        return null
+    }
+}
\ No newline at end of file
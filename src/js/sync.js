@@ -0,0 +1,125 @@
+// The events applyRemote is waiting to see from each player's element,
+// these are echoes of the remote action rather than the user's own and
+// aren't emitted.
+let pendingByElement = new WeakMap();
+let listeners = null;
+let clockOffset = 0;
+
+// Gives the synced player's element, null until it's created.
+let playerElement = () => null;
+
+
+function getPlayer(element) {
+    return window.videojs && element && window.videojs.getPlayer(element)
+}
+
+
+function pendingOf(element) {
+    if (!pendingByElement.has(element)) {
+        pendingByElement.set(element, { play: false, pause: false, seek: null });
+    }
+    return pendingByElement.get(element)
+}
+
+
+function attach(player) {
+    // Only the main player is synced, grid mode players follow it instead.
+    let element = playerElement();
+    if (getPlayer(element) !== player) { return }
+    if (listeners === null || player.__syncAttached) { return }
+    player.__syncAttached = true;
+
+    let pending = pendingOf(element);
+    let echoed = (action) => {
+        if (!pending[action]) { return false }
+        pending[action] = false;
+        return true
+    };
+
+    let seekEchoed = () => {
+        if (pending.seek === null) { return false }
+
+        let target = pending.seek;
+        pending.seek = null;
+        return Math.abs(player.currentTime() - target) < 1
+    };
+
+    player.on("play", () => { if (!echoed("play")) { listeners.onPlay(player.currentTime()) } });
+    player.on("pause", () => { if (!echoed("pause")) { listeners.onPause(player.currentTime()) } });
+    player.on("seeked", () => { if (!seekEchoed()) { listeners.onSeek(player.currentTime()) } });
+    player.ready(() => listeners.onReady());
+
+    let buffering = false;
+    let setBuffering = (value) => {
+        if (buffering === value) { return }
+        buffering = value;
+        listeners.onBuffering(value);
+    };
+    player.on("waiting", () => { if (!player.paused()) { setBuffering(true) } });
+    player.on("playing", () => setBuffering(false));
+    player.on("canplaythrough", () => setBuffering(false));
+}
+
+
+export function setSyncListeners(getPlayerElement, onPlay, onPause, onSeek, onReady, onBuffering) {
+    listeners = { onPlay, onPause, onSeek, onReady, onBuffering };
+    playerElement = getPlayerElement;
+
+    if (window.videojs === undefined) { return false }
+    window.videojs.hook("setup", attach);
+
+    let player = getPlayer(playerElement());
+    if (player) { attach(player) }
+
+    return true
+}
+
+
+export function applyRemote(element, action, position) {
+    let player = getPlayer(element);
+    if (!player) { return }
+
+    let pending = pendingOf(element);
+    pending.play = false;
+    pending.pause = false;
+    pending.seek = null;
+
+    if (Math.abs(player.currentTime() - position) > 1) {
+        pending.seek = position;
+        player.currentTime(position);
+    }
+
+    if (action === "play" && player.paused()) {
+        pending.play = true;
+
+        // A blocked autoplay never fires play, don't swallow the user's own.
+        let started = player.play();
+        if (started) { started.catch(() => { pending.play = false }) }
+    } else if (action === "pause" && !player.paused()) {
+        pending.pause = true;
+        player.pause();
+    }
+}
+
+
+export function setClockOffset(offsetMs) {
+    clockOffset = offsetMs;
+}
+
+
+export function serverNow() {
+    return Date.now() + clockOffset
+}
+
+export function seekLocally(element, position) {
+    let player = getPlayer(element);
+    if (!player) { return }
+
+    let pending = pendingOf(element);
+    let seek = () => {
+        pending.seek = position;
+        player.currentTime(position);
+    };
+
+    if (player.readyState() >= 1) { seek() } else { player.one("loadedmetadata", seek) }
+}
\ No newline at end of file
@@ -0,0 +1,63 @@
+const WIDTH = 160;
+const HEIGHT = 90;
+const MAX_THUMBNAILS = 240;
+
+// The captured thumbnails of each player's element by interval bucket.
+let thumbnailsByElement = new WeakMap();
+let canvas = null;
+
+
+function getVideoElement(element) {
+    return element.querySelector("video")
+}
+
+
+function thumbnailsOf(element) {
+    if (!thumbnailsByElement.has(element)) {
+        thumbnailsByElement.set(element, new Map());
+    }
+    return thumbnailsByElement.get(element)
+}
+
+
+export function captureThumbnail(element, intervalSecs) {
+    let video = getVideoElement(element);
+    if (!video || video.readyState < 2) { return false }
+
+    let thumbnails = thumbnailsOf(element);
+    let bucket = Math.floor(video.currentTime / intervalSecs);
+    if (thumbnails.has(bucket)) { return true }
+
+    if (canvas === null) {
+        canvas = document.createElement("canvas");
+        canvas.width = WIDTH;
+        canvas.height = HEIGHT;
+    }
+
+    try {
+        canvas.getContext("2d").drawImage(video, 0, 0, WIDTH, HEIGHT);
+        thumbnails.set(bucket, canvas.toDataURL("image/jpeg", 0.6));
+    } catch (e) {
+        // The stream is cross origin without CORS headers so the canvas is tainted.
+        return false
+    }
+
+    if (thumbnails.size > MAX_THUMBNAILS) {
+        thumbnails.delete(thumbnails.keys().next().value);
+    }
+
+    return true
+}
+
+
+export function thumbnailAt(element, position, intervalSecs) {
+    let thumbnails = thumbnailsOf(element);
+    let bucket = Math.floor(position / intervalSecs);
+
+    for (let offset = 0; offset <= 2; offset++) {
+        let thumbnail = thumbnails.get(bucket - offset) || thumbnails.get(bucket + offset);
+        if (thumbnail) { return thumbnail }
+    }
+
+    return null
+}
\ No newline at end of file
@@ -0,0 +1,28 @@
+export function applyVideoTransform(element, fit, aspect, rotation, zoom) {
+    let player = window.videojs && window.videojs.getPlayer(element);
+    if (!player) { return }
+
+    let apply = () => {
+        let el = player.el();
+        let video = player.tech({ IWillNotUseThisInPlugins: true }).el();
+
+        // Stretch horizontally from the stream's own aspect ratio.
+        let stretch = 1;
+        if (aspect > 0 && video.videoWidth > 0 && video.videoHeight > 0) {
+            stretch = aspect / (video.videoWidth / video.videoHeight);
+        }
+
+        // A sideways video has to shrink to fit the player's other side.
+        let turned = 1;
+        if (rotation % 180 === 90 && el.clientWidth > 0 && el.clientHeight > 0) {
+            turned = Math.min(el.clientWidth / el.clientHeight, el.clientHeight / el.clientWidth);
+        }
+
+        el.style.overflow = "hidden";
+        video.style.objectFit = aspect > 0 ? "contain" : fit;
+        video.style.transform = `rotate(${rotation}deg) scale(${zoom * turned * stretch}, ${zoom * turned})`;
+    };
+
+    apply();
+    player.one("loadedmetadata", apply);
+}
\ No newline at end of file
@@ -0,0 +1,4 @@
+export function browserLanguage() {
+    let language = navigator.language || "en";
+    return language.split("-")[0].toLowerCase()
+}
\ No newline at end of file
@@ -1,12 +1,54 @@
 #![recursion_limit="512"]
 
+mod analytics;
+mod announce;
+mod auth;
 mod binder;
+mod cast;
 mod player;
+mod prefs;
+mod quality;
+mod recap;
+mod resume;
+mod rules;
+mod rewards;
+mod screenshot;
 mod chat;
+mod chat_overlay;
+mod closed;
+mod controls;
+mod danmaku;
+mod effects;
+mod embed;
+mod flood;
+mod grid;
+mod handoff;
+mod hold;
+mod idle;
+mod miniplayer;
+mod nerds;
+mod now_playing;
+mod onboarding;
 mod opcodes;
+mod outbox;
+mod perf;
 mod websocket;
 mod settings;
+mod share;
+mod sleep;
+mod srt;
+mod streaming;
+mod subtitles;
+mod support;
+mod sync;
+mod theme;
+mod timefmt;
+mod translate;
+mod transform;
 mod utils;
+mod video;
+mod watchtime;
+mod webhook;
 
 use wasm_bindgen::prelude::*;
 use yew::prelude::*;
@@ -16,30 +58,104 @@ use yew::services::timeout::TimeoutTask;
 use std::time::Duration;
 use crossbeam::queue::SegQueue;
 
-use crate::websocket::{WsHandler, WebsocketStatus};
+use crate::websocket::{WsHandler, WebsocketStatus, WebsocketMessage};
+use crate::closed::RoomClosed;
+
+
+/// The events that can be invoked by callbacks for the MovieRoom.
+enum MovieRoomEvents {
+    /// The room was closed while we were in it.
+    RoomClosed(WebsocketMessage),
+
+    /// The result of checking the room still exists on load.
+    Checked(Option<RoomClosed>),
+
+    /// The room's rules have been fetched, None if it has none.
+    Rules(Option<String>),
+
+    /// The user has acknowledged the room's rules.
+    AcceptRules,
+}
 
 
 struct MovieRoom {
+    link: ComponentLink<Self>,
     ws: websocket::WsHandler,
     room_id: String,
+    start_at: Option<f64>,
+    closed: Option<RoomClosed>,
+    video: video::VideoElementHandle,
+
+    /// If the room's rules are still being fetched.
+    rules_pending: bool,
+
+    /// The rules the user is yet to acknowledge, the player isn't loaded
+    /// until they have.
+    rules: Option<String>,
 }
 
 impl Component for MovieRoom {
-    type Message = ();
+    type Message = MovieRoomEvents;
     type Properties = ();
 
-    fn create(_props: Self::Properties, _link: ComponentLink<Self>) -> Self {
-        let room_id = utils::get_room_id();
-        let url = settings::get_ws_url(&room_id);
+    fn create(_props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let location = utils::RoomLocation::current();
+        let url = settings::get_ws_url(&location.room_id);
         let ws = WsHandler::connect(url);
 
+        let cb = link.callback(MovieRoomEvents::RoomClosed);
+        ws.subscribe_to_message(settings::ROOM_ID, opcodes::OP_ROOM_CLOSED, cb);
+
+        utils::send_future(
+            link.clone(),
+            async_check_room(location.room_id.clone()),
+        );
+        utils::send_future(
+            link.clone(),
+            async_fetch_rules(location.room_id.clone()),
+        );
+
         Self {
+            link,
             ws,
-            room_id,
+            room_id: location.room_id,
+            start_at: location.start_at,
+            closed: None,
+            video: video::VideoElementHandle::default(),
+            rules_pending: true,
+            rules: None,
         }
     }
 
-    fn update(&mut self, _msg: Self::Message) -> ShouldRender {
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        let closed = match msg {
+            MovieRoomEvents::RoomClosed(msg) => msg
+                .unwrap_and_into::<RoomClosed>()
+                .unwrap_or_default(),
+            MovieRoomEvents::Checked(Some(closed)) => closed,
+            MovieRoomEvents::Checked(None) => return false,
+            MovieRoomEvents::Rules(rules) => {
+                self.rules_pending = false;
+                self.rules = rules.filter(|rules| !rules::is_acknowledged(&self.room_id, rules));
+                return true;
+            },
+            MovieRoomEvents::AcceptRules => {
+                if let Some(rules) = self.rules.take() {
+                    rules::acknowledge(&self.room_id, &rules);
+                }
+                return true;
+            },
+        };
+
+        if self.closed.is_some() {
+            return false;
+        }
+
+        // Unmounting the rest of the room stops the player and drops
+        // every component's timers, leaving only the socket to close.
+        self.ws.close();
+        self.closed = Some(closed);
+
         true
     }
 
@@ -51,19 +167,63 @@ impl Component for MovieRoom {
     }
 
     fn view(&self) -> Html {
+        if let Some(closed) = self.closed.as_ref() {
+            return html! {
+                <closed::ClosedScreen closed=closed.clone() />
+            };
+        }
+
+        let player = if self.rules_pending {
+            html!{}
+        } else if let Some(rules) = self.rules.as_ref() {
+            let accept_cb = self.link.callback(|_| MovieRoomEvents::AcceptRules);
+            html! {
+                <rules::RulesScreen rules=rules.clone() on_accept=accept_cb />
+            }
+        } else {
+            html! {
+                <player::MediaPlayer
+                    ws=self.ws.clone()
+                    room_id=self.room_id.clone()
+                    start_at=self.start_at
+                    minimal=false
+                    video=self.video.clone()
+                />
+            }
+        };
+
         html! {
-            <div class="flex justify-around p-8">
-                <player::MediaPlayer ws=self.ws.clone() room_id=self.room_id.clone() />
+            <>
+                <theme::RoomBranding room_id=self.room_id.clone() />
+                <outbox::OutboxToast />
 
-                <chat::ChatRoom ws=self.ws.clone() room_id=self.room_id.clone() />
+                <div class="flex justify-around p-8">
+                    { player }
 
-                <WsEventDisplay ws=self.ws.clone() />
-            </div>
+                    <chat::ChatRoom ws=self.ws.clone() room_id=self.room_id.clone() video=self.video.clone() />
+
+                    <WsEventDisplay ws=self.ws.clone() />
+
+                    <auth::JoinCodePrompt ws=self.ws.clone() />
+
+                    <idle::IdleCheck ws=self.ws.clone() video=self.video.clone() />
+                </div>
+            </>
         }
     }
 }
 
 
+async fn async_check_room(room_id: String) -> MovieRoomEvents {
+    MovieRoomEvents::Checked(closed::check_room_closed(room_id).await)
+}
+
+
+async fn async_fetch_rules(room_id: String) -> MovieRoomEvents {
+    MovieRoomEvents::Rules(rules::fetch_rules(room_id).await)
+}
+
+
 #[derive(Properties, Clone)]
 pub struct WsDisplayProperties {
     pub ws: WsHandler,
@@ -104,9 +264,7 @@ impl Component for WsEventDisplay {
     fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
         let ws = props.ws;
 
-        let cb = link.callback(
-            |event| WsEventMessages::Status(event)
-        );
+        let cb = link.callback(WsEventMessages::Status);
         ws.subscribe_to_status(settings::EVENT_DISPLAY_ID, cb);
 
         Self {
@@ -147,7 +305,7 @@ impl Component for WsEventDisplay {
             return true;
         }
 
-        while let Some(_) = self.pending_tasks.pop() {
+        while self.pending_tasks.pop().is_some() {
             continue
         }
 
@@ -233,6 +391,24 @@ impl Component for WsEventDisplay {
 #[wasm_bindgen(start)]
 pub fn run_app() {
     let document = yew::utils::document();
-    let elm = document.get_element_by_id("bodyMount").unwrap();
-    App::<MovieRoom>::new().mount(elm);
+
+    // The embed page has no body mount, it starts itself with `run_embed`.
+    if let Some(elm) = document.get_element_by_id("bodyMount") {
+        App::<MovieRoom>::new().mount(elm);
+    }
+}
+
+
+/// The entry point of the embeddable widget, see `static/embed.html`.
+#[wasm_bindgen]
+pub fn run_embed() {
+    let document = yew::utils::document();
+    let elm = document.get_element_by_id("embedMount").unwrap();
+
+    if embed::EmbedConfig::from_query().is_none() {
+        elm.set_text_content(Some("No room was given to embed."));
+        return;
+    }
+
+    App::<embed::EmbedRoom>::new().mount(elm);
 }
\ No newline at end of file
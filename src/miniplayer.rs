@@ -0,0 +1,128 @@
+use wasm_bindgen::prelude::*;
+use yew::prelude::*;
+use yew::web_sys::Element;
+
+use crate::binder;
+use crate::video::VideoElementHandle;
+
+
+#[derive(Properties, Clone)]
+pub struct MiniPlayerProperties {
+    /// The player docked, its container keeps its place on the page.
+    pub video: VideoElementHandle,
+}
+
+
+/// The events that can be invoked by callbacks for the MiniPlayer.
+pub enum MiniPlayerEvents {
+    /// The player has been docked or returned to the page.
+    Docked(bool),
+
+    /// Play or pause from the docked controls.
+    TogglePlay,
+
+    /// Scroll back to the player.
+    Return,
+
+    /// Close the mini player until the user scrolls back to the player.
+    Close,
+}
+
+
+/// Docks the player in the corner of the page while it plays scrolled out
+/// of view, with controls to play or pause, scroll back to it or close it.
+///
+/// The docking itself is done in js by restyling the player, so it keeps
+/// playing without being recreated.
+pub struct MiniPlayer {
+    link: ComponentLink<Self>,
+    video: VideoElementHandle,
+    docked: bool,
+    watching: bool,
+    js_docked: Closure<dyn FnMut(bool)>,
+    js_player: Closure<dyn FnMut() -> Option<Element>>,
+}
+
+impl Component for MiniPlayer {
+    type Message = MiniPlayerEvents;
+    type Properties = MiniPlayerProperties;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let js_docked = {
+            let cb = link.callback(MiniPlayerEvents::Docked);
+            Closure::wrap(Box::new(move |docked: bool| cb.emit(docked)) as Box<dyn FnMut(bool)>)
+        };
+
+        Self {
+            link,
+            js_player: props.video.element_getter(),
+            video: props.video,
+            docked: false,
+            watching: false,
+            js_docked,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            MiniPlayerEvents::Docked(docked) => {
+                self.docked = docked;
+                return true;
+            },
+            MiniPlayerEvents::TogglePlay => {
+                self.video.toggle_play();
+                return true;
+            },
+            MiniPlayerEvents::Return => binder::undock_mini_player(true),
+            MiniPlayerEvents::Close => binder::undock_mini_player(false),
+        }
+
+        false
+    }
+
+    fn change(&mut self, _props: Self::Properties) -> ShouldRender {
+        false
+    }
+
+    fn rendered(&mut self, _first_render: bool) {
+        // The player container is rendered by our parent, it may not be
+        // on the page on the first pass.
+        if self.watching {
+            return;
+        }
+
+        if let Some(container) = self.video.container() {
+            self.watching = binder::watch_mini_player(&container, &self.js_player, &self.js_docked);
+        }
+    }
+
+    fn destroy(&mut self) {
+        binder::stop_mini_player();
+    }
+
+    fn view(&self) -> Html {
+        if !self.docked {
+            return html!{};
+        }
+
+        let play_cb = self.link.callback(|_| MiniPlayerEvents::TogglePlay);
+        let return_cb = self.link.callback(|_| MiniPlayerEvents::Return);
+        let close_cb = self.link.callback(|_| MiniPlayerEvents::Close);
+
+        let playing = self.video.is_playing();
+
+        html! {
+            <div class="miniplayer-bar fixed right-0 bottom-0 z-50 flex justify-between items-center bg-discord-dark rounded-t-lg px-2 py-1 mr-4">
+                <button onclick=play_cb class="text-white text-sm font-semibold focus:outline-none px-2">
+                    { if playing { "Pause" } else { "Play" } }
+                </button>
+                <button onclick=return_cb class="text-white text-sm focus:outline-none px-2" title="Scroll back to the player">
+                    { "Back to player" }
+                </button>
+                <button onclick=close_cb class="text-white text-sm focus:outline-none px-2" title="Close the mini player">
+                    { "✕" }
+                </button>
+            </div>
+        }
+    }
+}
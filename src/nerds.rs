@@ -0,0 +1,234 @@
+use yew::prelude::*;
+use yew::services::IntervalService;
+use yew::services::interval::IntervalTask;
+
+use serde::Deserialize;
+use std::time::Duration;
+
+use crate::binder;
+use crate::perf::{self, OpcodeMetrics, PerfMeasure};
+use crate::prefs::Preferences;
+use crate::video::VideoElementHandle;
+
+
+/// How often the stats are refreshed.
+const REFRESH_INTERVAL_MS: u64 = 1000;
+
+/// The names of the media element's ready states.
+const READY_STATES: [&str; 5] = [
+    "HAVE_NOTHING",
+    "HAVE_METADATA",
+    "HAVE_CURRENT_DATA",
+    "HAVE_FUTURE_DATA",
+    "HAVE_ENOUGH_DATA",
+];
+
+
+/// The playback stats as reported by the js player.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct PlaybackStats {
+    /// The resolution of the decoded video.
+    pub width: u32,
+    pub height: u32,
+
+    /// The frames dropped since the player was created.
+    pub dropped_frames: u32,
+    pub total_frames: u32,
+
+    /// The bandwidth of the playing rendition in bits per second.
+    pub bitrate: f64,
+
+    /// The bandwidth measured by the player in bits per second.
+    pub bandwidth: f64,
+
+    /// Seconds buffered ahead of the current position.
+    pub buffer_secs: f64,
+
+    /// The media element's ready state from 0 to 4.
+    pub ready_state: usize,
+}
+
+impl PlaybackStats {
+    /// Reads the stats from the player, None if it hasn't been created.
+    pub(crate) fn read(video: &VideoElementHandle) -> Option<Self> {
+        let stats = video.playback_stats()?;
+        serde_json::from_str(&stats).ok()
+    }
+}
+
+
+/// Formats bits per second as Kbps or Mbps.
+fn format_bitrate(bps: f64) -> String {
+    if bps >= 1_000_000.0 {
+        format!("{:.2} Mbps", bps / 1_000_000.0)
+    } else {
+        format!("{:.0} Kbps", bps / 1000.0)
+    }
+}
+
+
+#[derive(Properties, Clone)]
+pub struct StatsForNerdsProperties {
+    /// The player the stats are read from.
+    pub video: VideoElementHandle,
+}
+
+
+/// The events that can be invoked by callbacks for the StatsForNerds.
+pub enum StatsForNerdsEvents {
+    /// Refresh the stats.
+    Tick,
+}
+
+
+/// A debug overlay in the corner of the player showing what the player
+/// is doing, toggled from the preferences panel.
+///
+/// While shown it also summarises the frame times, the performance
+/// measures recorded around message dispatch and the heavier views, and
+/// the websocket traffic per opcode.
+pub struct StatsForNerds {
+    video: VideoElementHandle,
+    _refresh: IntervalTask,
+
+    /// The last read stats, None while the overlay is hidden.
+    stats: Option<PlaybackStats>,
+    perf: Vec<PerfMeasure>,
+    opcodes: Vec<OpcodeMetrics>,
+}
+
+impl Component for StatsForNerds {
+    type Message = StatsForNerdsEvents;
+    type Properties = StatsForNerdsProperties;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let refresh = IntervalService::spawn(
+            Duration::from_millis(REFRESH_INTERVAL_MS),
+            link.callback(|_| StatsForNerdsEvents::Tick),
+        );
+
+        Self {
+            video: props.video,
+            _refresh: refresh,
+            stats: None,
+            perf: vec![],
+            opcodes: vec![],
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            StatsForNerdsEvents::Tick => {
+                let was_shown = self.stats.is_some();
+                self.stats = if Preferences::load().show_stats {
+                    Some(PlaybackStats::read(&self.video).unwrap_or_default())
+                } else {
+                    None
+                };
+
+                if was_shown != self.stats.is_some() {
+                    binder::set_frame_sampling(self.stats.is_some());
+                }
+                self.perf = if self.stats.is_some() { perf::summary() } else { vec![] };
+                self.opcodes = if self.stats.is_some() { perf::opcode_metrics() } else { vec![] };
+
+                was_shown || self.stats.is_some()
+            },
+        }
+    }
+
+    fn change(&mut self, _props: Self::Properties) -> ShouldRender {
+        false
+    }
+
+    fn destroy(&mut self) {
+        binder::set_frame_sampling(false);
+    }
+
+    fn view(&self) -> Html {
+        let stats = match self.stats.as_ref() {
+            Some(stats) => stats,
+            None => return html!{},
+        };
+
+        let ready_state = READY_STATES
+            .get(stats.ready_state)
+            .copied()
+            .unwrap_or("UNKNOWN");
+
+        let rows = [
+            ("Resolution", format!("{}x{}", stats.width, stats.height)),
+            ("Dropped frames", format!("{} / {}", stats.dropped_frames, stats.total_frames)),
+            ("Bitrate", format_bitrate(stats.bitrate)),
+            ("Bandwidth", format_bitrate(stats.bandwidth)),
+            ("Buffer", format!("{:.1}s", stats.buffer_secs)),
+            ("Ready state", format!("{} ({})", ready_state, stats.ready_state)),
+        ];
+
+        let rows = rows.iter().map(|(name, value)| html! {
+            <tr>
+                <td class="text-gray-400 pr-4">{ name }</td>
+                <td>{ value }</td>
+            </tr>
+        });
+
+        let perf = self.perf.iter().map(|measure| html! {
+            <tr>
+                <td class="text-gray-400 pr-4">{ &measure.name }</td>
+                <td>
+                    { format!("{:.2}ms avg, {:.2}ms max ({})", measure.avg_ms, measure.max_ms, measure.count) }
+                </td>
+            </tr>
+        });
+
+        html! {
+            <div class="absolute top-0 left-0 m-2 bg-black bg-opacity-75 rounded p-2 text-white text-xs font-mono pointer-events-none z-20">
+                <table>
+                    { for rows }
+                    { for perf }
+                </table>
+                { self.view_opcodes() }
+            </div>
+        }
+    }
+}
+
+impl StatsForNerds {
+    /// The received, sent and failed counts of each opcode with the
+    /// average time its subscribers took to handle it.
+    fn view_opcodes(&self) -> Html {
+        if self.opcodes.is_empty() {
+            return html! {};
+        }
+
+        let rows = self.opcodes.iter().map(|metrics| {
+            let opcode = metrics.opcode
+                .map(|opcode| opcode.to_string())
+                .unwrap_or_else(|| "?".to_string());
+
+            html! {
+                <tr>
+                    <td class="text-gray-400 pr-4">{ opcode }</td>
+                    <td class="pr-4 text-right">{ metrics.received }</td>
+                    <td class="pr-4 text-right">{ metrics.sent }</td>
+                    <td class="pr-4 text-right">{ metrics.parse_failures }</td>
+                    <td class="text-right">{ format!("{:.2}ms", metrics.avg_handler_ms) }</td>
+                </tr>
+            }
+        });
+
+        html! {
+            <table class="mt-2">
+                <tr class="text-gray-400">
+                    <th class="pr-4 text-left">{ "Opcode" }</th>
+                    <th class="pr-4">{ "Recv" }</th>
+                    <th class="pr-4">{ "Sent" }</th>
+                    <th class="pr-4">{ "Failed" }</th>
+                    <th>{ "Avg" }</th>
+                </tr>
+                { for rows }
+            </table>
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use yew::prelude::*;
+
+use serde::Serialize;
+
+use crate::opcodes;
+use crate::utils::{start_future, emit_event, load_local, store_local};
+use crate::websocket::WrappingWsMessage;
+
+
+/// The local storage key the host's toggle for the room is stored under.
+fn enabled_key(room_id: &str) -> String {
+    format!("spooderfy.now_playing.{}", room_id)
+}
+
+
+/// The message posted to the room's Discord channel for the stream.
+pub fn now_playing_message(title: &str, owner: &str) -> String {
+    let title = title.trim();
+    let title = if title.is_empty() { "Untitled stream" } else { title };
+
+    if owner.trim().is_empty() {
+        format!("🎬 Now playing: **{}**", title)
+    } else {
+        format!("🎬 Now playing: **{}**, hosted by {}", title, owner.trim())
+    }
+}
+
+
+/// Tells the gateway what the room has started playing so it can be
+/// mirrored into the room's Discord channel.
+///
+/// Only the host may send these, the gateway drops them from anyone else.
+#[derive(Debug, Clone, Serialize)]
+pub struct NowPlayingEvent {
+    pub title: String,
+
+    /// The formatted message as previewed to the host.
+    pub content: String,
+}
+
+
+#[derive(Properties, Clone)]
+pub struct NowPlayingProperties {
+    /// The room id.
+    pub room_id: String,
+
+    /// The title of the stream.
+    pub title: String,
+
+    /// The name of whoever is streaming.
+    pub owner: String,
+
+    /// If the stream is live.
+    pub live: bool,
+}
+
+
+/// The events that can be invoked by callbacks for the NowPlaying.
+pub enum NowPlayingEvents {
+    /// The panel has been opened or closed.
+    Toggle,
+
+    /// The automatic announcements toggle has been pressed.
+    ToggleEnabled,
+
+    /// Post the current stream now.
+    Post,
+}
+
+
+/// The host's panel for announcing what's playing in Discord, only
+/// rendered for the host.
+///
+/// When enabled, a "Now playing" message is posted every time the room
+/// goes live or the stream's title changes. The toggle is kept per room
+/// on the host's device.
+pub struct NowPlaying {
+    link: ComponentLink<Self>,
+    props: NowPlayingProperties,
+    open: bool,
+    enabled: bool,
+
+    /// The title last posted, stops re-renders posting it twice.
+    announced: Option<String>,
+}
+
+impl NowPlaying {
+    fn post(&mut self) {
+        let event = NowPlayingEvent {
+            title: self.props.title.clone(),
+            content: now_playing_message(&self.props.title, &self.props.owner),
+        };
+
+        let payload = WrappingWsMessage {
+            opcode: opcodes::OP_NOW_PLAYING,
+            payload: serde_json::to_value(event).ok(),
+            mirror: true,
+        };
+        start_future(emit_event(self.props.room_id.clone(), payload));
+
+        self.announced = Some(self.props.title.clone());
+    }
+}
+
+impl Component for NowPlaying {
+    type Message = NowPlayingEvents;
+    type Properties = NowPlayingProperties;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let enabled = load_local(&enabled_key(&props.room_id)).unwrap_or(false);
+
+        Self {
+            link,
+            props,
+            open: false,
+            enabled,
+            announced: None,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            NowPlayingEvents::Toggle => self.open = !self.open,
+            NowPlayingEvents::ToggleEnabled => {
+                self.enabled = !self.enabled;
+                store_local(&enabled_key(&self.props.room_id), &self.enabled);
+            },
+            NowPlayingEvents::Post => {
+                if self.props.live {
+                    self.post();
+                }
+                self.open = false;
+            },
+        }
+
+        true
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        let went_live = props.live && !self.props.live;
+        let retitled = props.live && props.title != self.props.title;
+        self.props = props;
+
+        if !self.props.live {
+            self.announced = None;
+        } else if self.enabled
+            && (went_live || retitled)
+            && self.announced.as_ref() != Some(&self.props.title)
+        {
+            self.post();
+        }
+
+        self.open
+    }
+
+    fn view(&self) -> Html {
+        let toggle_cb = self.link.callback(|_| NowPlayingEvents::Toggle);
+
+        let panel = if self.open {
+            let enabled_cb = self.link.callback(|_| NowPlayingEvents::ToggleEnabled);
+            let post_cb = self.link.callback(|_| NowPlayingEvents::Post);
+            let preview = now_playing_message(&self.props.title, &self.props.owner);
+
+            html! {
+                <div class="absolute right-0 mt-2 w-72 bg-gray-800 border-2 border-gray-700 rounded-lg p-4 z-10">
+                    <h1 class="text-white font-bold mb-2">{ "Now playing in Discord" }</h1>
+                    <p class="text-gray-400 text-xs mb-1">{ "This is what will be posted:" }</p>
+                    <p class="bg-gray-900 text-white text-sm rounded-lg px-2 py-1 mb-2 break-words">
+                        { preview }
+                    </p>
+                    <label class="flex items-center text-white text-sm mb-2">
+                        <input type="checkbox" class="mr-2" checked=self.enabled onclick=enabled_cb />
+                        { "Post when the stream starts or changes" }
+                    </label>
+                    <div class="flex justify-end">
+                        <button
+                            onclick=post_cb
+                            disabled=!self.props.live
+                            class="bg-accent text-white font-semibold rounded-lg px-4 py-1 focus:outline-none">
+                            { "Post now" }
+                        </button>
+                    </div>
+                </div>
+            }
+        } else {
+            html!{}
+        };
+
+        html! {
+            <div class="relative flex items-center mx-2">
+                <button onclick=toggle_cb class="text-white text-lg focus:outline-none" title="Announce what's playing in Discord">
+                    { "🎬" }
+                </button>
+                { panel }
+            </div>
+        }
+    }
+}
@@ -0,0 +1,299 @@
+use yew::prelude::*;
+use yew::services::IntervalService;
+use yew::services::interval::IntervalTask;
+
+use reqwest::Client;
+use serde::Deserialize;
+use std::time::Duration;
+
+use crate::binder;
+use crate::hold::HoldPolicy;
+use crate::settings;
+use crate::share::room_link_at;
+use crate::utils::{load_local, send_future, store_local};
+
+
+/// How often the room is checked for completed steps.
+const REFRESH_SECS: u64 = 30;
+
+
+fn dismissed_key(room_id: &str) -> String {
+    format!("spooderfy.onboarding.{}", room_id)
+}
+
+fn invited_key(room_id: &str) -> String {
+    format!("spooderfy.onboarding.invited.{}", room_id)
+}
+
+
+/// The parts of the room config that show the owner has set it up.
+#[derive(Deserialize)]
+struct RoomConfig {
+    members: usize,
+
+    #[serde(default)]
+    leader_id: Option<String>,
+
+    #[serde(default)]
+    hold: HoldPolicy,
+}
+
+
+#[derive(Deserialize)]
+struct Webhook {
+    url: String,
+}
+
+
+/// The steps of the checklist the room has completed.
+#[derive(Debug, Clone, Default)]
+pub struct OnboardingProgress {
+    pub webhook: bool,
+    pub streamed: bool,
+    pub members: bool,
+    pub permissions: bool,
+}
+
+
+/// Checks the room's webhook, stream and config for completed steps.
+async fn fetch_progress(room_id: String) -> OwnerChecklistEvents {
+    let client = Client::new();
+
+    let webhook = match client.get(settings::get_webhook_api(&room_id)).send().await {
+        Ok(resp) if resp.status().is_success() => resp.json::<Webhook>()
+            .await
+            .map(|webhook| !webhook.url.is_empty())
+            .unwrap_or(false),
+        _ => false,
+    };
+
+    let streamed = match client.get(settings::get_stream_api_url(&room_id)).send().await {
+        Ok(resp) => resp.status().is_success(),
+        Err(_) => false,
+    };
+
+    let config = match client.get(settings::get_room_config_url(&room_id)).send().await {
+        Ok(resp) if resp.status().is_success() => resp.json::<RoomConfig>().await.ok(),
+        _ => None,
+    };
+
+    let (members, permissions) = match config {
+        Some(config) => (
+            config.members > 1,
+            config.leader_id.is_some() || config.hold != HoldPolicy::default(),
+        ),
+        None => (false, false),
+    };
+
+    OwnerChecklistEvents::Progress(OnboardingProgress {
+        webhook,
+        streamed,
+        members,
+        permissions,
+    })
+}
+
+
+#[derive(Properties, Clone)]
+pub struct OwnerChecklistProperties {
+    /// The room id.
+    pub room_id: String,
+}
+
+
+/// The events that can be invoked by callbacks for the OwnerChecklist.
+pub enum OwnerChecklistEvents {
+    /// Check the room for completed steps.
+    Refresh,
+
+    /// The room has been checked.
+    Progress(OnboardingProgress),
+
+    /// The invite link button has been pressed.
+    CopyInvite,
+
+    /// The invite link has been copied, false if it failed.
+    InviteCopied(bool),
+
+    /// The completed checklist has been dismissed.
+    Dismiss,
+}
+
+
+/// A checklist walking new owners through setting up their room, only
+/// rendered for the owner.
+///
+/// The steps complete themselves as the room is set up, each linking to
+/// where it's done. Once every step is complete the checklist can be
+/// dismissed, which is remembered per room on the owner's device.
+pub struct OwnerChecklist {
+    link: ComponentLink<Self>,
+    room_id: String,
+    refresh: Option<IntervalTask>,
+
+    /// None until the room has been checked.
+    progress: Option<OnboardingProgress>,
+
+    /// If the owner has copied the invite link from the checklist.
+    invited: bool,
+    dismissed: bool,
+}
+
+impl OwnerChecklist {
+    fn is_complete(&self) -> bool {
+        match self.progress.as_ref() {
+            Some(progress) => {
+                progress.webhook
+                    && progress.streamed
+                    && (progress.members || self.invited)
+                    && progress.permissions
+            },
+            None => false,
+        }
+    }
+
+    fn view_step(&self, done: bool, title: &str, action: Html) -> Html {
+        let (icon, colour) = if done {
+            ("✓", "text-green-400")
+        } else {
+            ("○", "text-gray-400")
+        };
+
+        html! {
+            <li class="flex justify-between items-center py-1">
+                <span class=("text-sm", if done { "text-gray-400 line-through" } else { "text-white" })>
+                    <span class=("mr-2", colour)>{ icon }</span>
+                    { title }
+                </span>
+                { if done { html!{} } else { action } }
+            </li>
+        }
+    }
+
+    fn settings_link(&self, section: &str) -> Html {
+        html! {
+            <a href=settings::get_room_settings_url(&self.room_id, section)
+                target="_blank" class="text-accent text-sm hover:underline">
+                { "Set up" }
+            </a>
+        }
+    }
+}
+
+impl Component for OwnerChecklist {
+    type Message = OwnerChecklistEvents;
+    type Properties = OwnerChecklistProperties;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let dismissed = load_local(&dismissed_key(&props.room_id)).unwrap_or(false);
+        let invited = load_local(&invited_key(&props.room_id)).unwrap_or(false);
+
+        let refresh = if dismissed {
+            None
+        } else {
+            send_future(link.clone(), fetch_progress(props.room_id.clone()));
+
+            Some(IntervalService::spawn(
+                Duration::from_secs(REFRESH_SECS),
+                link.callback(|_| OwnerChecklistEvents::Refresh),
+            ))
+        };
+
+        Self {
+            link,
+            room_id: props.room_id,
+            refresh,
+            progress: None,
+            invited,
+            dismissed,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            OwnerChecklistEvents::Refresh => {
+                // Nothing left to detect once the room is set up.
+                if !self.is_complete() {
+                    send_future(self.link.clone(), fetch_progress(self.room_id.clone()));
+                }
+                return false;
+            },
+            OwnerChecklistEvents::Progress(progress) => {
+                self.progress = Some(progress);
+            },
+            OwnerChecklistEvents::CopyInvite => {
+                let url = room_link_at(&self.room_id, None);
+                send_future(self.link.clone(), async move {
+                    let copied = binder::copy_text(url).await;
+                    OwnerChecklistEvents::InviteCopied(copied.as_bool().unwrap_or(false))
+                });
+                return false;
+            },
+            OwnerChecklistEvents::InviteCopied(copied) => {
+                if !copied {
+                    return false;
+                }
+
+                self.invited = true;
+                store_local(&invited_key(&self.room_id), &true);
+            },
+            OwnerChecklistEvents::Dismiss => {
+                self.dismissed = true;
+                self.refresh = None;
+                store_local(&dismissed_key(&self.room_id), &true);
+            },
+        }
+
+        true
+    }
+
+    fn change(&mut self, _props: Self::Properties) -> ShouldRender {
+        false
+    }
+
+    fn view(&self) -> Html {
+        if self.dismissed {
+            return html!{};
+        }
+
+        let progress = match self.progress.as_ref() {
+            Some(progress) => progress,
+            None => return html!{},
+        };
+
+        let invite_cb = self.link.callback(|_| OwnerChecklistEvents::CopyInvite);
+        let invite = html! {
+            <button onclick=invite_cb class="text-accent text-sm hover:underline focus:outline-none">
+                { "Copy invite link" }
+            </button>
+        };
+
+        let footer = if self.is_complete() {
+            let dismiss_cb = self.link.callback(|_| OwnerChecklistEvents::Dismiss);
+
+            html! {
+                <div class="flex justify-between items-center mt-2">
+                    <span class="text-white text-sm">{ "Your room is all set up!" }</span>
+                    <button onclick=dismiss_cb class="text-white text-sm border-2 rounded-lg px-2 focus:outline-none">
+                        { "Dismiss" }
+                    </button>
+                </div>
+            }
+        } else {
+            html!{}
+        };
+
+        html! {
+            <div class="bg-discord-dark rounded-lg p-4 mt-4">
+                <h2 class="text-white font-semibold mb-2">{ "Get your room ready" }</h2>
+                <ul>
+                    { self.view_step(progress.webhook, "Connect a Discord webhook", self.settings_link("webhook")) }
+                    { self.view_step(progress.streamed, "Start your first stream", self.settings_link("stream")) }
+                    { self.view_step(progress.members || self.invited, "Invite members", invite) }
+                    { self.view_step(progress.permissions, "Choose who controls playback", self.settings_link("permissions")) }
+                </ul>
+                { footer }
+            </div>
+        }
+    }
+}
@@ -5,4 +5,33 @@ pub type OpCode = usize;
 
 pub const OP_STATS_UPDATE: OpCode = 0;
 pub const OP_MESSAGE: OpCode = 5;
-pub const OP_LIVE_READY: OpCode = 2;
\ No newline at end of file
+pub const OP_LIVE_READY: OpCode = 2;
+pub const OP_AUTH_REQUIRED: OpCode = 6;
+pub const OP_AUTH: OpCode = 7;
+pub const OP_AUTH_ACCEPTED: OpCode = 8;
+pub const OP_CURFEW: OpCode = 9;
+pub const OP_PRESENCE: OpCode = 10;
+pub const OP_BOOST: OpCode = 11;
+pub const OP_PLAY: OpCode = 12;
+pub const OP_PAUSE: OpCode = 13;
+pub const OP_SEEK: OpCode = 14;
+pub const OP_EFFECT: OpCode = 15;
+pub const OP_FLOOD_REPORT: OpCode = 16;
+pub const OP_REQUEST_STATE: OpCode = 17;
+pub const OP_STATE_SNAPSHOT: OpCode = 18;
+pub const OP_TIME_CHECK: OpCode = 19;
+pub const OP_EDIT_MESSAGE: OpCode = 20;
+pub const OP_DELETE_MESSAGE: OpCode = 21;
+pub const OP_READ_STATE: OpCode = 22;
+pub const OP_ANNOUNCE: OpCode = 23;
+pub const OP_SET_RATE: OpCode = 24;
+pub const OP_BUFFER: OpCode = 25;
+pub const OP_CHAPTERS: OpCode = 26;
+pub const OP_HANDOFF_CLAIMED: OpCode = 27;
+pub const OP_COUNTDOWN: OpCode = 28;
+pub const OP_CELEBRATE: OpCode = 29;
+pub const OP_NOW_PLAYING: OpCode = 31;
+pub const OP_WATCH_STATS: OpCode = 32;
+pub const OP_PROFILE_UPDATED: OpCode = 33;
+pub const OP_ROOM_CLOSED: OpCode = 34;
+pub const OP_HOLD: OpCode = 35;
\ No newline at end of file
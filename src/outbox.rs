@@ -0,0 +1,281 @@
+use yew::prelude::*;
+use yew::services::{ConsoleService, TimeoutService};
+use yew::services::timeout::TimeoutTask;
+use yew::utils::window;
+
+use reqwest::{Client, StatusCode};
+use serde_json::Value;
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+
+use crate::opcodes::{self, OpCode};
+use crate::settings;
+
+
+/// How many times an event is sent before giving up on it.
+const MAX_ATTEMPTS: u32 = 5;
+
+/// The wait before the first retry, doubled after every failed attempt.
+const BASE_BACKOFF_MS: i32 = 500;
+const MAX_BACKOFF_MS: i32 = 8_000;
+
+/// How long the failure toast is shown for.
+const TOAST_SECS: u64 = 6;
+
+/// Events carrying the playback state at the moment they were made. They
+/// go in their own lane so other events failing doesn't hold them back,
+/// and are never retried as a late one would move the room backwards.
+const SYNC_OPCODES: [OpCode; 9] = [
+    opcodes::OP_PLAY,
+    opcodes::OP_PAUSE,
+    opcodes::OP_SEEK,
+    opcodes::OP_STATE_SNAPSHOT,
+    opcodes::OP_BUFFER,
+    opcodes::OP_TIME_CHECK,
+    opcodes::OP_SET_RATE,
+    opcodes::OP_COUNTDOWN,
+    opcodes::OP_HOLD,
+];
+
+
+/// Events waiting to be sent one at a time so they arrive in the order
+/// they were made.
+#[derive(Default)]
+struct Lane {
+    queue: VecDeque<Value>,
+
+    /// If a task is currently draining the queue, the front event is the
+    /// one being sent while it is.
+    sending: bool,
+}
+
+
+/// The events waiting to be emitted to a room.
+#[derive(Default)]
+struct Outbox {
+    events: Lane,
+    sync: Lane,
+}
+
+impl Outbox {
+    fn lane(&mut self, sync: bool) -> &mut Lane {
+        if sync { &mut self.sync } else { &mut self.events }
+    }
+}
+
+
+fn opcode_of(event: &Value) -> Option<OpCode> {
+    event.get("opcode")?.as_u64().map(|opcode| opcode as OpCode)
+}
+
+
+fn is_sync(event: &Value) -> bool {
+    opcode_of(event).is_some_and(|opcode| SYNC_OPCODES.contains(&opcode))
+}
+
+
+thread_local! {
+    static OUTBOXES: RefCell<HashMap<String, Outbox>> = RefCell::new(HashMap::new());
+
+    /// Told whenever an event is given up on.
+    static ON_FAILED: RefCell<Option<Callback<()>>> = const { RefCell::new(None) };
+}
+
+
+/// Queues the event to be emitted to the room, it is retried with backoff
+/// if the api can't be reached.
+///
+/// Sync events are sent once in their own lane instead, and replace any
+/// queued event of the same opcode that hasn't been sent yet.
+pub fn push(room_id: String, event: Value) {
+    let sync = is_sync(&event);
+
+    let start = OUTBOXES.with(|outboxes| {
+        let mut outboxes = outboxes.borrow_mut();
+        let lane = outboxes.entry(room_id.clone()).or_default().lane(sync);
+
+        if sync {
+            let opcode = opcode_of(&event);
+            let in_flight = usize::from(lane.sending);
+
+            let mut idx = 0;
+            lane.queue.retain(|queued| {
+                let keep = idx < in_flight || opcode_of(queued) != opcode;
+                idx += 1;
+                keep
+            });
+        }
+
+        lane.queue.push_back(event);
+        !std::mem::replace(&mut lane.sending, true)
+    });
+
+    if start {
+        spawn_local(drain(room_id, sync));
+    }
+}
+
+
+/// Sends the events of one of the room's lanes in order until it's empty.
+async fn drain(room_id: String, sync: bool) {
+    let attempts = if sync { 1 } else { MAX_ATTEMPTS };
+
+    loop {
+        let next = OUTBOXES.with(|outboxes| {
+            let mut outboxes = outboxes.borrow_mut();
+            let lane = outboxes.get_mut(&room_id)?.lane(sync);
+
+            let next = lane.queue.front().cloned();
+            if next.is_none() {
+                lane.sending = false;
+            }
+            next
+        });
+
+        let event = match next {
+            Some(event) => event,
+            None => return,
+        };
+
+        if !deliver(&room_id, &event, attempts).await {
+            ConsoleService::warn("Gave up emitting an event to the room");
+            ON_FAILED.with(|cb| {
+                if let Some(cb) = cb.borrow().as_ref() {
+                    cb.emit(());
+                }
+            });
+        }
+
+        OUTBOXES.with(|outboxes| {
+            if let Some(outbox) = outboxes.borrow_mut().get_mut(&room_id) {
+                outbox.lane(sync).queue.pop_front();
+            }
+        });
+    }
+}
+
+
+/// Sends the event, retrying network errors and server errors up to the
+/// given attempts. Returns false once it has been given up on.
+async fn deliver(room_id: &str, event: &Value, attempts: u32) -> bool {
+    let url = settings::get_emit_url(room_id);
+    let mut backoff_ms = BASE_BACKOFF_MS;
+
+    for attempt in 1..=attempts {
+        let resp = Client::new()
+            .put(&url)
+            .json(event)
+            .send()
+            .await;
+
+        match resp {
+            Ok(resp) if resp.status().is_success() => return true,
+
+            // The api has rejected the event itself, sending it again
+            // won't change that.
+            Ok(resp) if is_permanent(resp.status()) => return false,
+            _ => {},
+        }
+
+        if attempt < attempts {
+            sleep_ms(backoff_ms).await;
+            backoff_ms = (backoff_ms * 2).min(MAX_BACKOFF_MS);
+        }
+    }
+
+    false
+}
+
+
+fn is_permanent(status: StatusCode) -> bool {
+    status.is_client_error()
+        && status != StatusCode::REQUEST_TIMEOUT
+        && status != StatusCode::TOO_MANY_REQUESTS
+}
+
+
+async fn sleep_ms(ms: i32) {
+    let promise = js_sys::Promise::new(&mut |resolve, _| {
+        let _ = window().set_timeout_with_callback_and_timeout_and_arguments_0(&resolve, ms);
+    });
+    let _ = JsFuture::from(promise).await;
+}
+
+
+/// The events that can be invoked by callbacks for the OutboxToast.
+pub enum OutboxToastEvents {
+    /// An event has been given up on.
+    Failed,
+
+    /// Hide the toast.
+    Clear,
+}
+
+
+/// Tells the user when their actions couldn't be sent to the room even
+/// after retrying.
+pub struct OutboxToast {
+    link: ComponentLink<Self>,
+
+    /// The events given up on since the toast was last shown.
+    failed: u32,
+    clear: Option<TimeoutTask>,
+}
+
+impl Component for OutboxToast {
+    type Message = OutboxToastEvents;
+    type Properties = ();
+
+    fn create(_props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let cb = link.callback(|_| OutboxToastEvents::Failed);
+        ON_FAILED.with(|on_failed| *on_failed.borrow_mut() = Some(cb));
+
+        Self {
+            link,
+            failed: 0,
+            clear: None,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            OutboxToastEvents::Failed => {
+                self.failed += 1;
+                self.clear = Some(TimeoutService::spawn(
+                    Duration::from_secs(TOAST_SECS),
+                    self.link.callback(|_| OutboxToastEvents::Clear),
+                ));
+            },
+            OutboxToastEvents::Clear => {
+                self.failed = 0;
+                self.clear = None;
+            },
+        }
+
+        true
+    }
+
+    fn change(&mut self, _props: Self::Properties) -> ShouldRender {
+        false
+    }
+
+    fn destroy(&mut self) {
+        ON_FAILED.with(|on_failed| *on_failed.borrow_mut() = None);
+    }
+
+    fn view(&self) -> Html {
+        let text = match self.failed {
+            0 => return html!{},
+            1 => "An action couldn't be sent to the room, check your connection.".to_string(),
+            n => format!("{} actions couldn't be sent to the room, check your connection.", n),
+        };
+
+        html! {
+            <div class="fixed bottom-0 left-0 m-4 z-50 bg-gray-800 border-2 border-red-500 text-white text-sm rounded-lg px-4 py-2">
+                { text }
+            </div>
+        }
+    }
+}
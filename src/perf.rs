@@ -0,0 +1,143 @@
+use serde::{Serialize, Deserialize};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+use crate::binder;
+use crate::opcodes::OpCode;
+
+
+thread_local! {
+    /// The message counts of every opcode seen since the page loaded.
+    static OPCODE_COUNTS: RefCell<BTreeMap<OpCode, OpcodeCounts>> = const {
+        RefCell::new(BTreeMap::new())
+    };
+
+    /// Incoming messages that failed to parse before an opcode was read.
+    static UNKNOWN_FAILURES: RefCell<u32> = const { RefCell::new(0) };
+}
+
+
+#[derive(Default)]
+struct OpcodeCounts {
+    received: u32,
+    sent: u32,
+    parse_failures: u32,
+    handler_ms: f64,
+}
+
+
+/// Times the code until it is dropped, recording a User Timing measure
+/// with the given name so it shows up in the browser's profiler.
+pub struct PerfSpan {
+    name: &'static str,
+}
+
+impl PerfSpan {
+    pub fn start(name: &'static str) -> Self {
+        binder::perf_start(name);
+        Self { name }
+    }
+}
+
+impl Drop for PerfSpan {
+    fn drop(&mut self) {
+        binder::perf_end(self.name);
+    }
+}
+
+
+/// The timings recorded under a measure name since the page loaded.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PerfMeasure {
+    pub name: String,
+    pub count: u32,
+    pub avg_ms: f64,
+    pub max_ms: f64,
+}
+
+
+/// The timings of every measure recorded so far, including the frame
+/// times while frame sampling is on.
+pub fn summary() -> Vec<PerfMeasure> {
+    serde_json::from_str(&binder::perf_summary()).unwrap_or_default()
+}
+
+
+/// The traffic of a single opcode over the websocket and emits.
+#[derive(Debug, Clone, Serialize)]
+pub struct OpcodeMetrics {
+    /// None for messages that failed to parse before the opcode was read.
+    pub opcode: Option<OpCode>,
+    pub received: u32,
+    pub sent: u32,
+    pub parse_failures: u32,
+
+    /// The average time spent dispatching a received message.
+    pub avg_handler_ms: f64,
+}
+
+
+fn with_counts(opcode: OpCode, f: impl FnOnce(&mut OpcodeCounts)) {
+    OPCODE_COUNTS.with(|counts| f(counts.borrow_mut().entry(opcode).or_default()));
+}
+
+
+/// Records a received message and how long its subscribers took to
+/// handle it.
+pub fn record_received(opcode: OpCode, handler_ms: f64) {
+    with_counts(opcode, |counts| {
+        counts.received += 1;
+        counts.handler_ms += handler_ms;
+    });
+}
+
+
+/// Records a message sent to the room, either directly or as an emit.
+pub fn record_sent(opcode: OpCode) {
+    with_counts(opcode, |counts| counts.sent += 1);
+}
+
+
+/// Records an incoming message that couldn't be parsed, None if not even
+/// its opcode could be read.
+pub fn record_parse_failure(opcode: Option<OpCode>) {
+    match opcode {
+        Some(opcode) => with_counts(opcode, |counts| counts.parse_failures += 1),
+        None => UNKNOWN_FAILURES.with(|failures| *failures.borrow_mut() += 1),
+    }
+}
+
+
+/// A snapshot of the traffic of every opcode seen so far, ordered by
+/// opcode with unparseable messages last.
+pub fn opcode_metrics() -> Vec<OpcodeMetrics> {
+    let mut metrics: Vec<OpcodeMetrics> = OPCODE_COUNTS.with(|counts| {
+        counts.borrow()
+            .iter()
+            .map(|(opcode, counts)| OpcodeMetrics {
+                opcode: Some(*opcode),
+                received: counts.received,
+                sent: counts.sent,
+                parse_failures: counts.parse_failures,
+                avg_handler_ms: if counts.received > 0 {
+                    counts.handler_ms / f64::from(counts.received)
+                } else {
+                    0.0
+                },
+            })
+            .collect()
+    });
+
+    let unknown = UNKNOWN_FAILURES.with(|failures| *failures.borrow());
+    if unknown > 0 {
+        metrics.push(OpcodeMetrics {
+            opcode: None,
+            received: 0,
+            sent: 0,
+            parse_failures: unknown,
+            avg_handler_ms: 0.0,
+        });
+    }
+
+    metrics
+}
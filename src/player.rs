@@ -1,13 +1,58 @@
+use wasm_bindgen::prelude::*;
 use yew::prelude::*;
 use yew::services::ConsoleService;
+use yew::web_sys::Element;
 
+use reqwest::Client;
 use serde::Deserialize;
 
+use crate::announce::{AnnouncementOverlay, Announcer};
+use crate::chat_overlay::ChatOverlay;
+use crate::binder;
+use crate::controls::{ControlBar, PlayerState};
+use crate::danmaku::DanmakuOverlay;
+use crate::effects::{Celebrator, EffectsOverlay};
+use crate::grid::{SecondaryStream, SecondaryVideo};
+use crate::handoff::HandoffButton;
+use crate::nerds::StatsForNerds;
+use crate::now_playing::NowPlaying;
 use crate::opcodes;
+use crate::perf::PerfSpan;
 use crate::settings;
+use crate::prefs::{Preferences, PreferencesPanel};
+use crate::quality::QualityMenu;
+use crate::recap::RecapButton;
+use crate::resume::ResumePrompt;
+use crate::rewards::BoostPanel;
+use crate::sleep::SleepTimer;
+use crate::miniplayer::MiniPlayer;
+use crate::hold::{HoldButton, HoldPolicy};
+use crate::streaming::StreamKind;
+use crate::subtitles::{SubtitleOverlay, SubtitleTrack};
+use crate::support::SupportWidget;
+use crate::sync::PlaybackSync;
+use crate::transform::{TransformMenu, VideoTransform};
+use crate::utils::send_future;
+use crate::video::VideoElementHandle;
+use crate::watchtime::WatchStats;
 use crate::websocket::{WsHandler, WebsocketMessage};
 
 
+/// The art shown while waiting for the stream and in data saver mode.
+const POSTER_URL: &str = "https://cdn.discordapp.com/attachments/667270372042866699/805836261008211988/Spooderfy_Transparent.png";
+
+/// How many times an expired stream url is refreshed before giving up.
+const MAX_STREAM_REFRESHES: u32 = 3;
+
+/// How long the mouse has to be still in fullscreen before the controls
+/// and overlays fade out.
+const AUTO_HIDE_MS: u32 = 3000;
+
+/// The first MediaError code that means the source itself failed, network
+/// errors then decode errors then unsupported sources.
+const MEDIA_ERR_NETWORK: u32 = 2;
+
+
 /// The set component properties that can be set by the parent component.
 #[derive(Properties, Clone)]
 pub struct MediaPlayerProperties {
@@ -16,23 +61,153 @@ pub struct MediaPlayerProperties {
 
     /// The room id of the given room.
     pub room_id: String,
+
+    /// Where a shared link asks playback to start in seconds.
+    pub start_at: Option<f64>,
+
+    /// Only render the player and its controls without the room's stats
+    /// bar, as in the embed.
+    pub minimal: bool,
+
+    /// The handle the player is rendered into, shared with the rest of
+    /// the room.
+    pub video: VideoElementHandle,
 }
 
 
 pub enum MediaPlayerEvent {
     LiveStream(WebsocketMessage),
     StatsUpdate(WebsocketMessage),
+
+    /// The stream url of the room's state, answering our own request.
+    SnapshotStream(String),
+
+    /// The active user, None if it couldn't be fetched.
+    UserId(Option<UserId>),
+
+    /// The player hit a media error, with its code, message and the http
+    /// status of the failed request or 0.
+    PlayerError(u32, String, u32),
+
+    /// The stream url has been fetched again after it expired, None if
+    /// the request failed.
+    StreamRefreshed(Option<StreamUrlResp>),
+
+    /// Try every source of the stream again after they all failed.
+    RetrySources,
+
+    /// The grid mode button has been pressed.
+    ToggleGrid,
+
+    /// The watch privately button or the re-sync button has been pressed.
+    ToggleDetached,
 }
 
 #[derive(Deserialize)]
-struct StreamUrlResp {
+pub struct StreamUrlResp {
     stream_url: String,
+
+    /// The MIME type of the stream, if unset it is guessed from the url.
+    #[serde(default)]
+    content_type: Option<String>,
+
+    /// Other urls of the same stream, tried in order when the player
+    /// can't play the main one.
+    #[serde(default)]
+    fallback_urls: Vec<String>,
+
+    /// The WebVTT subtitle tracks available for the stream.
+    #[serde(default)]
+    subtitles: Vec<SubtitleTrack>,
+
+    /// A second stream that can be watched alongside this one.
+    #[serde(default)]
+    secondary: Option<SecondaryStream>,
+
+    /// The integrated loudness of the stream in LUFS as measured by the
+    /// api, None if it hasn't been measured.
+    #[serde(default)]
+    loudness_lufs: Option<f64>,
 }
 
 #[derive(Deserialize)]
 struct Stats {
     members: usize,
     multiplier: String,
+
+    /// The amount of boosts towards the next multiplier tier.
+    #[serde(default)]
+    boosts: u32,
+
+    /// The boosts needed for the next tier, None if at the max tier.
+    #[serde(default)]
+    next_tier_boosts: Option<u32>,
+
+    /// The user id of the room's leader, only the leader's playback
+    /// controls are emitted to the room. None if the room has no leader
+    /// in which case everyone is in control.
+    #[serde(default)]
+    leader_id: Option<String>,
+
+    /// If the room pauses for everyone while any member is buffering.
+    #[serde(default)]
+    wait_for_everyone: bool,
+
+    /// The fraction of members that must be buffering for the leader to
+    /// pause the room, None if the room has no such policy.
+    #[serde(default)]
+    buffer_pause_fraction: Option<f64>,
+
+    /// Who can ask the room to hold on.
+    #[serde(default)]
+    hold: HoldPolicy,
+}
+
+
+#[derive(Deserialize)]
+pub struct UserId {
+    id: String,
+
+    /// The ids of the user's roles in the room's Discord server.
+    #[serde(default)]
+    roles: Vec<String>,
+}
+
+
+/// Fetches the id of the active user to compare against the room leader.
+async fn fetch_user_id() -> MediaPlayerEvent {
+    let url = settings::get_who_am_i_url();
+
+    let resp = Client::new()
+        .get(&url)
+        .send()
+        .await;
+
+    let id = match resp {
+        Ok(resp) => resp.json::<UserId>().await.ok(),
+        Err(_) => None,
+    };
+
+    MediaPlayerEvent::UserId(id)
+}
+
+
+/// Fetches a fresh stream url, the signed segment urls of the old one
+/// expire on long sessions.
+async fn fetch_stream_url(room_id: String) -> MediaPlayerEvent {
+    let url = settings::get_stream_api_url(&room_id);
+
+    let resp = Client::new()
+        .get(&url)
+        .send()
+        .await;
+
+    let stream = match resp {
+        Ok(resp) if resp.status().is_success() => resp.json::<StreamUrlResp>().await.ok(),
+        _ => None,
+    };
+
+    MediaPlayerEvent::StreamRefreshed(stream)
 }
 
 
@@ -53,6 +228,14 @@ struct VideoInfo {
 /// handle the actual video events itself, this just displays the title
 /// and gives controls for track selection.
 pub struct MediaPlayer {
+    link: ComponentLink<Self>,
+
+    /// The WS handle, passed down to the child components.
+    ws: WsHandler,
+
+    /// The room id of the given room.
+    room_id: String,
+
     /// If the ws is connected or not
     is_connected: bool,
 
@@ -64,7 +247,210 @@ pub struct MediaPlayer {
 
     stream_url: String,
 
+    /// The MIME type of the stream given by the api.
+    content_type: Option<String>,
+
+    /// The fallback urls of the stream.
+    fallback_urls: Vec<String>,
+
+    /// Which source is playing, 0 is `stream_url` and the rest index into
+    /// `fallback_urls` from 1.
+    source: usize,
+
+    /// If every source of the stream failed to play.
+    sources_failed: bool,
+
+    /// The subtitle tracks of the stream.
+    subtitles: Vec<SubtitleTrack>,
+
+    /// The second stream of the room, if it has one.
+    secondary: Option<SecondaryStream>,
+
+    /// The measured loudness of the stream in LUFS.
+    loudness_lufs: Option<f64>,
+
+    /// If the second stream is shown next to the main one.
+    grid: bool,
+
+    /// If the user is watching privately.
+    detached: bool,
+
     abort: bool,
+
+    /// The id of the active user.
+    user_id: Option<String>,
+
+    /// The active user's Discord role ids.
+    user_roles: Vec<String>,
+
+    /// The url the player was last created for, the player is recreated
+    /// once rendered if this differs from `stream_url`.
+    player_src: Option<String>,
+
+    js_error: Closure<dyn FnMut(u32, String, u32)>,
+
+    /// The player, its element is the `<video-js>` element the player is
+    /// created on and its container holds the player, its overlays and
+    /// controls.
+    video: VideoElementHandle,
+
+    /// The canvas ambient mode glows behind the player.
+    ambient_ref: NodeRef,
+
+    /// How many times the stream url has been refreshed since the stream
+    /// last went live, this stops a broken stream refreshing forever.
+    refreshes: u32,
+
+    /// If a stream url refresh is in flight.
+    refreshing: bool,
+
+    /// Where to seek to and if to play once the refreshed stream loads.
+    resume: Option<(f64, bool)>,
+
+    /// The start time of the link the room was opened with, kept until
+    /// it can be applied without moving the rest of the room.
+    start_at: Option<f64>,
+
+    /// If the room's stats have been received, until then it isn't known
+    /// who leads or how many members there are.
+    stats_received: bool,
+
+    /// If the stats bar is hidden.
+    minimal: bool,
+}
+
+impl MediaPlayer {
+    /// The url of the source the player should be playing.
+    fn current_source(&self) -> &str {
+        match self.source {
+            0 => &self.stream_url,
+            i => &self.fallback_urls[i - 1],
+        }
+    }
+
+    /// Sets the stream to play from its main url.
+    fn set_stream(&mut self, stream: StreamUrlResp) {
+        self.stream_url = stream.stream_url;
+        self.content_type = stream.content_type;
+        self.fallback_urls = stream.fallback_urls;
+        self.subtitles = stream.subtitles;
+        self.secondary = stream.secondary;
+        self.loudness_lufs = stream.loudness_lufs;
+        self.source = 0;
+        self.sources_failed = false;
+    }
+
+    /// Moves the player onto the next source of the stream, returns false
+    /// if there are none left.
+    fn next_source(&mut self) -> bool {
+        if self.source >= self.fallback_urls.len() {
+            return false;
+        }
+
+        self.resume = PlayerState::read(&self.video).map(|state| (state.position, !state.paused));
+        self.source += 1;
+        self.player_src = None;
+        ConsoleService::warn(&format!("Falling back to stream source {}", self.source));
+        true
+    }
+
+    /// The button switching grid mode on and off, only shown if the room
+    /// has a second stream.
+    fn grid_toggle(&self) -> Html {
+        if self.secondary.is_none() || !self.is_connected {
+            return html!{};
+        }
+
+        let toggle_cb = self.link.callback(|_| MediaPlayerEvent::ToggleGrid);
+        let colour = if self.grid { "text-accent" } else { "text-white" };
+
+        html! {
+            <button
+                onclick=toggle_cb
+                class=("w-6 h-6 mx-2 focus:outline-none", colour)
+                title="Watch the second stream alongside this one">
+                <svg xmlns="http://www.w3.org/2000/svg" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                    <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M4 6a2 2 0 012-2h2a2 2 0 012 2v2a2 2 0 01-2 2H6a2 2 0 01-2-2V6zM14 6a2 2 0 012-2h2a2 2 0 012 2v2a2 2 0 01-2 2h-2a2 2 0 01-2-2V6zM4 16a2 2 0 012-2h2a2 2 0 012 2v2a2 2 0 01-2 2H6a2 2 0 01-2-2v-2zM14 16a2 2 0 012-2h2a2 2 0 012 2v2a2 2 0 01-2 2h-2a2 2 0 01-2-2v-2z" />
+                </svg>
+            </button>
+        }
+    }
+
+    /// If the player is detached from the room, the leader drives the room
+    /// so they can never detach.
+    fn is_detached(&self) -> bool {
+        self.detached && !self.is_leader()
+    }
+
+    /// The button to start watching privately, hidden from the leader.
+    fn detach_toggle(&self) -> Html {
+        if self.is_leader() || !self.is_connected {
+            return html!{};
+        }
+
+        let toggle_cb = self.link.callback(|_| MediaPlayerEvent::ToggleDetached);
+        let colour = if self.detached { "text-accent" } else { "text-white" };
+
+        html! {
+            <button
+                onclick=toggle_cb
+                class=("w-6 h-6 mx-2 focus:outline-none", colour)
+                title="Watch privately without following the room">
+                <svg xmlns="http://www.w3.org/2000/svg" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                    <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M13.828 10.172a4 4 0 00-5.656 0l-4 4a4 4 0 105.656 5.656l1.102-1.101m-.758-4.899a4 4 0 005.656 0l4-4a4 4 0 00-5.656-5.656l-1.1 1.1" />
+                </svg>
+            </button>
+        }
+    }
+
+    /// The banner over the player while detached, with the button to snap
+    /// back to the room.
+    fn detached_banner(&self) -> Html {
+        if !self.is_detached() {
+            return html!{};
+        }
+
+        let resync_cb = self.link.callback(|_| MediaPlayerEvent::ToggleDetached);
+
+        html! {
+            <div class="absolute top-0 inset-x-0 flex justify-center z-20 mt-4">
+                <div class="flex items-center bg-gray-900 bg-opacity-75 text-white text-sm font-semibold rounded-lg px-4 py-2">
+                    <span class="mr-4">{ "Watching privately" }</span>
+                    <button onclick=resync_cb class="bg-accent rounded px-2 py-1 focus:outline-none">
+                        { "Re-sync to room" }
+                    </button>
+                </div>
+            </div>
+        }
+    }
+
+    /// Moves the player to the start time of the link the room was opened
+    /// with, only while watching privately or alone so no one else is
+    /// dragged along. Otherwise it's kept for if the user detaches.
+    ///
+    /// The seek is never emitted to the room.
+    fn apply_start_at(&mut self) {
+        if self.player_src.is_none() || !self.stats_received {
+            return;
+        }
+
+        if !self.is_detached() && self.stats.members > 1 {
+            return;
+        }
+
+        if let Some(position) = self.start_at.take() {
+            self.video.seek_locally(position);
+        }
+    }
+
+    /// If the active user can control playback for the room, this is
+    /// everyone if the room has no leader.
+    fn is_leader(&self) -> bool {
+        match &self.stats.leader_id {
+            Some(leader) => self.user_id.as_ref() == Some(leader),
+            None => true,
+        }
+    }
 }
 
 impl Component for MediaPlayer {
@@ -72,22 +458,32 @@ impl Component for MediaPlayer {
     type Properties = MediaPlayerProperties;
 
     fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
-        let event_cb = link.callback(
-            |event| MediaPlayerEvent::StatsUpdate(event)
-        );
-
-        let live_cb = link.callback(
-            |event| MediaPlayerEvent::LiveStream(event)
-        );
+        let event_cb = link.callback(MediaPlayerEvent::StatsUpdate);
+        let live_cb = link.callback(MediaPlayerEvent::LiveStream);
 
         let ws = props.ws;
         ws.subscribe_to_message(settings::PLAYER_ID, opcodes::OP_STATS_UPDATE, event_cb);
         ws.subscribe_to_message(settings::PLAYER_ID, opcodes::OP_LIVE_READY, live_cb);
 
+        send_future(link.clone(), fetch_user_id());
+
+        let js_error = {
+            let cb = link.callback(|(code, msg, status)| MediaPlayerEvent::PlayerError(code, msg, status));
+            Closure::wrap(Box::new(move |code: u32, msg: String, status: u32| {
+                cb.emit((code, msg, status))
+            }) as Box<dyn FnMut(u32, String, u32)>)
+        };
+
 
         let stats = Stats {
             members: 1,
             multiplier: "1x".to_string(),
+            boosts: 0,
+            next_tier_boosts: None,
+            leader_id: None,
+            wait_for_everyone: false,
+            buffer_pause_fraction: None,
+            hold: HoldPolicy::default(),
         };
         
         let info = VideoInfo {
@@ -96,11 +492,35 @@ impl Component for MediaPlayer {
         };
 
         Self {
+            link,
+            ws,
+            room_id: props.room_id,
             is_connected: false,
             stats,
             info,
             stream_url: "".to_string(),
-            abort: false
+            content_type: None,
+            fallback_urls: vec![],
+            source: 0,
+            sources_failed: false,
+            subtitles: vec![],
+            secondary: None,
+            loudness_lufs: None,
+            grid: false,
+            detached: false,
+            abort: false,
+            user_id: None,
+            user_roles: Vec::new(),
+            player_src: None,
+            js_error,
+            video: props.video,
+            ambient_ref: NodeRef::default(),
+            refreshes: 0,
+            refreshing: false,
+            resume: None,
+            start_at: props.start_at,
+            stats_received: false,
+            minimal: props.minimal,
         }
     }
 
@@ -117,7 +537,9 @@ impl Component for MediaPlayer {
         match msg {
             MediaPlayerEvent::StatsUpdate(val) => {
                 if let Some(stats) = val.unwrap_and_into::<Stats>() {
-                    self.stats = stats
+                    self.stats = stats;
+                    self.stats_received = true;
+                    self.apply_start_at();
                 } else {
                     ConsoleService::warn("Failed to parse status update in player");
                 };
@@ -129,9 +551,83 @@ impl Component for MediaPlayer {
                     return true
                 }
 
-                let res = res.unwrap();
-                self.stream_url = res.stream_url;
+                self.set_stream(res.unwrap());
                 self.is_connected = true;
+                self.refreshes = 0;
+            },
+            MediaPlayerEvent::SnapshotStream(url) => {
+                // Late joiners may have missed the live ready event, the
+                // sync component applies the rest of the snapshot.
+                if self.is_connected {
+                    return false;
+                }
+
+                self.stream_url = url;
+                self.content_type = None;
+                self.fallback_urls = vec![];
+                self.loudness_lufs = None;
+                self.source = 0;
+                self.is_connected = true;
+                self.abort = false;
+            },
+            MediaPlayerEvent::UserId(user) => {
+                self.user_roles = user.as_ref().map(|user| user.roles.clone()).unwrap_or_default();
+                self.user_id = user.map(|user| user.id);
+            },
+            MediaPlayerEvent::PlayerError(code, msg, status) => {
+                ConsoleService::error(&format!("Player error {}: {}", code, msg));
+
+                let expired = status == 403 || status == 410;
+                if self.refreshing {
+                    return false;
+                }
+
+                // Signed urls that expired are refreshed, anything else
+                // the media element can't fetch or decode moves onto the
+                // next source, aborted loads are left alone.
+                if !expired || self.refreshes >= MAX_STREAM_REFRESHES {
+                    if code < MEDIA_ERR_NETWORK || self.sources_failed {
+                        return false;
+                    }
+
+                    if !self.next_source() {
+                        self.sources_failed = true;
+                    }
+                    return true;
+                }
+
+                self.resume = PlayerState::read(&self.video).map(|state| (state.position, !state.paused));
+                self.refreshing = true;
+                self.refreshes += 1;
+                send_future(self.link.clone(), fetch_stream_url(self.room_id.clone()));
+                return false;
+            },
+            MediaPlayerEvent::StreamRefreshed(stream) => {
+                self.refreshing = false;
+
+                let stream = match stream {
+                    Some(stream) => stream,
+                    None => {
+                        ConsoleService::warn("Failed to refresh the expired stream url");
+                        self.resume = None;
+                        return false;
+                    },
+                };
+
+                // Always reload the source, the url may be unchanged with
+                // only the segment signatures renewed.
+                self.set_stream(stream);
+                self.player_src = None;
+            },
+            MediaPlayerEvent::RetrySources => {
+                self.source = 0;
+                self.sources_failed = false;
+                self.player_src = None;
+            },
+            MediaPlayerEvent::ToggleGrid => self.grid = !self.grid,
+            MediaPlayerEvent::ToggleDetached => {
+                self.detached = !self.detached;
+                self.apply_start_at();
             },
         }
 
@@ -142,6 +638,57 @@ impl Component for MediaPlayer {
         false
     }
 
+    /// Creates the player once the `<video-js>` element is on the page
+    /// or the stream has changed.
+    fn rendered(&mut self, first_render: bool) {
+        if first_render {
+            if let Some(container) = self.video.container() {
+                binder::set_auto_hide(&container, AUTO_HIDE_MS);
+            }
+
+            if let Some(canvas) = self.ambient_ref.cast::<Element>() {
+                binder::attach_ambient_canvas(&canvas);
+            }
+        }
+
+        let source = self.current_source().to_string();
+        if !self.is_connected || self.player_src.as_ref() == Some(&source) {
+            return;
+        }
+
+        // The api's content type only describes the main url.
+        let content_type = if self.source == 0 { self.content_type.as_deref() } else { None };
+        if self.video.element().is_none() {
+            return;
+        }
+
+        let kind = StreamKind::detect(&source, content_type);
+        if self.video.create(&source, kind.mime_type(), &self.js_error) {
+            self.player_src = Some(source);
+
+            let prefs = Preferences::load();
+            self.video.set_rendition(prefs.quality_height);
+            self.video.set_data_saver(prefs.data_saver());
+            self.video.set_audio_delay(prefs.audio_delay_ms);
+            prefs.apply_volume(&self.video);
+            self.video.set_stream_loudness(self.loudness_lufs, prefs.normalize_loudness);
+            prefs.apply_ambient_mode(&self.video);
+            VideoTransform::load(&self.stream_url).apply(&self.video);
+
+            if let Some((position, play)) = self.resume.take() {
+                self.video.resume_at(position, play);
+            } else {
+                self.apply_start_at();
+            }
+        } else {
+            ConsoleService::error("Failed to create the player, video.js is not loaded");
+        }
+    }
+
+    fn destroy(&mut self) {
+        self.video.destroy();
+    }
+
     /// Renders the whole media player half of the page.
     ///
     /// This displays the help page of the player if no videos are added or set
@@ -152,6 +699,8 @@ impl Component for MediaPlayer {
     /// handle the actual video events itself, this just displays the title
     /// and gives controls for track selection.
     fn view(&self) -> Html {
+        let _span = PerfSpan::start("player.view");
+
         let status = if self.is_connected {
             html! {
                 <div class="text-white text-lg font-semibold flex items-center">
@@ -180,17 +729,6 @@ impl Component for MediaPlayer {
             </div>
         };
 
-        let multiplier = html! {
-            <div class="flex justify-center items-center mx-2">
-                <div class="w-5 h-5 object-contain text-red-600 mx-2">
-                    <svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 20 20" fill="currentColor">
-                      <path fill-rule="evenodd" d="M12.395 2.553a1 1 0 00-1.45-.385c-.345.23-.614.558-.822.88-.214.33-.403.713-.57 1.116-.334.804-.614 1.768-.84 2.734a31.365 31.365 0 00-.613 3.58 2.64 2.64 0 01-.945-1.067c-.328-.68-.398-1.534-.398-2.654A1 1 0 005.05 6.05 6.981 6.981 0 003 11a7 7 0 1011.95-4.95c-.592-.591-.98-.985-1.348-1.467-.363-.476-.724-1.063-1.207-2.03zM12.12 15.12A3 3 0 017 13s.879.5 2.5.5c0-1 .5-4 1.25-4.5.5 1 .786 1.293 1.371 1.879A2.99 2.99 0 0113 13a2.99 2.99 0 01-.879 2.121z" clip-rule="evenodd" />
-                    </svg>
-                </div>
-                <h1 class="text-lg text-white font-semibold">{&self.stats.multiplier}</h1>
-            </div>
-        };
-
         let owner_and_title = html! {
             <div class="flex justify-center items-center mx-1">
                 <h1 class="text-lg text-white font-semibold">
@@ -199,41 +737,144 @@ impl Component for MediaPlayer {
             </div>
         };
 
+        let announcer = if self.is_leader() {
+            html! {
+                <>
+                    <Announcer room_id=self.room_id.clone() />
+                    <Celebrator room_id=self.room_id.clone() />
+                    <NowPlaying
+                        room_id=self.room_id.clone()
+                        title=self.info.title.clone()
+                        owner=self.info.owner.clone()
+                        live=self.is_connected
+                    />
+                </>
+            }
+        } else {
+            html!{}
+        };
+
         let stats_block = html! {
             <div class="flex justify-between mb-2 px-8">
                 { status }
                 { owner_and_title }
                 <div class="flex justify-center">
                     { members }
-                    { multiplier }
+                    <BoostPanel
+                        ws=self.ws.clone()
+                        multiplier=self.stats.multiplier.clone()
+                        boosts=self.stats.boosts
+                        next_tier_boosts=self.stats.next_tier_boosts
+                    />
+                    { announcer }
+                    <RecapButton
+                        ws=self.ws.clone()
+                        room_id=self.room_id.clone()
+                        title=self.info.title.clone()
+                        members=self.stats.members
+                        is_leader=self.is_leader()
+                    />
+                    { self.grid_toggle() }
+                    { self.detach_toggle() }
+                    <WatchStats
+                        ws=self.ws.clone()
+                        room_id=self.room_id.clone()
+                        is_leader=self.is_leader()
+                        video=self.video.clone()
+                    />
+                    <QualityMenu video=self.video.clone() />
+                    <TransformMenu stream_url=self.stream_url.clone() video=self.video.clone() />
+                    <SleepTimer ws=self.ws.clone() video=self.video.clone() />
+                    <HoldButton
+                        ws=self.ws.clone()
+                        room_id=self.room_id.clone()
+                        allowed=self.stats.hold.allows(&self.user_roles)
+                        detached=self.is_detached()
+                        video=self.video.clone()
+                    />
+                    <HandoffButton
+                        ws=self.ws.clone()
+                        room_id=self.room_id.clone()
+                        stream_url=self.stream_url.clone()
+                        video=self.video.clone()
+                    />
+                    <SupportWidget room_id=self.room_id.clone() video=self.video.clone() />
+                    <PreferencesPanel video=self.video.clone() />
                 </div>
             </div>
         };
 
+        let header = if self.minimal {
+            html!{}
+        } else {
+            html! {
+                <div class="w-full mb-4">
+                    { stats_block }
+                    <div class="w-full border-b-4 border-white rounded-full"></div>
+                </div>
+            }
+        };
+
         let player = if self.is_connected {
             html! {
                  <>
                     <video-js
-                        id="player"
-                        class="bg-gray-900 video-js vjs-live vjs-liveui w-full"
-                        controls=true
+                        ref=self.video.element_ref()
+                        class="bg-gray-900 video-js vjs-live vjs-liveui w-full min-h-player main-player"
+                        controls=false
+                        poster=POSTER_URL
                         preload="auto"
                         width="100%"
-                        height="100%"
-                        style="min-height: 30vw;">
-                        <source src=&self.stream_url type="application/x-mpegURL"/>
+                        height="100%">
+                        { for self.subtitles.iter().map(SubtitleTrack::to_html) }
                     </video-js>
-                    <script src="https://vjs.zencdn.net/7.10.2/video.min.js"></script>
-                    <script src="https://unpkg.com/browse/@videojs/http-streaming@2.6.1/dist/videojs-http-streaming.min.js"></script>
-                    <script>
-                        {"var player = videojs('player', {'liveui': true});"}
-                    </script>
                  </>
             }
         } else {
             html!{}
         };
 
+        let controls = if self.is_connected {
+            html! {
+                <ControlBar
+                    ws=self.ws.clone()
+                    room_id=self.room_id.clone()
+                    is_leader=self.is_leader()
+                    subtitles=self.subtitles.clone()
+                    title=self.info.title.clone()
+                    video=self.video.clone()
+                />
+            }
+        } else {
+            html!{}
+        };
+
+        let sources_failed = if self.sources_failed {
+            let retry_cb = self.link.callback(|_| MediaPlayerEvent::RetrySources);
+
+            html! {
+                <div class="absolute top-0 inset-x-0 flex justify-center z-20 mt-8 px-8">
+                    <div class="flex items-center bg-red-600 rounded-lg shadow-lg px-4 py-2">
+                        <p class="text-white font-semibold mr-4">
+                            { "This stream couldn't be played from any of its sources." }
+                        </p>
+                        <button onclick=retry_cb class="text-white text-sm font-semibold border-2 rounded-lg px-2 focus:outline-none">
+                            { "Try again" }
+                        </button>
+                    </div>
+                </div>
+            }
+        } else {
+            html!{}
+        };
+
+        let secondary = match &self.secondary {
+            Some(stream) if self.grid && self.is_connected => html! {
+                <SecondaryVideo stream=stream.clone() main=self.video.clone() />
+            },
+            _ => html!{},
+        };
+
         let poster_style = if !self.is_connected & !self.abort {
             "flex justify-center items-center w-full h-full bg-gray-900 rounded-lg shadow-inner"
         } else {
@@ -251,22 +892,58 @@ impl Component for MediaPlayer {
         html!{
              <div class="w-2/3 h-full my-auto py-4 px-20">
                 <div class="h-full bg-discord-dark rounded-lg p-4">
-                    <div class="w-full mb-4">
-                        { stats_block }
-                        <div class="w-full border-b-4 border-white rounded-full"></div>
-                    </div>
-                    <div class="flex justify-center">
-                        { player }
-                        <div class=poster_style style="min-height: 30vw;">
-                            <div>
-                                <h1 class="text-white font-bold text-4xl text-center">
-                                    { message }
-                                </h1>
-                                <div class="flex justify-center">
-                                    <img class="w-64 h-64 object-contain rounded-full" src="https://cdn.discordapp.com/attachments/667270372042866699/805836261008211988/Spooderfy_Transparent.png" alt=""/>
+                    { header }
+                    <MiniPlayer video=self.video.clone() />
+                    <div class="player-container" ref=self.video.container_ref()>
+                        <div class="flex">
+                            <div class="relative z-0 flex justify-center flex-grow">
+                                <canvas ref=self.ambient_ref.clone() class="ambient-glow"></canvas>
+                                <PlaybackSync
+                                    ws=self.ws.clone()
+                                    room_id=self.room_id.clone()
+                                    stream_url=self.stream_url.clone()
+                                    is_leader=self.is_leader()
+                                    wait_for_everyone=self.stats.wait_for_everyone
+                                    buffer_pause_fraction=self.stats.buffer_pause_fraction
+                                    members=self.stats.members
+                                    detached=self.is_detached()
+                                    video=self.video.clone()
+                                    on_stream=self.link.callback(MediaPlayerEvent::SnapshotStream)
+                                />
+                                { self.detached_banner() }
+                                <EffectsOverlay ws=self.ws.clone() video=self.video.clone() />
+                                <AnnouncementOverlay ws=self.ws.clone() />
+                                <SubtitleOverlay video=self.video.clone() />
+                                <ChatOverlay ws=self.ws.clone() />
+                                <DanmakuOverlay
+                                    ws=self.ws.clone()
+                                    room_id=self.room_id.clone()
+                                    stream_url=self.stream_url.clone()
+                                    video=self.video.clone()
+                                />
+                                <StatsForNerds video=self.video.clone() />
+                                <ResumePrompt
+                                    room_id=self.room_id.clone()
+                                    stream_url=self.stream_url.clone()
+                                    is_leader=self.is_leader()
+                                    video=self.video.clone()
+                                />
+                                { sources_failed }
+                                { player }
+                                <div class=(poster_style, "min-h-player")>
+                                    <div>
+                                        <h1 class="text-white font-bold text-4xl text-center">
+                                            { message }
+                                        </h1>
+                                        <div class="flex justify-center">
+                                            <img class="w-64 h-64 object-contain rounded-full" src=POSTER_URL alt=""/>
+                                        </div>
+                                    </div>
                                 </div>
                             </div>
+                            { secondary }
                         </div>
+                        { controls }
                     </div>
                 </div>
              </div>
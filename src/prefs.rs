@@ -0,0 +1,754 @@
+use yew::prelude::*;
+
+use serde::{Serialize, Deserialize};
+
+use crate::binder;
+use crate::timefmt;
+use crate::utils::{load_local, store_local};
+use crate::video::VideoElementHandle;
+
+
+/// The local storage key the preferences are stored under.
+const PREFERENCES_KEY: &str = "spooderfy.preferences";
+
+/// The idle check intervals the user can pick from in hours.
+const IDLE_CHECK_OPTIONS: [u32; 4] = [1, 2, 3, 4];
+
+/// The speech rates the user can pick from.
+const TTS_RATE_OPTIONS: [f32; 5] = [0.75, 1.0, 1.25, 1.5, 2.0];
+
+/// The largest audio delay that can be picked in milliseconds.
+const MAX_AUDIO_DELAY_MS: u32 = 1000;
+
+/// How long bullet comments take to cross the video in seconds.
+const DANMAKU_SPEED_OPTIONS: [f64; 3] = [12.0, 8.0, 5.0];
+
+/// The lane counts for bullet comments, more lanes fit more comments.
+const DANMAKU_LANE_OPTIONS: [u32; 3] = [4, 8, 12];
+
+/// How often ambient mode samples the video, and how often in performance
+/// mode, in milliseconds.
+const AMBIENT_INTERVAL_MS: u32 = 250;
+const AMBIENT_LOW_POWER_INTERVAL_MS: u32 = 1000;
+
+/// The drift thresholds the user can pick from in seconds.
+const DRIFT_THRESHOLD_OPTIONS: [f64; 4] = [1.0, 2.0, 5.0, 10.0];
+
+/// The loudest the volume can be boosted to, 2 being 200%.
+pub const MAX_VOLUME_BOOST: f64 = 2.0;
+
+
+/// The corners of the player overlays can be placed in.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverlayCorner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl OverlayCorner {
+    const ALL: [OverlayCorner; 4] = [
+        OverlayCorner::TopLeft,
+        OverlayCorner::TopRight,
+        OverlayCorner::BottomLeft,
+        OverlayCorner::BottomRight,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            OverlayCorner::TopLeft => "Top left",
+            OverlayCorner::TopRight => "Top right",
+            OverlayCorner::BottomLeft => "Bottom left",
+            OverlayCorner::BottomRight => "Bottom right",
+        }
+    }
+}
+
+
+/// The user's local preferences, these are per browser rather than per
+/// room and are persisted in local storage.
+///
+/// Components load these when they need them rather than holding a copy
+/// so changes from the panel apply without any extra plumbing.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Preferences {
+    /// Hours of playback without any interaction before asking if the
+    /// user is still watching, 0 disables the check.
+    pub idle_check_hours: u32,
+
+    /// If incoming chat messages are read aloud.
+    pub tts_enabled: bool,
+
+    /// The speech rate chat is read aloud at.
+    pub tts_rate: f32,
+
+    /// If chat messages in other languages are translated as they arrive.
+    pub auto_translate: bool,
+
+    /// If overlay effects should be rendered, hosts can still trigger them
+    /// but nothing is drawn locally.
+    pub effects_enabled: bool,
+
+    /// Trades smoothness for less CPU usage in effects and animations.
+    pub performance_mode: bool,
+
+    /// How far in seconds the player can drift from the room before it is
+    /// seeked back in line, smaller drifts are corrected gradually.
+    pub drift_threshold_secs: f64,
+
+    /// The height of the stream rendition to play, 0 picks automatically.
+    pub quality_height: u32,
+
+    /// If the picked rendition is kept even when the connection can't keep
+    /// up with it, otherwise a lower one is stepped down to.
+    pub quality_locked: bool,
+
+    /// If only the audio of the stream is played with the room's poster
+    /// shown instead, None follows the browser's save data hint.
+    pub data_saver: Option<bool>,
+
+    /// If the stats for nerds overlay is shown over the player.
+    pub show_stats: bool,
+
+    /// If the colours of the video glow behind the player.
+    pub ambient_mode: bool,
+
+    /// How long the audio is delayed behind the video in milliseconds, to
+    /// correct lip sync.
+    pub audio_delay_ms: u32,
+
+    /// If chat is shown over the video in fullscreen.
+    pub chat_overlay: bool,
+
+    /// The opacity of the fullscreen chat overlay from 0 to 1.
+    pub chat_overlay_opacity: f64,
+
+    /// Where the fullscreen chat overlay is placed.
+    pub chat_overlay_corner: OverlayCorner,
+
+    /// If chat messages scroll across the video as bullet comments.
+    pub danmaku: bool,
+
+    /// How long bullet comments take to cross the video in seconds.
+    pub danmaku_speed_secs: f64,
+
+    /// How many lanes bullet comments are spread over.
+    pub danmaku_lanes: u32,
+
+    /// The opacity of bullet comments from 0 to 1.
+    pub danmaku_opacity: f64,
+
+    /// The player volume, over 1 if boosted.
+    pub volume: f64,
+
+    pub muted: bool,
+
+    /// If the volume can be boosted past 100% for quiet videos.
+    pub volume_boost: bool,
+
+    /// If streams are turned up or down to a common loudness so switching
+    /// between them doesn't jump in volume.
+    pub normalize_loudness: bool,
+
+    /// If times of day use the 24 hour clock rather than AM and PM.
+    pub clock_24h: bool,
+
+    /// If times of day include seconds.
+    pub clock_seconds: bool,
+
+    /// If chat timestamps show how long ago rather than the time of day.
+    pub relative_times: bool,
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        Self {
+            idle_check_hours: 3,
+            tts_enabled: false,
+            tts_rate: 1.0,
+            auto_translate: false,
+            effects_enabled: true,
+            performance_mode: false,
+            drift_threshold_secs: 2.0,
+            quality_height: 0,
+            quality_locked: false,
+            data_saver: None,
+            show_stats: false,
+            ambient_mode: false,
+            audio_delay_ms: 0,
+            chat_overlay: true,
+            chat_overlay_opacity: 0.9,
+            chat_overlay_corner: OverlayCorner::TopRight,
+            danmaku: false,
+            danmaku_speed_secs: 8.0,
+            danmaku_lanes: 8,
+            danmaku_opacity: 0.8,
+            volume: 1.0,
+            muted: false,
+            volume_boost: false,
+            normalize_loudness: false,
+            clock_24h: false,
+            clock_seconds: false,
+            relative_times: false,
+        }
+    }
+}
+
+impl Preferences {
+    /// Loads the stored preferences or the defaults if none are stored.
+    pub fn load() -> Self {
+        load_local(PREFERENCES_KEY).unwrap_or_default()
+    }
+
+    /// If data saver mode is on, either picked by the user or asked for
+    /// by the browser.
+    pub fn data_saver(&self) -> bool {
+        self.data_saver.unwrap_or_else(binder::prefers_save_data)
+    }
+
+    /// Starts or stops ambient mode to match the preferences.
+    pub fn apply_ambient_mode(&self, video: &VideoElementHandle) {
+        let interval_ms = if self.performance_mode {
+            AMBIENT_LOW_POWER_INTERVAL_MS
+        } else {
+            AMBIENT_INTERVAL_MS
+        };
+
+        video.set_ambient_mode(self.ambient_mode, interval_ms);
+    }
+
+    /// The loudest the volume can be set to.
+    pub fn max_volume(&self) -> f64 {
+        if self.volume_boost { MAX_VOLUME_BOOST } else { 1.0 }
+    }
+
+    /// Applies the stored volume to the player, anything over 100% is
+    /// made up with Web Audio gain as the element stops at 1.
+    pub fn apply_volume(&self, video: &VideoElementHandle) {
+        let volume = self.volume.clamp(0.0, self.max_volume());
+
+        video.set_volume(volume.min(1.0));
+        video.set_audio_gain(volume.max(1.0));
+        video.set_muted(self.muted || volume == 0.0);
+    }
+
+    /// Formats a timestamp in milliseconds as a time of day in the user's
+    /// clock format.
+    pub fn format_clock_time(&self, ms: f64) -> String {
+        timefmt::format_time_of_day(ms, self.clock_24h, self.clock_seconds)
+    }
+
+    /// Formats a timestamp in milliseconds for a chat message, relative if
+    /// the user prefers it.
+    pub fn format_timestamp(&self, ms: f64) -> String {
+        if self.relative_times {
+            timefmt::format_ago(ms)
+        } else {
+            self.format_clock_time(ms)
+        }
+    }
+
+    /// Persists the preferences to local storage.
+    pub fn save(&self) {
+        store_local(PREFERENCES_KEY, self);
+    }
+}
+
+
+#[derive(Properties, Clone)]
+pub struct PreferencesPanelProperties {
+    /// The player the preferences are applied to.
+    pub video: VideoElementHandle,
+}
+
+
+/// The events that can be invoked by callbacks for the PreferencesPanel.
+pub enum PreferencesEvents {
+    /// The panel has been opened or closed.
+    Toggle,
+
+    /// The idle check interval has been changed.
+    IdleCheckHours(u32),
+
+    /// Reading chat aloud has been toggled.
+    ToggleTts,
+
+    /// The speech rate has been changed.
+    TtsRate(f32),
+
+    /// Translating chat automatically has been toggled.
+    ToggleAutoTranslate,
+
+    /// Overlay effects have been toggled.
+    ToggleEffects,
+
+    /// Performance mode has been toggled.
+    TogglePerformance,
+
+    /// The drift threshold has been changed.
+    DriftThreshold(f64),
+
+    /// Data saver mode has been toggled.
+    ToggleDataSaver,
+
+    /// The stats for nerds overlay has been toggled.
+    ToggleStats,
+
+    /// Ambient mode has been toggled.
+    ToggleAmbient,
+
+    /// The audio delay has been changed.
+    AudioDelay(u32),
+
+    /// Loudness normalization has been toggled.
+    ToggleNormalize,
+
+    /// The fullscreen chat overlay has been toggled.
+    ToggleChatOverlay,
+
+    /// The opacity of the fullscreen chat overlay has been changed.
+    ChatOverlayOpacity(f64),
+
+    /// The corner of the fullscreen chat overlay has been changed.
+    ChatOverlayCorner(usize),
+
+    /// Bullet comments have been toggled.
+    ToggleDanmaku,
+
+    /// The speed of bullet comments has been changed.
+    DanmakuSpeed(f64),
+
+    /// The density of bullet comments has been changed.
+    DanmakuLanes(u32),
+
+    /// The opacity of bullet comments has been changed.
+    DanmakuOpacity(f64),
+
+    /// The 12 or 24 hour clock has been picked.
+    Clock24h(bool),
+
+    /// Showing seconds in times has been toggled.
+    ToggleClockSeconds,
+
+    /// Relative chat timestamps have been toggled.
+    ToggleRelativeTimes,
+}
+
+
+/// The settings cog and its dropdown panel for editing the preferences,
+/// every change is saved immediately.
+pub struct PreferencesPanel {
+    link: ComponentLink<Self>,
+    video: VideoElementHandle,
+    prefs: Preferences,
+    open: bool,
+}
+
+impl PreferencesPanel {
+    /// Renders the bullet comment controls.
+    fn view_danmaku(&self) -> Html {
+        let danmaku_cb = self.link.callback(|_| PreferencesEvents::ToggleDanmaku);
+        let danmaku_speed_cb = self.link.callback(|e: ChangeData| {
+            if let ChangeData::Select(select) = e {
+                PreferencesEvents::DanmakuSpeed(select.value().parse().unwrap_or(8.0))
+            } else {
+                PreferencesEvents::DanmakuSpeed(8.0)
+            }
+        });
+        let danmaku_lanes_cb = self.link.callback(|e: ChangeData| {
+            if let ChangeData::Select(select) = e {
+                PreferencesEvents::DanmakuLanes(select.value().parse().unwrap_or(8))
+            } else {
+                PreferencesEvents::DanmakuLanes(8)
+            }
+        });
+        let danmaku_opacity_cb = self.link.callback(|e: InputData| {
+            PreferencesEvents::DanmakuOpacity(e.value.parse().unwrap_or(1.0))
+        });
+
+        let current_speed = self.prefs.danmaku_speed_secs;
+        let speed_options = DANMAKU_SPEED_OPTIONS.iter().zip(["Slow", "Normal", "Fast"].iter()).map(|(secs, name)| {
+            let selected = (*secs - current_speed).abs() < f64::EPSILON;
+
+            html! {
+                <option value=secs.to_string() selected=selected>{ name }</option>
+            }
+        });
+
+        let current_lanes = self.prefs.danmaku_lanes;
+        let lane_options = DANMAKU_LANE_OPTIONS.iter().zip(["Sparse", "Normal", "Dense"].iter()).map(|(lanes, name)| {
+            html! {
+                <option value=lanes.to_string() selected=*lanes == current_lanes>{ name }</option>
+            }
+        });
+
+        html! {
+            <>
+                <label class="text-white text-sm flex items-center mt-3 mb-1">
+                    <input type="checkbox" class="mr-2" checked=self.prefs.danmaku onclick=danmaku_cb />
+                    { "Scroll chat across the video" }
+                </label>
+                <div class="flex mb-1">
+                    <select onchange=danmaku_speed_cb disabled=!self.prefs.danmaku class="bg-gray-900 text-white rounded-lg focus:outline-none w-1/2 px-2 mr-1">
+                        { for speed_options }
+                    </select>
+                    <select onchange=danmaku_lanes_cb disabled=!self.prefs.danmaku class="bg-gray-900 text-white rounded-lg focus:outline-none w-1/2 px-2">
+                        { for lane_options }
+                    </select>
+                </div>
+                <input
+                    type="range"
+                    class="slider w-full"
+                    min="0.1"
+                    max="1"
+                    step="0.05"
+                    value=self.prefs.danmaku_opacity.to_string()
+                    disabled=!self.prefs.danmaku
+                    oninput=danmaku_opacity_cb
+                />
+            </>
+        }
+    }
+
+    /// Renders the audio delay and loudness controls.
+    fn view_audio(&self) -> Html {
+        let audio_delay_cb = self.link.callback(|e: InputData| {
+            PreferencesEvents::AudioDelay(e.value.parse().unwrap_or(0))
+        });
+        let normalize_cb = self.link.callback(|_| PreferencesEvents::ToggleNormalize);
+
+        html! {
+            <>
+                <label class="text-white text-sm block mt-3 mb-1">
+                    { format!("Audio delay: {}ms", self.prefs.audio_delay_ms) }
+                </label>
+                <input
+                    type="range"
+                    class="slider w-full"
+                    min="0"
+                    max=MAX_AUDIO_DELAY_MS.to_string()
+                    step="10"
+                    value=self.prefs.audio_delay_ms.to_string()
+                    oninput=audio_delay_cb
+                />
+                <label class="text-white text-sm flex items-center mt-1">
+                    <input type="checkbox" class="mr-2" checked=self.prefs.normalize_loudness onclick=normalize_cb />
+                    { "Normalize loudness" }
+                </label>
+            </>
+        }
+    }
+
+    /// Renders the time display controls.
+    fn view_clock(&self) -> Html {
+        let clock_cb = self.link.callback(|e: ChangeData| {
+            if let ChangeData::Select(select) = e {
+                PreferencesEvents::Clock24h(select.value() == "24")
+            } else {
+                PreferencesEvents::Clock24h(false)
+            }
+        });
+        let seconds_cb = self.link.callback(|_| PreferencesEvents::ToggleClockSeconds);
+        let relative_cb = self.link.callback(|_| PreferencesEvents::ToggleRelativeTimes);
+
+        html! {
+            <>
+                <label class="text-white text-sm block mt-3 mb-1">{ "Times" }</label>
+                <select onchange=clock_cb class="bg-gray-900 text-white rounded-lg focus:outline-none w-full px-2">
+                    <option value="12" selected=!self.prefs.clock_24h>{ "12 hour clock" }</option>
+                    <option value="24" selected=self.prefs.clock_24h>{ "24 hour clock" }</option>
+                </select>
+                <label class="text-white text-sm flex items-center mt-1">
+                    <input type="checkbox" class="mr-2" checked=self.prefs.clock_seconds onclick=seconds_cb />
+                    { "Show seconds" }
+                </label>
+                <label class="text-white text-sm flex items-center mt-1">
+                    <input type="checkbox" class="mr-2" checked=self.prefs.relative_times onclick=relative_cb />
+                    { "Show chat times as time ago" }
+                </label>
+            </>
+        }
+    }
+}
+
+
+impl Component for PreferencesPanel {
+    type Message = PreferencesEvents;
+    type Properties = PreferencesPanelProperties;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        Self {
+            link,
+            video: props.video,
+            prefs: Preferences::load(),
+            open: false,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            PreferencesEvents::Toggle => {
+                self.open = !self.open;
+
+                // Some preferences can be changed outside of the panel,
+                // e.g. the audio only button, pick them up before editing.
+                self.prefs = Preferences::load();
+                return true;
+            },
+            PreferencesEvents::IdleCheckHours(hours) => {
+                self.prefs.idle_check_hours = hours;
+            },
+            PreferencesEvents::ToggleTts => {
+                self.prefs.tts_enabled = !self.prefs.tts_enabled;
+            },
+            PreferencesEvents::TtsRate(rate) => {
+                self.prefs.tts_rate = rate;
+            },
+            PreferencesEvents::ToggleAutoTranslate => {
+                self.prefs.auto_translate = !self.prefs.auto_translate;
+            },
+            PreferencesEvents::ToggleEffects => {
+                self.prefs.effects_enabled = !self.prefs.effects_enabled;
+            },
+            PreferencesEvents::TogglePerformance => {
+                self.prefs.performance_mode = !self.prefs.performance_mode;
+                self.prefs.apply_ambient_mode(&self.video);
+            },
+            PreferencesEvents::DriftThreshold(secs) => {
+                self.prefs.drift_threshold_secs = secs;
+            },
+            PreferencesEvents::ToggleDataSaver => {
+                let enabled = !self.prefs.data_saver();
+                self.prefs.data_saver = Some(enabled);
+                self.video.set_data_saver(enabled);
+            },
+            PreferencesEvents::ToggleStats => {
+                self.prefs.show_stats = !self.prefs.show_stats;
+            },
+            PreferencesEvents::ToggleAmbient => {
+                self.prefs.ambient_mode = !self.prefs.ambient_mode;
+                self.prefs.apply_ambient_mode(&self.video);
+            },
+            PreferencesEvents::AudioDelay(delay_ms) => {
+                self.prefs.audio_delay_ms = delay_ms.min(MAX_AUDIO_DELAY_MS);
+                self.video.set_audio_delay(self.prefs.audio_delay_ms);
+            },
+            PreferencesEvents::ToggleNormalize => {
+                self.prefs.normalize_loudness = !self.prefs.normalize_loudness;
+                self.video.set_loudness_normalization(self.prefs.normalize_loudness);
+            },
+            PreferencesEvents::ToggleChatOverlay => {
+                self.prefs.chat_overlay = !self.prefs.chat_overlay;
+            },
+            PreferencesEvents::ChatOverlayOpacity(opacity) => {
+                self.prefs.chat_overlay_opacity = opacity.clamp(0.1, 1.0);
+            },
+            PreferencesEvents::ChatOverlayCorner(index) => {
+                if let Some(corner) = OverlayCorner::ALL.get(index) {
+                    self.prefs.chat_overlay_corner = *corner;
+                }
+            },
+            PreferencesEvents::ToggleDanmaku => {
+                self.prefs.danmaku = !self.prefs.danmaku;
+            },
+            PreferencesEvents::DanmakuSpeed(secs) => {
+                self.prefs.danmaku_speed_secs = secs;
+            },
+            PreferencesEvents::DanmakuLanes(lanes) => {
+                self.prefs.danmaku_lanes = lanes;
+            },
+            PreferencesEvents::DanmakuOpacity(opacity) => {
+                self.prefs.danmaku_opacity = opacity.clamp(0.1, 1.0);
+            },
+            PreferencesEvents::Clock24h(clock_24h) => {
+                self.prefs.clock_24h = clock_24h;
+            },
+            PreferencesEvents::ToggleClockSeconds => {
+                self.prefs.clock_seconds = !self.prefs.clock_seconds;
+            },
+            PreferencesEvents::ToggleRelativeTimes => {
+                self.prefs.relative_times = !self.prefs.relative_times;
+            },
+        }
+
+        self.prefs.save();
+        true
+    }
+
+    fn change(&mut self, _props: Self::Properties) -> ShouldRender {
+        false
+    }
+
+    fn view(&self) -> Html {
+        let toggle_cb = self.link.callback(|_| PreferencesEvents::Toggle);
+
+        let panel = if self.open {
+            let idle_cb = self.link.callback(|e: ChangeData| {
+                if let ChangeData::Select(select) = e {
+                    PreferencesEvents::IdleCheckHours(select.value().parse().unwrap_or(0))
+                } else {
+                    PreferencesEvents::IdleCheckHours(0)
+                }
+            });
+
+            let current = self.prefs.idle_check_hours;
+            let idle_options = IDLE_CHECK_OPTIONS.iter().map(|hours| {
+                html! {
+                    <option value=hours.to_string() selected=*hours == current>
+                        { format!("After {} hours", hours) }
+                    </option>
+                }
+            });
+
+            let tts_cb = self.link.callback(|_| PreferencesEvents::ToggleTts);
+            let rate_cb = self.link.callback(|e: ChangeData| {
+                if let ChangeData::Select(select) = e {
+                    PreferencesEvents::TtsRate(select.value().parse().unwrap_or(1.0))
+                } else {
+                    PreferencesEvents::TtsRate(1.0)
+                }
+            });
+
+            let current_rate = self.prefs.tts_rate;
+            let rate_options = TTS_RATE_OPTIONS.iter().map(|rate| {
+                let selected = (*rate - current_rate).abs() < f32::EPSILON;
+
+                html! {
+                    <option value=rate.to_string() selected=selected>
+                        { format!("{}x", rate) }
+                    </option>
+                }
+            });
+
+            let drift_cb = self.link.callback(|e: ChangeData| {
+                if let ChangeData::Select(select) = e {
+                    PreferencesEvents::DriftThreshold(select.value().parse().unwrap_or(2.0))
+                } else {
+                    PreferencesEvents::DriftThreshold(2.0)
+                }
+            });
+
+            let current_drift = self.prefs.drift_threshold_secs;
+            let drift_options = DRIFT_THRESHOLD_OPTIONS.iter().map(|secs| {
+                let selected = (*secs - current_drift).abs() < f64::EPSILON;
+
+                html! {
+                    <option value=secs.to_string() selected=selected>
+                        { format!("Over {} seconds", secs) }
+                    </option>
+                }
+            });
+
+            let translate_cb = self.link.callback(|_| PreferencesEvents::ToggleAutoTranslate);
+            let effects_cb = self.link.callback(|_| PreferencesEvents::ToggleEffects);
+            let performance_cb = self.link.callback(|_| PreferencesEvents::TogglePerformance);
+            let data_saver_cb = self.link.callback(|_| PreferencesEvents::ToggleDataSaver);
+            let stats_cb = self.link.callback(|_| PreferencesEvents::ToggleStats);
+            let ambient_cb = self.link.callback(|_| PreferencesEvents::ToggleAmbient);
+
+            let chat_overlay_cb = self.link.callback(|_| PreferencesEvents::ToggleChatOverlay);
+            let overlay_opacity_cb = self.link.callback(|e: InputData| {
+                PreferencesEvents::ChatOverlayOpacity(e.value.parse().unwrap_or(1.0))
+            });
+            let overlay_corner_cb = self.link.callback(|e: ChangeData| {
+                if let ChangeData::Select(select) = e {
+                    PreferencesEvents::ChatOverlayCorner(select.value().parse().unwrap_or(0))
+                } else {
+                    PreferencesEvents::ChatOverlayCorner(0)
+                }
+            });
+
+            let current_corner = self.prefs.chat_overlay_corner;
+            let corner_options = OverlayCorner::ALL.iter().enumerate().map(|(i, corner)| {
+                html! {
+                    <option value=i.to_string() selected=*corner == current_corner>
+                        { corner.name() }
+                    </option>
+                }
+            });
+
+            html! {
+                <div class="absolute right-0 mt-2 w-64 bg-gray-800 border-2 border-gray-700 rounded-lg p-4 z-10">
+                    <h1 class="text-white font-bold mb-2">{ "Preferences" }</h1>
+                    <label class="text-white text-sm block mb-1">{ "Are you still watching?" }</label>
+                    <select onchange=idle_cb class="bg-gray-900 text-white rounded-lg focus:outline-none w-full px-2">
+                        <option value="0" selected=current == 0>{ "Never ask" }</option>
+                        { for idle_options }
+                    </select>
+                    <label class="text-white text-sm flex items-center mt-3 mb-1">
+                        <input type="checkbox" class="mr-2" checked=self.prefs.tts_enabled onclick=tts_cb />
+                        { "Read chat aloud" }
+                    </label>
+                    <select onchange=rate_cb disabled=!self.prefs.tts_enabled class="bg-gray-900 text-white rounded-lg focus:outline-none w-full px-2">
+                        { for rate_options }
+                    </select>
+                    <label class="text-white text-sm flex items-center mt-3">
+                        <input type="checkbox" class="mr-2" checked=self.prefs.auto_translate onclick=translate_cb />
+                        { "Translate chat automatically" }
+                    </label>
+                    <label class="text-white text-sm flex items-center mt-3">
+                        <input type="checkbox" class="mr-2" checked=self.prefs.effects_enabled onclick=effects_cb />
+                        { "Show overlay effects" }
+                    </label>
+                    <label class="text-white text-sm flex items-center mt-1">
+                        <input type="checkbox" class="mr-2" checked=self.prefs.performance_mode onclick=performance_cb />
+                        { "Performance mode" }
+                    </label>
+                    <label class="text-white text-sm flex items-center mt-1">
+                        <input type="checkbox" class="mr-2" checked=self.prefs.data_saver() onclick=data_saver_cb />
+                        { "Data saver (audio only)" }
+                    </label>
+                    <label class="text-white text-sm flex items-center mt-1">
+                        <input type="checkbox" class="mr-2" checked=self.prefs.show_stats onclick=stats_cb />
+                        { "Stats for nerds" }
+                    </label>
+                    <label class="text-white text-sm flex items-center mt-1">
+                        <input type="checkbox" class="mr-2" checked=self.prefs.ambient_mode onclick=ambient_cb />
+                        { "Ambient mode" }
+                    </label>
+                    { self.view_audio() }
+                    <label class="text-white text-sm flex items-center mt-3 mb-1">
+                        <input type="checkbox" class="mr-2" checked=self.prefs.chat_overlay onclick=chat_overlay_cb />
+                        { "Show chat over fullscreen video" }
+                    </label>
+                    <select onchange=overlay_corner_cb disabled=!self.prefs.chat_overlay class="bg-gray-900 text-white rounded-lg focus:outline-none w-full px-2 mb-1">
+                        { for corner_options }
+                    </select>
+                    <input
+                        type="range"
+                        class="slider w-full"
+                        min="0.1"
+                        max="1"
+                        step="0.05"
+                        value=self.prefs.chat_overlay_opacity.to_string()
+                        disabled=!self.prefs.chat_overlay
+                        oninput=overlay_opacity_cb
+                    />
+                    { self.view_danmaku() }
+                    { self.view_clock() }
+                    <label class="text-white text-sm block mt-3 mb-1">{ "Resync when out of sync by" }</label>
+                    <select onchange=drift_cb class="bg-gray-900 text-white rounded-lg focus:outline-none w-full px-2">
+                        { for drift_options }
+                    </select>
+                </div>
+            }
+        } else {
+            html!{}
+        };
+
+        html! {
+            <div class="relative flex items-center mx-2">
+                <button onclick=toggle_cb class="w-6 h-6 text-white focus:outline-none">
+                    <svg xmlns="http://www.w3.org/2000/svg" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                        <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M10.325 4.317c.426-1.756 2.924-1.756 3.35 0a1.724 1.724 0 002.573 1.066c1.543-.94 3.31.826 2.37 2.37a1.724 1.724 0 001.065 2.572c1.756.426 1.756 2.924 0 3.35a1.724 1.724 0 00-1.066 2.573c.94 1.543-.826 3.31-2.37 2.37a1.724 1.724 0 00-2.572 1.065c-.426 1.756-2.924 1.756-3.35 0a1.724 1.724 0 00-2.573-1.066c-1.543.94-3.31-.826-2.37-2.37a1.724 1.724 0 00-1.065-2.572c-1.756-.426-1.756-2.924 0-3.35a1.724 1.724 0 001.066-2.573c-.94-1.543.826-3.31 2.37-2.37.996.608 2.296.07 2.572-1.065z" />
+                        <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M15 12a3 3 0 11-6 0 3 3 0 016 0z" />
+                    </svg>
+                </button>
+                { panel }
+            </div>
+        }
+    }
+}
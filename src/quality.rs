@@ -0,0 +1,277 @@
+use yew::prelude::*;
+use yew::services::IntervalService;
+use yew::services::interval::IntervalTask;
+
+use std::time::Duration;
+
+use crate::nerds::PlaybackStats;
+use crate::prefs::Preferences;
+use crate::video::VideoElementHandle;
+
+
+/// How often the connection is checked against the playing rendition.
+const BANDWIDTH_CHECK_MS: u64 = 2000;
+
+/// How much more bandwidth than the rendition's bitrate is needed for
+/// the connection to be keeping up.
+const BANDWIDTH_HEADROOM: f64 = 1.2;
+
+/// Seconds buffered ahead below which a slow connection starts to stall.
+const LOW_BUFFER_SECS: f64 = 5.0;
+
+/// How many checks in a row have to fall behind before acting on it.
+const STRUGGLING_CHECKS: u32 = 3;
+
+
+/// Formats a rendition height for the menu, 0 being automatic.
+fn height_name(height: u32) -> String {
+    if height == 0 {
+        "Auto".to_string()
+    } else {
+        format!("{}p", height)
+    }
+}
+
+
+#[derive(Properties, Clone)]
+pub struct QualityMenuProperties {
+    /// The player whose renditions are picked from.
+    pub video: VideoElementHandle,
+}
+
+
+/// The events that can be invoked by callbacks for the QualityMenu.
+pub enum QualityMenuEvents {
+    /// The menu has been opened or closed.
+    Toggle,
+
+    /// A rendition height has been picked, 0 is automatic.
+    Select(u32),
+
+    /// Locking the picked quality has been toggled.
+    ToggleLock,
+
+    /// Check the measured bandwidth against the playing rendition.
+    CheckBandwidth,
+}
+
+
+/// The stream quality dropdown in the stats bar, the pick is saved to
+/// the preferences and applied to every stream.
+///
+/// Automatic quality is left to the player. A picked quality is watched
+/// against the measured bandwidth, if the connection falls behind and the
+/// buffer runs low it steps down a rendition for the session, or only
+/// warns if the user locked the quality.
+pub struct QualityMenu {
+    link: ComponentLink<Self>,
+    video: VideoElementHandle,
+    _check: IntervalTask,
+    open: bool,
+
+    /// The heights available, read from the player when opened.
+    renditions: Vec<u32>,
+    selected: u32,
+    locked: bool,
+
+    /// The lower height stepped down to for this session.
+    stepped_down: Option<u32>,
+
+    /// How many checks in a row the connection has fallen behind.
+    struggling: u32,
+}
+
+impl QualityMenu {
+    /// The height being played, 0 if automatic.
+    fn effective_height(&self) -> u32 {
+        self.stepped_down.unwrap_or(self.selected)
+    }
+
+    /// If the connection can't keep up with the playing rendition.
+    fn is_struggling(&self) -> bool {
+        self.struggling >= STRUGGLING_CHECKS
+    }
+
+    /// Steps down to the next lower rendition, returns false if already
+    /// on the lowest.
+    fn step_down(&mut self) -> bool {
+        self.renditions = serde_json::from_str(&self.video.renditions()).unwrap_or_default();
+
+        let current = self.effective_height();
+        let lower = self.renditions.iter().copied().find(|height| *height < current);
+
+        match lower {
+            Some(height) => {
+                self.video.set_rendition(height);
+                self.stepped_down = Some(height);
+                self.struggling = 0;
+                true
+            },
+            None => false,
+        }
+    }
+}
+
+impl Component for QualityMenu {
+    type Message = QualityMenuEvents;
+    type Properties = QualityMenuProperties;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let prefs = Preferences::load();
+        props.video.set_rendition(prefs.quality_height);
+
+        let check = IntervalService::spawn(
+            Duration::from_millis(BANDWIDTH_CHECK_MS),
+            link.callback(|_| QualityMenuEvents::CheckBandwidth),
+        );
+
+        Self {
+            link,
+            video: props.video,
+            _check: check,
+            open: false,
+            renditions: vec![],
+            selected: prefs.quality_height,
+            locked: prefs.quality_locked,
+            stepped_down: None,
+            struggling: 0,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            QualityMenuEvents::Toggle => {
+                self.open = !self.open;
+                if self.open {
+                    self.renditions = serde_json::from_str(&self.video.renditions())
+                        .unwrap_or_default();
+                }
+            },
+            QualityMenuEvents::Select(height) => {
+                self.video.set_rendition(height);
+
+                let mut prefs = Preferences::load();
+                prefs.quality_height = height;
+                prefs.save();
+
+                self.selected = height;
+                self.stepped_down = None;
+                self.struggling = 0;
+                self.open = false;
+            },
+            QualityMenuEvents::ToggleLock => {
+                self.locked = !self.locked;
+
+                let mut prefs = Preferences::load();
+                prefs.quality_locked = self.locked;
+                prefs.save();
+
+                // Locking goes back to the picked quality.
+                if self.locked && self.stepped_down.take().is_some() {
+                    self.video.set_rendition(self.selected);
+                }
+            },
+            QualityMenuEvents::CheckBandwidth => {
+                if self.effective_height() == 0 {
+                    return false;
+                }
+
+                let stats = match PlaybackStats::read(&self.video) {
+                    Some(stats) if stats.bandwidth > 0.0 && stats.bitrate > 0.0 => stats,
+                    _ => return false,
+                };
+
+                let was_struggling = self.is_struggling();
+                let behind = stats.bandwidth < stats.bitrate * BANDWIDTH_HEADROOM;
+                if behind && stats.buffer_secs < LOW_BUFFER_SECS {
+                    self.struggling += 1;
+                } else {
+                    self.struggling = 0;
+                }
+
+                if self.is_struggling() && !self.locked {
+                    return self.step_down();
+                }
+
+                return was_struggling != self.is_struggling();
+            },
+        }
+
+        true
+    }
+
+    fn change(&mut self, _props: Self::Properties) -> ShouldRender {
+        false
+    }
+
+    fn view(&self) -> Html {
+        let toggle_cb = self.link.callback(|_| QualityMenuEvents::Toggle);
+
+        let label = match self.stepped_down {
+            Some(height) => format!("{} ↓", height_name(height)),
+            None => height_name(self.selected),
+        };
+
+        let (border, title) = if self.is_struggling() {
+            ("border-yellow-400", "Your connection can't keep up with this quality")
+        } else {
+            ("", "Quality")
+        };
+
+        let menu = if self.open {
+            let options = std::iter::once(0).chain(self.renditions.iter().copied()).map(|height| {
+                let select_cb = self.link.callback(move |_| QualityMenuEvents::Select(height));
+                let weight = if height == self.selected { "font-bold" } else { "" };
+
+                html! {
+                    <button onclick=select_cb class=("block w-full text-left text-white text-sm px-2 py-1 hover:bg-gray-700 focus:outline-none", weight)>
+                        { height_name(height) }
+                    </button>
+                }
+            });
+
+            let empty = if self.renditions.is_empty() {
+                html! { <p class="text-gray-400 text-xs px-2">{ "No other qualities available" }</p> }
+            } else {
+                html!{}
+            };
+
+            let notice = if let Some(height) = self.stepped_down {
+                html! {
+                    <p class="text-yellow-400 text-xs px-2">
+                        { format!("Lowered to {} for your connection", height_name(height)) }
+                    </p>
+                }
+            } else if self.is_struggling() {
+                html! { <p class="text-yellow-400 text-xs px-2">{ title }</p> }
+            } else {
+                html!{}
+            };
+
+            let lock_cb = self.link.callback(|_| QualityMenuEvents::ToggleLock);
+
+            html! {
+                <div class="absolute right-0 mt-2 w-48 bg-gray-800 border-2 border-gray-700 rounded-lg py-1 z-10">
+                    { for options }
+                    { empty }
+                    { notice }
+                    <label class="flex items-center text-gray-400 text-xs px-2 pt-1">
+                        <input type="checkbox" class="mr-2" checked=self.locked onclick=lock_cb />
+                        { "Lock quality" }
+                    </label>
+                </div>
+            }
+        } else {
+            html!{}
+        };
+
+        html! {
+            <div class="relative flex items-center mx-2">
+                <button onclick=toggle_cb class=("text-white text-sm font-semibold border-2 rounded-lg px-2 focus:outline-none", border) title=title>
+                    { label }
+                </button>
+                { menu }
+            </div>
+        }
+    }
+}
@@ -0,0 +1,299 @@
+use yew::prelude::*;
+
+use reqwest::Client;
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::opcodes;
+use crate::settings;
+use crate::timefmt::format_duration;
+use crate::utils::send_future;
+use crate::webhook;
+use crate::websocket::{WsHandler, WebsocketMessage};
+
+
+/// How many of the most repeated messages are included in the recap.
+const TOP_MESSAGES: usize = 3;
+
+/// The most characters quoted from each top message, Discord limits
+/// embed fields to 1024 characters.
+const MAX_QUOTE_CHARS: usize = 100;
+
+/// The colour of the recap embed, Discord's blurple.
+const EMBED_COLOUR: u32 = 0x7289da;
+
+
+#[derive(Properties, Clone)]
+pub struct RecapButtonProperties {
+    /// The room websocket handle.
+    pub ws: WsHandler,
+
+    /// The room id.
+    pub room_id: String,
+
+    /// The title of what is being watched.
+    pub title: String,
+
+    /// The members currently in the room.
+    pub members: usize,
+
+    /// If the active user is the host, only the host can post a recap.
+    pub is_leader: bool,
+}
+
+
+/// The parts of a chat message the recap cares about.
+#[derive(Deserialize)]
+struct RecapMessage {
+    username: String,
+    content: String,
+}
+
+
+#[derive(Deserialize)]
+struct Webhook {
+    url: String,
+}
+
+
+/// Fetches the room's webhook and posts the recap embed to it.
+async fn post_recap(room_id: String, embed: Value) -> RecapButtonEvents {
+    let url = settings::get_webhook_api(&room_id);
+
+    let resp = Client::new()
+        .get(&url)
+        .send()
+        .await;
+
+    let webhook = match resp {
+        Ok(resp) => resp.json::<Webhook>().await.ok(),
+        Err(_) => None,
+    };
+
+    match webhook {
+        Some(webhook) if webhook::post_embed(&webhook.url, embed).await => RecapButtonEvents::Posted,
+        _ => RecapButtonEvents::Failed,
+    }
+}
+
+
+/// The events that can be invoked by callbacks for the RecapButton.
+pub enum RecapButtonEvents {
+    /// A chat message has been sent in the room.
+    Message(WebsocketMessage),
+
+    /// The preview has been opened or closed.
+    TogglePreview,
+
+    /// The host has confirmed posting the recap.
+    Post,
+
+    /// The recap has been posted to Discord.
+    Posted,
+
+    /// The webhook couldn't be fetched or rejected the recap.
+    Failed,
+}
+
+
+/// Collects a summary of the session and lets the host post it to the
+/// room's Discord channel as a webhook embed after previewing it.
+pub struct RecapButton {
+    link: ComponentLink<Self>,
+    _ws: WsHandler,
+    room_id: String,
+    title: String,
+    is_leader: bool,
+
+    /// When the session started in milliseconds.
+    started_at: f64,
+
+    /// The most members in the room at once.
+    peak_members: usize,
+
+    /// Everyone who has chatted this session.
+    chatters: FxHashSet<String>,
+
+    /// How many times each message has been sent.
+    message_counts: FxHashMap<String, u32>,
+
+    preview_open: bool,
+
+    /// The outcome of the last post, shown in the preview.
+    status: Option<&'static str>,
+}
+
+impl RecapButton {
+    /// The most repeated messages, most repeated first.
+    fn top_messages(&self) -> Vec<(&str, u32)> {
+        let mut messages: Vec<(&str, u32)> = self.message_counts
+            .iter()
+            .map(|(content, count)| (content.as_str(), *count))
+            .collect();
+
+        messages.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        messages.truncate(TOP_MESSAGES);
+        messages
+    }
+
+    /// The fields of the recap as name and value pairs.
+    fn fields(&self) -> Vec<(&'static str, String)> {
+        let top = self.top_messages()
+            .iter()
+            .map(|(content, count)| {
+                let content: String = content.chars().take(MAX_QUOTE_CHARS).collect();
+                format!("\"{}\" x{}", content, count)
+            })
+            .collect::<Vec<String>>();
+
+        let top = if top.is_empty() {
+            "Nobody said anything".to_string()
+        } else {
+            top.join("\n")
+        };
+
+        vec![
+            ("Watched", self.title.clone()),
+            ("Duration", format_duration((js_sys::Date::now() - self.started_at) / 1000.0)),
+            ("Attendees", format!("{} at peak, {} chatted", self.peak_members, self.chatters.len())),
+            ("Top messages", top),
+        ]
+    }
+
+    /// The recap as a Discord webhook payload.
+    fn embed(&self) -> Value {
+        let fields: Vec<Value> = self.fields()
+            .into_iter()
+            .map(|(name, value)| json!({ "name": name, "value": value }))
+            .collect();
+
+        json!({
+            "embeds": [{
+                "title": "Watch party recap",
+                "color": EMBED_COLOUR,
+                "fields": fields,
+            }]
+        })
+    }
+
+    fn view_preview(&self) -> Html {
+        let cancel_cb = self.link.callback(|_| RecapButtonEvents::TogglePreview);
+        let post_cb = self.link.callback(|_| RecapButtonEvents::Post);
+
+        let fields = self.fields().into_iter().map(|(name, value)| {
+            html! {
+                <div class="mb-2">
+                    <h2 class="text-white text-sm font-bold">{ name }</h2>
+                    <p class="text-gray-300 text-sm whitespace-pre-line">{ value }</p>
+                </div>
+            }
+        });
+
+        let status = match self.status {
+            Some(status) => html! { <p class="text-yellow-400 text-xs mb-2">{ status }</p> },
+            None => html!{},
+        };
+
+        html! {
+            <div class="fixed inset-0 flex justify-center items-center bg-black bg-opacity-50 z-40">
+                <div class="bg-discord-dark rounded-lg p-6 w-96">
+                    <h1 class="text-white font-bold text-lg mb-4">{ "Post a recap to Discord" }</h1>
+                    <div class="border-l-4 border-accent bg-gray-800 rounded p-3 mb-4">
+                        <h1 class="text-white font-bold mb-2">{ "Watch party recap" }</h1>
+                        { for fields }
+                    </div>
+                    { status }
+                    <div class="flex justify-end">
+                        <button onclick=cancel_cb class="text-white border-2 rounded-lg px-4 py-1 mx-2 focus:outline-none">
+                            { "Close" }
+                        </button>
+                        <button onclick=post_cb class="bg-accent text-white font-semibold rounded-lg px-4 py-1 focus:outline-none">
+                            { "Post" }
+                        </button>
+                    </div>
+                </div>
+            </div>
+        }
+    }
+}
+
+impl Component for RecapButton {
+    type Message = RecapButtonEvents;
+    type Properties = RecapButtonProperties;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let ws = props.ws;
+        let cb = link.callback(RecapButtonEvents::Message);
+        ws.subscribe_to_message(settings::RECAP_ID, opcodes::OP_MESSAGE, cb);
+
+        Self {
+            link,
+            _ws: ws,
+            room_id: props.room_id,
+            title: props.title,
+            is_leader: props.is_leader,
+            started_at: js_sys::Date::now(),
+            peak_members: props.members,
+            chatters: FxHashSet::default(),
+            message_counts: FxHashMap::default(),
+            preview_open: false,
+            status: None,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            RecapButtonEvents::Message(msg) => {
+                if let Some(msg) = msg.unwrap_and_into::<RecapMessage>() {
+                    self.chatters.insert(msg.username);
+                    *self.message_counts.entry(msg.content).or_default() += 1;
+                }
+                return self.preview_open;
+            },
+            RecapButtonEvents::TogglePreview => {
+                self.preview_open = !self.preview_open;
+                self.status = None;
+            },
+            RecapButtonEvents::Post => {
+                self.status = Some("Posting...");
+                send_future(self.link.clone(), post_recap(self.room_id.clone(), self.embed()));
+            },
+            RecapButtonEvents::Posted => {
+                self.status = Some("Posted to Discord.");
+            },
+            RecapButtonEvents::Failed => {
+                self.status = Some("Failed to post the recap, Discord rejected it or the webhook is missing.");
+            },
+        }
+
+        true
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        self.peak_members = self.peak_members.max(props.members);
+        self.title = props.title;
+
+        let changed = self.is_leader != props.is_leader;
+        self.is_leader = props.is_leader;
+        changed
+    }
+
+    fn view(&self) -> Html {
+        if !self.is_leader {
+            return html!{};
+        }
+
+        let toggle_cb = self.link.callback(|_| RecapButtonEvents::TogglePreview);
+        let preview = if self.preview_open { self.view_preview() } else { html!{} };
+
+        html! {
+            <div class="flex items-center mx-2">
+                <button onclick=toggle_cb class="text-white text-sm border-2 rounded-lg px-2 focus:outline-none">
+                    { "Post recap" }
+                </button>
+                { preview }
+            </div>
+        }
+    }
+}
@@ -0,0 +1,199 @@
+use yew::prelude::*;
+use yew::services::IntervalService;
+use yew::services::interval::IntervalTask;
+
+use serde::{Serialize, Deserialize};
+use std::time::Duration;
+
+use crate::controls::PlayerState;
+use crate::timefmt::format_clock;
+use crate::utils::{load_local, store_local, remove_local};
+use crate::video::VideoElementHandle;
+
+
+/// How often the position is saved.
+const SAVE_INTERVAL_MS: u64 = 5000;
+
+/// Positions closer than this to the start or the end aren't worth
+/// offering to resume from.
+const MIN_RESUME_SECS: f64 = 30.0;
+
+
+/// The local storage key the room's saved position is stored under.
+fn resume_key(room_id: &str) -> String {
+    format!("spooderfy.resume.{}", room_id)
+}
+
+
+/// Where playback was last at for a stream.
+#[derive(Serialize, Deserialize)]
+struct SavedPosition {
+    stream_url: String,
+    position: f64,
+}
+
+
+/// Saves the position to be offered the next time the room's stream is
+/// loaded.
+pub fn store_position(room_id: &str, stream_url: &str, position: f64) {
+    let saved = SavedPosition {
+        stream_url: stream_url.to_string(),
+        position,
+    };
+    store_local(&resume_key(room_id), &saved);
+}
+
+
+#[derive(Properties, Clone)]
+pub struct ResumePromptProperties {
+    /// The room id.
+    pub room_id: String,
+
+    /// The url of the stream being played.
+    pub stream_url: String,
+
+    /// If the active user controls playback for the room, only they are
+    /// offered to resume as the room follows their player.
+    pub is_leader: bool,
+
+    /// The player whose position is saved and resumed.
+    pub video: VideoElementHandle,
+}
+
+
+/// The events that can be invoked by callbacks for the ResumePrompt.
+pub enum ResumePromptEvents {
+    /// Save the current position.
+    Tick,
+
+    /// The user has chosen to resume from the saved position.
+    Resume,
+
+    /// The prompt has been dismissed.
+    Dismiss,
+}
+
+
+/// Saves the playback position of the room's stream every few seconds
+/// and offers to resume from it when the same stream is loaded again,
+/// e.g. after the page has been reloaded.
+pub struct ResumePrompt {
+    link: ComponentLink<Self>,
+    _save: IntervalTask,
+    video: VideoElementHandle,
+    room_id: String,
+    stream_url: String,
+    is_leader: bool,
+
+    /// The position to offer resuming from, None once handled.
+    offer: Option<f64>,
+}
+
+impl ResumePrompt {
+    /// The saved position of the stream, if any.
+    fn saved_offer(room_id: &str, stream_url: &str) -> Option<f64> {
+        let saved: SavedPosition = load_local(&resume_key(room_id))?;
+        if saved.stream_url == stream_url && saved.position >= MIN_RESUME_SECS {
+            Some(saved.position)
+        } else {
+            None
+        }
+    }
+
+    fn save_position(&self) {
+        if self.stream_url.is_empty() || (self.is_leader && self.offer.is_some()) {
+            return;
+        }
+
+        let state = match PlayerState::read(&self.video) {
+            Some(state) if !state.paused => state,
+            _ => return,
+        };
+
+        let key = resume_key(&self.room_id);
+        if state.duration - state.position < MIN_RESUME_SECS {
+            remove_local(&key);
+            return;
+        }
+
+        store_position(&self.room_id, &self.stream_url, state.position);
+    }
+}
+
+impl Component for ResumePrompt {
+    type Message = ResumePromptEvents;
+    type Properties = ResumePromptProperties;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let save = IntervalService::spawn(
+            Duration::from_millis(SAVE_INTERVAL_MS),
+            link.callback(|_| ResumePromptEvents::Tick),
+        );
+
+        let offer = Self::saved_offer(&props.room_id, &props.stream_url);
+
+        Self {
+            link,
+            _save: save,
+            video: props.video,
+            room_id: props.room_id,
+            stream_url: props.stream_url,
+            is_leader: props.is_leader,
+            offer,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            ResumePromptEvents::Tick => {
+                self.save_position();
+                return false;
+            },
+            ResumePromptEvents::Resume => {
+                if let Some(position) = self.offer.take() {
+                    // Seeking locally is picked up by the playback sync
+                    // and emitted to the rest of the room.
+                    self.video.seek(position);
+                }
+            },
+            ResumePromptEvents::Dismiss => self.offer = None,
+        }
+
+        true
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        let changed = self.stream_url != props.stream_url || self.is_leader != props.is_leader;
+        if self.stream_url != props.stream_url {
+            self.offer = Self::saved_offer(&props.room_id, &props.stream_url);
+        }
+
+        self.room_id = props.room_id;
+        self.stream_url = props.stream_url;
+        self.is_leader = props.is_leader;
+        changed
+    }
+
+    fn view(&self) -> Html {
+        let position = match self.offer {
+            Some(position) if self.is_leader => position,
+            _ => return html!{},
+        };
+
+        let resume_cb = self.link.callback(|_| ResumePromptEvents::Resume);
+        let dismiss_cb = self.link.callback(|_| ResumePromptEvents::Dismiss);
+
+        html! {
+            <div class="absolute top-0 inset-x-0 flex justify-center mt-4 z-20">
+                <div class="flex items-center bg-gray-800 border-2 border-gray-700 rounded-lg px-4 py-2">
+                    <button onclick=resume_cb class="text-white text-sm font-semibold focus:outline-none mr-4">
+                        { format!("Resume from {}", format_clock(position)) }
+                    </button>
+                    <button onclick=dismiss_cb class="text-gray-400 text-sm focus:outline-none">
+                        { "Dismiss" }
+                    </button>
+                </div>
+            </div>
+        }
+    }
+}
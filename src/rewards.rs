@@ -0,0 +1,194 @@
+use yew::prelude::*;
+use yew::services::{ConsoleService, TimeoutService};
+use yew::services::timeout::TimeoutTask;
+
+use serde::Deserialize;
+use std::time::Duration;
+
+use crate::opcodes;
+use crate::settings;
+use crate::websocket::{WsHandler, WebsocketMessage};
+
+
+/// How long the boost celebration is shown for.
+const CELEBRATION_SECS: u64 = 6;
+
+
+#[derive(Properties, Clone)]
+pub struct BoostPanelProperties {
+    /// The room websocket handle.
+    pub ws: WsHandler,
+
+    /// The room's current multiplier e.g. 1x.
+    pub multiplier: String,
+
+    /// The amount of boosts towards the next tier.
+    pub boosts: u32,
+
+    /// The boosts needed for the next tier, None if at the max tier.
+    pub next_tier_boosts: Option<u32>,
+}
+
+
+/// A boost as announced by the gateway.
+#[derive(Deserialize)]
+struct Boost {
+    /// The display name of who boosted the room.
+    username: String,
+
+    /// The multiplier after the boost was applied.
+    multiplier: String,
+}
+
+
+/// The events that can be invoked by callbacks for the BoostPanel.
+pub enum BoostPanelEvents {
+    /// Someone has boosted the room.
+    Boost(WebsocketMessage),
+
+    /// The explanation panel has been opened or closed.
+    Toggle,
+
+    /// The celebration has finished.
+    CelebrationDone,
+}
+
+
+/// The multiplier stat and its rewards panel.
+///
+/// Clicking the multiplier explains what it is and shows the progress
+/// towards the next tier, when someone boosts the room a celebration is
+/// shown to everyone.
+pub struct BoostPanel {
+    link: ComponentLink<Self>,
+    props: BoostPanelProperties,
+
+    open: bool,
+
+    /// The boost being celebrated and the task hiding it.
+    celebrating: Option<(Boost, TimeoutTask)>,
+}
+
+impl Component for BoostPanel {
+    type Message = BoostPanelEvents;
+    type Properties = BoostPanelProperties;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let boost_cb = link.callback(BoostPanelEvents::Boost);
+        props.ws.subscribe_to_message(settings::BOOST_ID, opcodes::OP_BOOST, boost_cb);
+
+        Self {
+            link,
+            props,
+            open: false,
+            celebrating: None,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            BoostPanelEvents::Boost(msg) => {
+                let boost: Boost = match msg.unwrap_and_into() {
+                    Some(boost) => boost,
+                    None => {
+                        ConsoleService::warn("Received boost without a valid payload");
+                        return false;
+                    }
+                };
+
+                let task = TimeoutService::spawn(
+                    Duration::from_secs(CELEBRATION_SECS),
+                    self.link.callback(|_| BoostPanelEvents::CelebrationDone),
+                );
+                self.celebrating = Some((boost, task));
+            },
+            BoostPanelEvents::Toggle => {
+                self.open = !self.open;
+            },
+            BoostPanelEvents::CelebrationDone => {
+                self.celebrating = None;
+            },
+        }
+
+        true
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        let changed = self.props.multiplier != props.multiplier
+            || self.props.boosts != props.boosts
+            || self.props.next_tier_boosts != props.next_tier_boosts;
+
+        self.props = props;
+        changed
+    }
+
+    fn view(&self) -> Html {
+        let toggle_cb = self.link.callback(|_| BoostPanelEvents::Toggle);
+
+        let panel = if self.open {
+            let progress = match self.props.next_tier_boosts {
+                Some(next) if next > 0 => {
+                    let percent = (self.props.boosts.min(next) * 100) / next;
+                    let bar_style = format!("width: {}%;", percent);
+                    let remaining = next.saturating_sub(self.props.boosts);
+
+                    html! {
+                        <>
+                            <div class="w-full bg-gray-900 rounded-full h-2 mt-3">
+                                <div class="bg-red-600 rounded-full h-2" data-style=bar_style></div>
+                            </div>
+                            <p class="text-gray-300 text-sm mt-1">
+                                { format!("{} more boosts until the next tier.", remaining) }
+                            </p>
+                        </>
+                    }
+                },
+                _ => html! {
+                    <p class="text-gray-300 text-sm mt-3">{ "This room is at the highest tier!" }</p>
+                },
+            };
+
+            html! {
+                <div class="absolute right-0 top-0 mt-8 w-64 bg-gray-800 border-2 border-gray-700 rounded-lg p-4 z-10">
+                    <h1 class="text-white font-bold">{ format!("Room multiplier: {}", &self.props.multiplier) }</h1>
+                    <p class="text-gray-300 text-sm mt-2">
+                        { "Boosting the room increases the multiplier, everyone watching \
+                           earns rewards faster while it is active." }
+                    </p>
+                    { progress }
+                </div>
+            }
+        } else {
+            html!{}
+        };
+
+        let celebration = if let Some((boost, _)) = self.celebrating.as_ref() {
+            html! {
+                <div class="fixed top-0 left-0 flex justify-center w-full p-8 z-20 pointer-events-none">
+                    <div class="animate-bounce bg-red-600 border-2 border-red-400 rounded-lg py-4 px-8">
+                        <h1 class="text-white font-bold text-2xl">
+                            { format!("🔥 {} boosted the room to {}! 🔥", &boost.username, &boost.multiplier) }
+                        </h1>
+                    </div>
+                </div>
+            }
+        } else {
+            html!{}
+        };
+
+        html! {
+            <div class="relative flex justify-center items-center mx-2">
+                <button onclick=toggle_cb class="flex justify-center items-center focus:outline-none">
+                    <div class="w-5 h-5 object-contain text-red-600 mx-2">
+                        <svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 20 20" fill="currentColor">
+                          <path fill-rule="evenodd" d="M12.395 2.553a1 1 0 00-1.45-.385c-.345.23-.614.558-.822.88-.214.33-.403.713-.57 1.116-.334.804-.614 1.768-.84 2.734a31.365 31.365 0 00-.613 3.58 2.64 2.64 0 01-.945-1.067c-.328-.68-.398-1.534-.398-2.654A1 1 0 005.05 6.05 6.981 6.981 0 003 11a7 7 0 1011.95-4.95c-.592-.591-.98-.985-1.348-1.467-.363-.476-.724-1.063-1.207-2.03zM12.12 15.12A3 3 0 017 13s.879.5 2.5.5c0-1 .5-4 1.25-4.5.5 1 .786 1.293 1.371 1.879A2.99 2.99 0 0113 13a2.99 2.99 0 01-.879 2.121z" clip-rule="evenodd" />
+                        </svg>
+                    </div>
+                    <h1 class="text-lg text-white font-semibold">{ &self.props.multiplier }</h1>
+                </button>
+                { panel }
+                { celebration }
+            </div>
+        }
+    }
+}
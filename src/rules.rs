@@ -0,0 +1,121 @@
+use yew::prelude::*;
+
+use reqwest::Client;
+use serde::Deserialize;
+use rustc_hash::FxHasher;
+use std::hash::{Hash, Hasher};
+
+use crate::settings;
+use crate::utils::{load_local, store_local};
+
+
+/// The local storage key the rules acknowledged in the room are stored
+/// under.
+fn acknowledged_key(room_id: &str) -> String {
+    format!("spooderfy.rules.{}", room_id)
+}
+
+
+/// A hash of the rules, editing them asks everyone to acknowledge them
+/// again.
+fn rules_hash(rules: &str) -> String {
+    let mut hasher = FxHasher::default();
+    rules.trim().hash(&mut hasher);
+    format!("{:x}", hasher.finish())
+}
+
+
+/// If the user has acknowledged these rules in the room before.
+pub fn is_acknowledged(room_id: &str, rules: &str) -> bool {
+    load_local::<String>(&acknowledged_key(room_id)) == Some(rules_hash(rules))
+}
+
+
+/// Records that the user has acknowledged the rules in the room.
+pub fn acknowledge(room_id: &str, rules: &str) {
+    store_local(&acknowledged_key(room_id), &rules_hash(rules));
+}
+
+
+#[derive(Deserialize)]
+struct RoomRules {
+    #[serde(default)]
+    rules: Option<String>,
+}
+
+
+/// Fetches the room's rules as set in the owner's branding editor, None if
+/// the room has none or they couldn't be fetched.
+pub async fn fetch_rules(room_id: String) -> Option<String> {
+    let url = settings::get_room_theme_url(&room_id);
+
+    let resp = Client::new()
+        .get(&url)
+        .send()
+        .await
+        .ok()?;
+
+    resp.json::<RoomRules>()
+        .await
+        .ok()?
+        .rules
+        .filter(|rules| !rules.trim().is_empty())
+}
+
+
+#[derive(Properties, Clone)]
+pub struct RulesScreenProperties {
+    /// The room's rules.
+    pub rules: String,
+
+    /// Called once the user has acknowledged the rules.
+    pub on_accept: Callback<()>,
+}
+
+
+/// The room's rules shown in place of the player until the user has
+/// acknowledged them.
+pub struct RulesScreen {
+    props: RulesScreenProperties,
+}
+
+impl Component for RulesScreen {
+    type Message = ();
+    type Properties = RulesScreenProperties;
+
+    fn create(props: Self::Properties, _link: ComponentLink<Self>) -> Self {
+        Self { props }
+    }
+
+    fn update(&mut self, _msg: Self::Message) -> ShouldRender {
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        let changed = self.props.rules != props.rules;
+        self.props = props;
+        changed
+    }
+
+    fn view(&self) -> Html {
+        let accept_cb = self.props.on_accept.reform(|_| ());
+
+        let lines = self.props.rules.lines().map(|line| html! {
+            <p class="text-gray-300 mb-2 break-words">{ line }</p>
+        });
+
+        html! {
+            <div class="flex justify-center items-center w-2/3 p-8">
+                <div class="bg-discord-dark rounded-lg shadow-lg max-w-xl p-8">
+                    <h1 class="text-white font-bold text-2xl mb-4">{ "Before you join" }</h1>
+                    <div class="mb-6">{ for lines }</div>
+                    <div class="flex justify-end">
+                        <button onclick=accept_cb class="bg-accent text-white font-semibold rounded-lg px-4 py-2 focus:outline-none">
+                            { "I understand" }
+                        </button>
+                    </div>
+                </div>
+            </div>
+        }
+    }
+}
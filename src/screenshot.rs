@@ -0,0 +1,183 @@
+use yew::prelude::*;
+use yew::services::TimeoutService;
+use yew::services::timeout::TimeoutTask;
+
+use std::time::Duration;
+
+use crate::controls::PlayerState;
+use crate::timefmt::format_clock;
+use crate::utils::send_future;
+use crate::video::VideoElementHandle;
+
+
+/// How long the result of a capture is shown for.
+const STATUS_SECS: u64 = 3;
+
+
+/// The file name a screenshot is saved as, the track title followed by
+/// the position it was taken at e.g. `Big Buck Bunny 1-02-03.png`.
+pub fn screenshot_filename(title: &str, position: f64) -> String {
+    let title: String = title
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == ' ' || c == '-' { c } else { '_' })
+        .collect();
+
+    let title = match title.trim() {
+        "" => "Screenshot",
+        title => title,
+    };
+
+    format!("{} {}.png", title, format_clock(position).replace(':', "-"))
+}
+
+
+/// Saves the current frame of the player, named after the title.
+///
+/// Returns false if the frame can't be read, e.g. the stream is cross
+/// origin without CORS headers.
+pub fn save_screenshot(video: &VideoElementHandle, title: &str) -> bool {
+    let position = PlayerState::read(video).map_or(0.0, |state| state.position);
+    video.download_frame(&screenshot_filename(title, position))
+}
+
+
+#[derive(Properties, Clone)]
+pub struct ScreenshotButtonProperties {
+    /// The title of the stream being played.
+    pub title: String,
+
+    /// The player captured.
+    pub video: VideoElementHandle,
+}
+
+
+/// The events that can be invoked by callbacks for the ScreenshotButton.
+pub enum ScreenshotButtonEvents {
+    /// The menu has been opened or closed.
+    Toggle,
+
+    /// Save the current frame as a file.
+    Save,
+
+    /// Copy the current frame to the clipboard.
+    Copy,
+
+    /// The frame has been copied, false if it failed.
+    Copied(bool),
+
+    /// Hide the capture result.
+    ClearStatus,
+}
+
+
+/// The screenshot button of the control bar, the current frame can be
+/// saved as a png or copied to the clipboard.
+pub struct ScreenshotButton {
+    link: ComponentLink<Self>,
+    video: VideoElementHandle,
+    title: String,
+    open: bool,
+
+    /// The result of the last capture.
+    status: Option<&'static str>,
+    clear_status: Option<TimeoutTask>,
+}
+
+impl ScreenshotButton {
+    fn show_status(&mut self, status: &'static str) {
+        self.status = Some(status);
+        self.clear_status = Some(TimeoutService::spawn(
+            Duration::from_secs(STATUS_SECS),
+            self.link.callback(|_| ScreenshotButtonEvents::ClearStatus),
+        ));
+    }
+}
+
+impl Component for ScreenshotButton {
+    type Message = ScreenshotButtonEvents;
+    type Properties = ScreenshotButtonProperties;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        Self {
+            link,
+            video: props.video,
+            title: props.title,
+            open: false,
+            status: None,
+            clear_status: None,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            ScreenshotButtonEvents::Toggle => self.open = !self.open,
+            ScreenshotButtonEvents::Save => {
+                self.open = false;
+                if !save_screenshot(&self.video, &self.title) {
+                    self.show_status("This stream can't be captured");
+                }
+            },
+            ScreenshotButtonEvents::Copy => {
+                self.open = false;
+                let video = self.video.clone();
+                send_future(self.link.clone(), async move {
+                    ScreenshotButtonEvents::Copied(video.copy_frame().await)
+                });
+            },
+            ScreenshotButtonEvents::Copied(true) => self.show_status("Copied to clipboard"),
+            ScreenshotButtonEvents::Copied(false) => self.show_status("Couldn't copy the frame"),
+            ScreenshotButtonEvents::ClearStatus => {
+                self.status = None;
+                self.clear_status = None;
+            },
+        }
+
+        true
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        self.title = props.title;
+        false
+    }
+
+    fn view(&self) -> Html {
+        let toggle_cb = self.link.callback(|_| ScreenshotButtonEvents::Toggle);
+
+        let menu = if self.open {
+            let save_cb = self.link.callback(|_| ScreenshotButtonEvents::Save);
+            let copy_cb = self.link.callback(|_| ScreenshotButtonEvents::Copy);
+
+            html! {
+                <div class="absolute bottom-0 right-0 mb-8 w-36 bg-gray-800 border-2 border-gray-700 rounded-lg py-1 z-10">
+                    <button onclick=save_cb class="block w-full text-left text-white text-sm px-2 py-1 hover:bg-gray-700 focus:outline-none">
+                        { "Save frame" }
+                    </button>
+                    <button onclick=copy_cb class="block w-full text-left text-white text-sm px-2 py-1 hover:bg-gray-700 focus:outline-none">
+                        { "Copy frame" }
+                    </button>
+                </div>
+            }
+        } else {
+            html!{}
+        };
+
+        let status = match self.status {
+            Some(status) => html! {
+                <div class="absolute bottom-0 right-0 mb-8 bg-gray-800 text-white text-xs rounded-lg px-2 py-1 whitespace-nowrap">
+                    { status }
+                </div>
+            },
+            None => html!{},
+        };
+
+        html! {
+            <div class="relative mr-4">
+                <button onclick=toggle_cb class="text-white text-sm focus:outline-none" title="Screenshot">
+                    { "📷" }
+                </button>
+                { menu }
+                { status }
+            </div>
+        }
+    }
+}
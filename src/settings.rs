@@ -9,6 +9,20 @@ pub const API_PATH: &str = "/api";
 pub const EVENT_DISPLAY_ID: usize = 0;
 pub const CHAT_ID: usize = 1;
 pub const PLAYER_ID: usize = 2;
+pub const AUTH_ID: usize = 3;
+pub const SLEEP_ID: usize = 4;
+pub const BOOST_ID: usize = 5;
+pub const SYNC_ID: usize = 6;
+pub const EFFECTS_ID: usize = 7;
+pub const ANNOUNCE_ID: usize = 8;
+pub const RECAP_ID: usize = 9;
+pub const CONTROLS_ID: usize = 10;
+pub const HANDOFF_ID: usize = 11;
+pub const CHAT_OVERLAY_ID: usize = 12;
+pub const DANMAKU_ID: usize = 13;
+pub const WATCH_STATS_ID: usize = 14;
+pub const ROOM_ID: usize = 15;
+pub const HOLD_ID: usize = 16;
 
 pub fn get_emit_url(room_id: &str) -> String {
     format!("{}://{}{}/{}/emit", SCHEMA, DOMAIN, API_PATH, room_id)
@@ -22,14 +36,50 @@ pub fn get_webhook_api(room_id: &str) -> String {
     format!("{}://{}{}/room/{}/webhook", SCHEMA, DOMAIN, API_PATH, room_id)
 }
 
+pub fn get_room_config_url(room_id: &str) -> String {
+    format!("{}://{}{}/room/{}/config", SCHEMA, DOMAIN, API_PATH, room_id)
+}
+
+pub fn get_room_theme_url(room_id: &str) -> String {
+    format!("{}://{}{}/room/{}/theme", SCHEMA, DOMAIN, API_PATH, room_id)
+}
+
+pub fn get_room_analytics_url(room_id: &str) -> String {
+    format!("{}://{}{}/room/{}/analytics", SCHEMA, DOMAIN, API_PATH, room_id)
+}
+
 pub fn get_stream_api_url(room_id: &str) -> String {
     format!("{}://{}{}/room/{}/stream", SCHEMA, DOMAIN, API_PATH, room_id)
 }
 
+pub fn get_handoff_url(room_id: &str) -> String {
+    format!("{}://{}{}/room/{}/handoff", SCHEMA, DOMAIN, API_PATH, room_id)
+}
+
+pub fn get_handoff_claim_url(code: &str) -> String {
+    format!("{}://{}{}/handoff/{}/claim", SCHEMA, DOMAIN, API_PATH, code)
+}
+
+pub fn get_support_url() -> String {
+    format!("{}://{}{}/support", SCHEMA, DOMAIN, API_PATH)
+}
+
 pub fn get_who_am_i_url() -> String {
     format!("{}://{}{}/@me", SCHEMA, DOMAIN, API_PATH)
 }
 
+pub fn get_home_url() -> String {
+    format!("{}://{}", SCHEMA, DOMAIN)
+}
+
 pub fn get_room_url() -> String {
     format!("{}://{}/room", SCHEMA, DOMAIN)
+}
+
+pub fn get_translate_url(room_id: &str) -> String {
+    format!("{}://{}{}/room/{}/translate", SCHEMA, DOMAIN, API_PATH, room_id)
+}
+
+pub fn get_room_settings_url(room_id: &str, section: &str) -> String {
+    format!("{}://{}/room/{}/settings#{}", SCHEMA, DOMAIN, room_id, section)
 }
\ No newline at end of file
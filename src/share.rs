@@ -0,0 +1,134 @@
+use yew::prelude::*;
+use yew::services::TimeoutService;
+use yew::services::timeout::TimeoutTask;
+
+use std::time::Duration;
+
+use crate::binder;
+use crate::controls::PlayerState;
+use crate::settings;
+use crate::timefmt::format_units;
+use crate::utils::send_future;
+use crate::video::VideoElementHandle;
+
+
+/// How long the copy confirmation is shown for.
+const TOAST_SECS: u64 = 3;
+
+
+/// The link to the room starting at the position, e.g.
+/// `https://spooderfy.com/room/1234?t=1h2m3s`. Live streams have no
+/// position to share so their links have no start time.
+pub fn room_link_at(room_id: &str, state: Option<&PlayerState>) -> String {
+    let base = format!("{}/{}", settings::get_room_url(), room_id);
+
+    match state {
+        Some(state) if !state.live && state.position >= 1.0 => {
+            format!("{}?t={}", base, format_units(state.position))
+        },
+        _ => base,
+    }
+}
+
+
+#[derive(Properties, Clone)]
+pub struct ShareButtonProperties {
+    /// The room id.
+    pub room_id: String,
+
+    /// The player whose position is linked to.
+    pub video: VideoElementHandle,
+}
+
+
+/// The events that can be invoked by callbacks for the ShareButton.
+pub enum ShareButtonEvents {
+    /// The button has been pressed.
+    Copy,
+
+    /// The link has been copied, false if it failed.
+    Copied(bool),
+
+    /// Hide the confirmation.
+    ClearToast,
+}
+
+
+/// Copies a link to the room at the current position to the clipboard,
+/// opening it alone or while watching privately starts the player there.
+pub struct ShareButton {
+    link: ComponentLink<Self>,
+    video: VideoElementHandle,
+    room_id: String,
+    toast: Option<&'static str>,
+    clear_toast: Option<TimeoutTask>,
+}
+
+impl Component for ShareButton {
+    type Message = ShareButtonEvents;
+    type Properties = ShareButtonProperties;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        Self {
+            link,
+            video: props.video,
+            room_id: props.room_id,
+            toast: None,
+            clear_toast: None,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            ShareButtonEvents::Copy => {
+                let url = room_link_at(&self.room_id, PlayerState::read(&self.video).as_ref());
+                send_future(self.link.clone(), async move {
+                    let copied = binder::copy_text(url).await;
+                    ShareButtonEvents::Copied(copied.as_bool().unwrap_or(false))
+                });
+                return false;
+            },
+            ShareButtonEvents::Copied(copied) => {
+                self.toast = Some(if copied { "Link copied" } else { "Couldn't copy the link" });
+                self.clear_toast = Some(TimeoutService::spawn(
+                    Duration::from_secs(TOAST_SECS),
+                    self.link.callback(|_| ShareButtonEvents::ClearToast),
+                ));
+            },
+            ShareButtonEvents::ClearToast => {
+                self.toast = None;
+                self.clear_toast = None;
+            },
+        }
+
+        true
+    }
+
+    fn change(&mut self, _props: Self::Properties) -> ShouldRender {
+        false
+    }
+
+    fn view(&self) -> Html {
+        let copy_cb = self.link.callback(|_| ShareButtonEvents::Copy);
+
+        let toast = match self.toast {
+            Some(toast) => html! {
+                <div class="absolute bottom-0 right-0 mb-8 bg-gray-800 text-white text-xs rounded-lg px-2 py-1 whitespace-nowrap">
+                    { toast }
+                </div>
+            },
+            None => html!{},
+        };
+
+        html! {
+            <div class="relative mr-4">
+                <button onclick=copy_cb class="w-5 h-5 text-white focus:outline-none" title="Copy a link to this moment">
+                    <svg xmlns="http://www.w3.org/2000/svg" fill="none" viewBox="0 0 24 24" stroke="currentColor">
+                        <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M13.828 10.172a4 4 0 00-5.656 0l-4 4a4 4 0 105.656 5.656l1.102-1.101m-.758-4.899a4 4 0 005.656 0l4-4a4 4 0 00-5.656-5.656l-1.1 1.1" />
+                    </svg>
+                </button>
+                { toast }
+            </div>
+        }
+    }
+}
@@ -0,0 +1,317 @@
+use yew::prelude::*;
+use yew::services::{ConsoleService, TimeoutService};
+use yew::services::timeout::TimeoutTask;
+
+use serde::Deserialize;
+use std::time::Duration;
+
+use crate::opcodes;
+use crate::prefs::Preferences;
+use crate::settings;
+use crate::video::VideoElementHandle;
+use crate::websocket::{WsHandler, WebsocketMessage};
+
+
+/// How long before the deadline the warning overlay is shown.
+const WARNING_SECS: u64 = 60;
+
+/// How long a snooze pushes the personal sleep timer back.
+const SNOOZE_SECS: u64 = 10 * 60;
+
+/// How long the volume fades out for before pausing.
+const FADE_MS: u32 = 5_000;
+
+/// The sleep timer lengths the user can pick from in minutes.
+const TIMER_OPTIONS: [u64; 5] = [15, 30, 45, 60, 90];
+
+
+#[derive(Properties, Clone)]
+pub struct SleepTimerProperties {
+    /// The room websocket handle.
+    pub ws: WsHandler,
+
+    /// The player paused once a timer runs out.
+    pub video: VideoElementHandle,
+}
+
+
+/// The room curfew as set by the host, the remaining time is sent rather
+/// than a timestamp so clients with skewed clocks still agree.
+#[derive(Deserialize)]
+struct Curfew {
+    /// Seconds until the curfew, `None` clears any set curfew.
+    remaining_secs: Option<u64>,
+
+    /// If playback should fade out rather than pausing immediately.
+    #[serde(default)]
+    fade: bool,
+}
+
+
+/// Which deadline an event belongs to.
+#[derive(Clone, Copy, PartialEq)]
+pub enum DeadlineKind {
+    /// The user's own sleep timer.
+    Personal,
+
+    /// The host's room wide curfew.
+    Curfew,
+}
+
+
+/// The events that can be invoked by callbacks for the SleepTimer.
+pub enum SleepTimerEvents {
+    /// The host has set or cleared the curfew.
+    Curfew(WebsocketMessage),
+
+    /// The user has picked a sleep timer length in minutes, 0 clears it.
+    Select(u64),
+
+    /// The deadline is a minute away.
+    Warn(DeadlineKind),
+
+    /// The deadline has been reached.
+    Expire(DeadlineKind),
+
+    /// The user snoozed the warning.
+    Snooze,
+
+    /// The user dismissed the warning.
+    Dismiss,
+}
+
+
+/// A pending pause with its warning, both tasks are cancelled on drop.
+struct Deadline {
+    fade: bool,
+
+    /// When the deadline is reached in local time ms.
+    at: f64,
+    _warn: Option<TimeoutTask>,
+    _expire: TimeoutTask,
+}
+
+impl Deadline {
+    /// Schedules the warning and expiry for the given kind in `secs` seconds.
+    fn schedule(
+        link: &ComponentLink<SleepTimer>,
+        kind: DeadlineKind,
+        secs: u64,
+        fade: bool,
+    ) -> Self {
+        let warn = if secs > WARNING_SECS {
+            let task = TimeoutService::spawn(
+                Duration::from_secs(secs - WARNING_SECS),
+                link.callback(move |_| SleepTimerEvents::Warn(kind)),
+            );
+            Some(task)
+        } else {
+            None
+        };
+
+        let expire = TimeoutService::spawn(
+            Duration::from_secs(secs),
+            link.callback(move |_| SleepTimerEvents::Expire(kind)),
+        );
+
+        Self {
+            fade,
+            at: js_sys::Date::now() + secs as f64 * 1_000.0,
+            _warn: warn,
+            _expire: expire,
+        }
+    }
+}
+
+
+/// A personal sleep timer and the host set room curfew.
+///
+/// Either one pauses local playback when it is reached, showing a warning
+/// overlay a minute beforehand. The personal timer can be snoozed, the
+/// curfew can only be dismissed.
+pub struct SleepTimer {
+    link: ComponentLink<Self>,
+    _ws: WsHandler,
+    video: VideoElementHandle,
+
+    personal: Option<Deadline>,
+    curfew: Option<Deadline>,
+
+    /// The deadline currently showing its warning overlay.
+    warning: Option<DeadlineKind>,
+}
+
+impl SleepTimer {
+    fn deadline_mut(&mut self, kind: DeadlineKind) -> &mut Option<Deadline> {
+        match kind {
+            DeadlineKind::Personal => &mut self.personal,
+            DeadlineKind::Curfew => &mut self.curfew,
+        }
+    }
+
+    fn on_curfew(&mut self, msg: WebsocketMessage) {
+        let curfew: Curfew = match msg.unwrap_and_into() {
+            Some(curfew) => curfew,
+            None => {
+                ConsoleService::warn("Received curfew update without a valid payload");
+                return;
+            }
+        };
+
+        self.curfew = curfew.remaining_secs.map(|secs| {
+            Deadline::schedule(&self.link, DeadlineKind::Curfew, secs, curfew.fade)
+        });
+
+        if self.warning == Some(DeadlineKind::Curfew) {
+            self.warning = None;
+        }
+    }
+}
+
+impl Component for SleepTimer {
+    type Message = SleepTimerEvents;
+    type Properties = SleepTimerProperties;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let curfew_cb = link.callback(SleepTimerEvents::Curfew);
+
+        let ws = props.ws;
+        ws.subscribe_to_message(settings::SLEEP_ID, opcodes::OP_CURFEW, curfew_cb);
+
+        Self {
+            link,
+            _ws: ws,
+            video: props.video,
+            personal: None,
+            curfew: None,
+            warning: None,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            SleepTimerEvents::Curfew(msg) => self.on_curfew(msg),
+            SleepTimerEvents::Select(0) => {
+                self.personal = None;
+                if self.warning == Some(DeadlineKind::Personal) {
+                    self.warning = None;
+                }
+            },
+            SleepTimerEvents::Select(mins) => {
+                let deadline = Deadline::schedule(
+                    &self.link,
+                    DeadlineKind::Personal,
+                    mins * 60,
+                    true,
+                );
+                self.personal = Some(deadline);
+            },
+            SleepTimerEvents::Warn(kind) => {
+                self.warning = Some(kind);
+            },
+            SleepTimerEvents::Expire(kind) => {
+                if let Some(deadline) = self.deadline_mut(kind).take() {
+                    let fade_ms = if deadline.fade { FADE_MS } else { 0 };
+                    self.video.fade_out(fade_ms);
+                }
+
+                if self.warning == Some(kind) {
+                    self.warning = None;
+                }
+            },
+            SleepTimerEvents::Snooze => {
+                if self.warning.take() == Some(DeadlineKind::Personal) {
+                    let deadline = Deadline::schedule(
+                        &self.link,
+                        DeadlineKind::Personal,
+                        SNOOZE_SECS,
+                        true,
+                    );
+                    self.personal = Some(deadline);
+                }
+            },
+            SleepTimerEvents::Dismiss => {
+                self.warning = None;
+            },
+        }
+
+        true
+    }
+
+    fn change(&mut self, _props: Self::Properties) -> ShouldRender {
+        false
+    }
+
+    fn view(&self) -> Html {
+        let select_cb = self.link.callback(|e: ChangeData| {
+            if let ChangeData::Select(select) = e {
+                SleepTimerEvents::Select(select.value().parse().unwrap_or(0))
+            } else {
+                SleepTimerEvents::Select(0)
+            }
+        });
+
+        let options = TIMER_OPTIONS.iter().map(|mins| {
+            html! {
+                <option value=mins.to_string()>{ format!("{} min", mins) }</option>
+            }
+        });
+
+        let selected_off = self.personal.is_none();
+
+        let prefs = Preferences::load();
+        let until = |deadline: Option<&Deadline>, label: &str| match deadline {
+            Some(deadline) => html! {
+                <span class="text-sm text-gray-400 mx-2">
+                    { format!("{} {}", label, prefs.format_clock_time(deadline.at)) }
+                </span>
+            },
+            None => html!{},
+        };
+
+        let overlay = match self.warning {
+            None => html!{},
+            Some(kind) => {
+                let (title, snooze) = if kind == DeadlineKind::Personal {
+                    let snooze_cb = self.link.callback(|_| SleepTimerEvents::Snooze);
+                    let snooze = html! {
+                        <button onclick=snooze_cb class="bg-blue-600 text-white font-semibold rounded-lg px-4 py-2 mx-2 focus:outline-none">
+                            { "Snooze 10 min" }
+                        </button>
+                    };
+
+                    ("Your sleep timer will pause playback in 1 minute.", snooze)
+                } else {
+                    ("The host's curfew will pause playback in 1 minute.", html!{})
+                };
+
+                let dismiss_cb = self.link.callback(|_| SleepTimerEvents::Dismiss);
+
+                html! {
+                    <div class="animate-slide fixed top-0 flex justify-center w-full p-4">
+                        <div class="bg-discord-dark border-2 border-gray-200 rounded-lg py-4 px-8 flex items-center">
+                            <h1 class="text-white font-bold mr-4">{ title }</h1>
+                            { snooze }
+                            <button onclick=dismiss_cb class="text-white border-2 rounded-lg px-4 py-2 mx-2 focus:outline-none">
+                                { "Dismiss" }
+                            </button>
+                        </div>
+                    </div>
+                }
+            },
+        };
+
+        html! {
+            <div class="flex justify-center items-center mx-2">
+                <label class="text-lg text-white font-semibold mx-2">{ "Sleep" }</label>
+                <select onchange=select_cb class="bg-gray-800 text-white rounded-lg focus:outline-none px-2">
+                    <option value="0" selected=selected_off>{ "Off" }</option>
+                    { for options }
+                </select>
+                { until(self.personal.as_ref(), "Until") }
+                { until(self.curfew.as_ref(), "Curfew at") }
+                { overlay }
+            </div>
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use anyhow::{anyhow, Result};
+
+
+/// A single subtitle cue.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cue {
+    /// When the cue is shown in milliseconds.
+    pub start_ms: u64,
+
+    /// When the cue is hidden in milliseconds.
+    pub end_ms: u64,
+
+    /// The lines of text shown.
+    pub text: Vec<String>,
+}
+
+
+/// Parses a `HH:MM:SS,mmm` timestamp into milliseconds, `.` is accepted
+/// in place of `,` as plenty of files in the wild use it.
+fn parse_timestamp(timestamp: &str) -> Result<u64> {
+    let timestamp = timestamp.trim();
+    let (time, millis) = timestamp
+        .split_once([',', '.'])
+        .ok_or_else(|| anyhow!("timestamp {:?} has no milliseconds", timestamp))?;
+
+    let parts = time
+        .split(':')
+        .map(|part| part.trim().parse::<u64>())
+        .collect::<Result<Vec<u64>, _>>()?;
+
+    let (hours, mins, secs) = match parts.as_slice() {
+        [hours, mins, secs] => (*hours, *mins, *secs),
+        [mins, secs] => (0, *mins, *secs),
+        _ => return Err(anyhow!("timestamp {:?} is not HH:MM:SS", timestamp)),
+    };
+
+    Ok(((hours * 60 + mins) * 60 + secs) * 1_000 + parse_fraction(millis)?)
+}
+
+
+/// Parses the fraction after the seconds into milliseconds, it's a
+/// decimal fraction so `5` is 500ms and digits past the third are dropped.
+fn parse_fraction(fraction: &str) -> Result<u64> {
+    let fraction = fraction.trim();
+    if fraction.is_empty() || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(anyhow!("{:?} is not a fraction of a second", fraction));
+    }
+
+    let millis: String = fraction.chars().chain("00".chars()).take(3).collect();
+    Ok(millis.parse()?)
+}
+
+
+/// Formats milliseconds as a WebVTT `HH:MM:SS.mmm` timestamp.
+fn format_timestamp(ms: u64) -> String {
+    let (hours, mins, secs, millis) = (
+        ms / 3_600_000,
+        (ms / 60_000) % 60,
+        (ms / 1_000) % 60,
+        ms % 1_000,
+    );
+
+    format!("{:02}:{:02}:{:02}.{:03}", hours, mins, secs, millis)
+}
+
+
+/// Parses the cues of a SubRip (.srt) file.
+///
+/// Cue numbers are optional and ignored, anything after the end
+/// timestamp on the timing line (SRT position hints) is dropped.
+pub fn parse(srt: &str) -> Result<Vec<Cue>> {
+    let srt = srt.trim_start_matches('\u{feff}').replace("\r\n", "\n");
+
+    let mut cues = vec![];
+    for block in srt.split("\n\n") {
+        let mut lines = block.lines().filter(|line| !line.trim().is_empty()).peekable();
+
+        if let Some(first) = lines.peek() {
+            if !first.contains("-->") {
+                lines.next();
+            }
+        }
+
+        let timing = match lines.next() {
+            Some(timing) => timing,
+            None => continue,
+        };
+
+        let (start, end) = timing
+            .split_once("-->")
+            .ok_or_else(|| anyhow!("expected a timing line but got {:?}", timing))?;
+        let end = end.split_whitespace().next().unwrap_or("");
+
+        cues.push(Cue {
+            start_ms: parse_timestamp(start)?,
+            end_ms: parse_timestamp(end)?,
+            text: lines.map(str::to_string).collect(),
+        });
+    }
+
+    if cues.is_empty() {
+        return Err(anyhow!("the file has no subtitles"));
+    }
+
+    Ok(cues)
+}
+
+
+/// Serializes the cues as a WebVTT file.
+pub fn to_vtt(cues: &[Cue]) -> String {
+    let mut vtt = String::from("WEBVTT\n\n");
+
+    for cue in cues {
+        vtt.push_str(&format_timestamp(cue.start_ms));
+        vtt.push_str(" --> ");
+        vtt.push_str(&format_timestamp(cue.end_ms));
+        vtt.push('\n');
+
+        for line in cue.text.iter() {
+            vtt.push_str(line);
+            vtt.push('\n');
+        }
+        vtt.push('\n');
+    }
+
+    vtt
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, parse_timestamp, to_vtt, Cue};
+
+    fn cue(start_ms: u64, end_ms: u64, text: &[&str]) -> Cue {
+        Cue {
+            start_ms,
+            end_ms,
+            text: text.iter().map(|line| line.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn short_fractions_are_padded_to_milliseconds() {
+        assert_eq!(parse_timestamp("00:00:01,5").unwrap(), 1_500);
+        assert_eq!(parse_timestamp("00:00:01,05").unwrap(), 1_050);
+        assert_eq!(parse_timestamp("00:00:01,005").unwrap(), 1_005);
+        assert_eq!(parse_timestamp("00:00:01,0055").unwrap(), 1_005);
+    }
+
+    #[test]
+    fn dots_are_accepted_as_the_separator() {
+        assert_eq!(parse_timestamp("01:02:03.456").unwrap(), 3_723_456);
+    }
+
+    #[test]
+    fn malformed_timestamps_are_rejected() {
+        assert!(parse_timestamp("00:00:01").is_err());
+        assert!(parse_timestamp("00:00:01,").is_err());
+        assert!(parse_timestamp("00:00:01,-5").is_err());
+        assert!(parse_timestamp("1:2:3:4,000").is_err());
+    }
+
+    #[test]
+    fn crlf_files_with_a_bom_are_parsed() {
+        let srt = "\u{feff}1\r\n00:00:01,000 --> 00:00:02,000\r\nHello\r\n\r\n2\r\n00:00:03,000 --> 00:00:04,000\r\nWorld\r\n";
+        assert_eq!(parse(srt).unwrap(), vec![
+            cue(1_000, 2_000, &["Hello"]),
+            cue(3_000, 4_000, &["World"]),
+        ]);
+    }
+
+    #[test]
+    fn cue_numbers_are_optional() {
+        let srt = "00:00:01,000 --> 00:00:02,000\nFirst\nline two\n\n00:00:03,000 --> 00:00:04,000\nSecond\n";
+        assert_eq!(parse(srt).unwrap(), vec![
+            cue(1_000, 2_000, &["First", "line two"]),
+            cue(3_000, 4_000, &["Second"]),
+        ]);
+    }
+
+    #[test]
+    fn position_hints_are_dropped() {
+        let srt = "1\n00:00:01,000 --> 00:00:02,000 X1:100 X2:200 Y1:10 Y2:20\nHello\n";
+        assert_eq!(parse(srt).unwrap(), vec![cue(1_000, 2_000, &["Hello"])]);
+    }
+
+    #[test]
+    fn empty_files_are_rejected() {
+        assert!(parse("").is_err());
+        assert!(parse("\n\n\n").is_err());
+    }
+
+    #[test]
+    fn cues_are_written_as_vtt() {
+        let vtt = to_vtt(&[cue(1_500, 3_723_456, &["Hello", "World"])]);
+        assert_eq!(vtt, "WEBVTT\n\n00:00:01.500 --> 01:02:03.456\nHello\nWorld\n\n");
+    }
+}
@@ -0,0 +1,58 @@
+/// The streaming protocols the player can play.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StreamKind {
+    /// HTTP Live Streaming, `.m3u8` playlists.
+    Hls,
+
+    /// MPEG-DASH, `.mpd` manifests.
+    Dash,
+}
+
+impl StreamKind {
+    /// Picks the protocol of the stream from its MIME type if the api gave
+    /// one, otherwise from the extension of the url. Anything unknown is
+    /// assumed to be HLS as that is what rooms have always streamed.
+    pub fn detect(url: &str, content_type: Option<&str>) -> Self {
+        if let Some(kind) = content_type.and_then(Self::from_mime) {
+            return kind;
+        }
+
+        let path = url
+            .split(['?', '#'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        if path.ends_with(".mpd") {
+            Self::Dash
+        } else {
+            Self::Hls
+        }
+    }
+
+    fn from_mime(mime: &str) -> Option<Self> {
+        let mime = mime
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+
+        match mime.as_str() {
+            "application/dash+xml" => Some(Self::Dash),
+            "application/x-mpegurl"
+            | "application/vnd.apple.mpegurl"
+            | "audio/mpegurl" => Some(Self::Hls),
+            _ => None,
+        }
+    }
+
+    /// The MIME type given to the player's source so it uses the right
+    /// engine.
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            Self::Hls => "application/x-mpegURL",
+            Self::Dash => "application/dash+xml",
+        }
+    }
+}
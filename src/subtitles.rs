@@ -0,0 +1,391 @@
+use yew::prelude::*;
+use yew::services::{ConsoleService, IntervalService, ReaderService};
+use yew::services::interval::IntervalTask;
+use yew::services::reader::{File, FileData, ReaderTask};
+
+use serde::Deserialize;
+use std::time::Duration;
+
+use crate::srt;
+use crate::video::VideoElementHandle;
+
+
+/// How often the shown cues are refreshed.
+const CUE_POLL_MS: u64 = 200;
+
+/// The subtitle slots, the main line and the second line shown above it.
+const SLOTS: [u32; 2] = [0, 1];
+
+
+/// A WebVTT subtitle track available for the stream.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SubtitleTrack {
+    /// The name shown in the subtitle menu e.g. English.
+    pub label: String,
+
+    /// The url of the .vtt file.
+    pub src: String,
+
+    /// The BCP 47 language tag of the track.
+    #[serde(default)]
+    pub language: String,
+
+    /// If the track is shown without the user selecting it.
+    #[serde(default)]
+    pub default: bool,
+}
+
+impl SubtitleTrack {
+    /// Renders the track as a `<track>` element for the player.
+    pub fn to_html(&self) -> Html {
+        html! {
+            <track
+                kind="subtitles"
+                src=&self.src
+                srclang=&self.language
+                label=&self.label
+                default=self.default
+            />
+        }
+    }
+}
+
+
+#[derive(Properties, Clone)]
+pub struct SubtitleMenuProperties {
+    /// The subtitle tracks of the stream.
+    pub tracks: Vec<SubtitleTrack>,
+
+    /// The player showing the tracks.
+    pub video: VideoElementHandle,
+}
+
+
+/// The events that can be invoked by callbacks for the SubtitleMenu.
+pub enum SubtitleMenuEvents {
+    /// The menu has been opened or closed.
+    Toggle,
+
+    /// A track has been picked for the slot, None empties the slot.
+    Select(u32, Option<usize>),
+
+    /// The slot's offset in seconds has been changed.
+    Offset(u32, f64),
+
+    /// The user has picked an .srt file to upload.
+    Upload(Option<File>),
+
+    /// The picked file has been read.
+    Loaded(FileData),
+}
+
+
+/// The subtitle button of the control bar and its dropdown of tracks.
+///
+/// Two tracks can be shown at once, e.g. English and Japanese for
+/// language learning watch parties, each with its own offset.
+///
+/// Users can also upload their own .srt files, these are converted to
+/// WebVTT and only added to their own player.
+pub struct SubtitleMenu {
+    link: ComponentLink<Self>,
+    tracks: Vec<SubtitleTrack>,
+    video: VideoElementHandle,
+
+    /// The labels of the tracks uploaded by the user, these come after
+    /// the stream's tracks in the player.
+    uploaded: Vec<String>,
+    reader: Option<ReaderTask>,
+
+    /// Why the last upload failed.
+    error: Option<String>,
+
+    /// The index of the track shown in each slot.
+    selected: [Option<usize>; 2],
+
+    /// The offset of each slot in seconds.
+    offsets: [f64; 2],
+    open: bool,
+}
+
+impl SubtitleMenu {
+    fn select(&mut self, slot: u32, index: Option<usize>) {
+        let js_index = index.map(|i| i as i32).unwrap_or(-1);
+        if !self.video.select_subtitle(slot, js_index) && index.is_some() {
+            ConsoleService::warn("Subtitle track is not loaded by the player");
+        }
+
+        self.selected[slot as usize] = index;
+    }
+
+    /// Shows the stream's default track, if any, in the main slot.
+    fn select_default(&mut self) {
+        let default = self.tracks.iter().position(|track| track.default);
+        self.select(0, default);
+        self.select(1, None);
+    }
+
+    /// Converts the uploaded .srt file to WebVTT and shows it.
+    fn load_srt(&mut self, data: FileData) {
+        let text = String::from_utf8_lossy(&data.content);
+        let cues = match srt::parse(&text) {
+            Ok(cues) => cues,
+            Err(e) => {
+                self.error = Some(format!("Couldn't read {}: {}", data.name, e));
+                return;
+            },
+        };
+
+        let index = self.video.add_subtitle_track(&data.name, &srt::to_vtt(&cues));
+        if index < 0 {
+            self.error = Some("The player hasn't loaded yet".to_string());
+            return;
+        }
+
+        self.uploaded.push(data.name);
+        self.select(0, Some(index as usize));
+        self.error = None;
+        self.open = false;
+    }
+
+    /// Renders the track picker and offset input of a slot.
+    fn view_slot(&self, slot: u32, title: &str) -> Html {
+        let selected = self.selected[slot as usize];
+
+        let off_cb = self.link.callback(move |_| SubtitleMenuEvents::Select(slot, None));
+        let off_weight = if selected.is_none() { "font-bold" } else { "" };
+
+        let labels = self.tracks
+            .iter()
+            .map(|track| &track.label)
+            .chain(self.uploaded.iter());
+
+        let tracks = labels.enumerate().map(|(i, label)| {
+            let select_cb = self.link.callback(move |_| SubtitleMenuEvents::Select(slot, Some(i)));
+            let weight = if selected == Some(i) { "font-bold" } else { "" };
+
+            html! {
+                <button onclick=select_cb class=("block w-full text-left text-white text-sm px-2 py-1 hover:bg-gray-700 focus:outline-none", weight)>
+                    { label }
+                </button>
+            }
+        });
+
+        let offset_cb = self.link.callback(move |e: InputData| {
+            SubtitleMenuEvents::Offset(slot, e.value.parse().unwrap_or(0.0))
+        });
+
+        html! {
+            <div class="border-b border-gray-700 pb-1 mb-1">
+                <h2 class="text-gray-400 text-xs font-semibold px-2">{ title }</h2>
+                <button onclick=off_cb class=("block w-full text-left text-white text-sm px-2 py-1 hover:bg-gray-700 focus:outline-none", off_weight)>
+                    { "Off" }
+                </button>
+                { for tracks }
+                <label class="flex items-center justify-between text-gray-400 text-xs px-2">
+                    { "Offset (s)" }
+                    <input
+                        type="number"
+                        step="0.1"
+                        value=self.offsets[slot as usize].to_string()
+                        oninput=offset_cb
+                        class="bg-gray-900 text-white rounded focus:outline-none w-16 px-1"
+                    />
+                </label>
+            </div>
+        }
+    }
+}
+
+impl Component for SubtitleMenu {
+    type Message = SubtitleMenuEvents;
+    type Properties = SubtitleMenuProperties;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let mut menu = Self {
+            link,
+            tracks: props.tracks,
+            video: props.video,
+            uploaded: vec![],
+            reader: None,
+            error: None,
+            selected: [None, None],
+            offsets: [0.0, 0.0],
+            open: false,
+        };
+
+        menu.select_default();
+        menu
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            SubtitleMenuEvents::Toggle => self.open = !self.open,
+            SubtitleMenuEvents::Select(slot, index) => self.select(slot, index),
+            SubtitleMenuEvents::Offset(slot, offset) => {
+                self.video.set_subtitle_offset(slot, offset);
+                self.offsets[slot as usize] = offset;
+                return false;
+            },
+            SubtitleMenuEvents::Upload(file) => {
+                let file = match file {
+                    Some(file) => file,
+                    None => return false,
+                };
+
+                let cb = self.link.callback(SubtitleMenuEvents::Loaded);
+                match ReaderService::new().read_file(file, cb) {
+                    Ok(task) => self.reader = Some(task),
+                    Err(e) => self.error = Some(e.to_string()),
+                }
+            },
+            SubtitleMenuEvents::Loaded(data) => {
+                self.reader = None;
+                self.load_srt(data);
+            },
+        }
+
+        true
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.tracks == props.tracks {
+            return false;
+        }
+
+        self.tracks = props.tracks;
+        self.select_default();
+        true
+    }
+
+    fn view(&self) -> Html {
+        let toggle_cb = self.link.callback(|_| SubtitleMenuEvents::Toggle);
+
+        let menu = if self.open {
+            let upload_cb = self.link.callback(|e: ChangeData| {
+                if let ChangeData::Files(files) = e {
+                    SubtitleMenuEvents::Upload(files.get(0))
+                } else {
+                    SubtitleMenuEvents::Upload(None)
+                }
+            });
+
+            let error = match self.error.as_ref() {
+                Some(error) => html! { <p class="text-red-400 text-xs px-2">{ error }</p> },
+                None => html!{},
+            };
+
+            html! {
+                <div class="absolute bottom-0 right-0 mb-8 w-48 bg-gray-800 border-2 border-gray-700 rounded-lg py-1 z-10">
+                    { self.view_slot(0, "Subtitles") }
+                    { self.view_slot(1, "Second line") }
+                    <label class="block text-gray-400 text-sm px-2 py-1 cursor-pointer hover:bg-gray-700">
+                        { "Upload .srt file" }
+                        <input type="file" accept=".srt" class="hidden" onchange=upload_cb />
+                    </label>
+                    { error }
+                </div>
+            }
+        } else {
+            html!{}
+        };
+
+        let active = self.selected.iter().any(Option::is_some);
+        let colour = if active { "text-accent" } else { "text-white" };
+
+        html! {
+            <div class="relative mr-4">
+                <button onclick=toggle_cb class=("text-sm font-bold focus:outline-none", colour) title="Subtitles">
+                    { "CC" }
+                </button>
+                { menu }
+            </div>
+        }
+    }
+}
+
+
+#[derive(Properties, Clone)]
+pub struct SubtitleOverlayProperties {
+    /// The player whose cues are shown.
+    pub video: VideoElementHandle,
+}
+
+
+/// The events that can be invoked by callbacks for the SubtitleOverlay.
+pub enum SubtitleOverlayEvents {
+    /// Refresh the shown cues.
+    Tick,
+}
+
+
+/// Renders the cues of both subtitle slots over the player, the second
+/// line is stacked above the main line in a different style so the two
+/// languages are easy to tell apart.
+pub struct SubtitleOverlay {
+    video: VideoElementHandle,
+    _poll: IntervalTask,
+
+    /// The text currently shown in each slot.
+    lines: [String; 2],
+}
+
+impl Component for SubtitleOverlay {
+    type Message = SubtitleOverlayEvents;
+    type Properties = SubtitleOverlayProperties;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let poll = IntervalService::spawn(
+            Duration::from_millis(CUE_POLL_MS),
+            link.callback(|_| SubtitleOverlayEvents::Tick),
+        );
+
+        Self {
+            video: props.video,
+            _poll: poll,
+            lines: [String::new(), String::new()],
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            SubtitleOverlayEvents::Tick => {
+                let mut changed = false;
+                for slot in SLOTS.iter().copied() {
+                    let text = self.video.active_cue_text(slot);
+                    if self.lines[slot as usize] != text {
+                        self.lines[slot as usize] = text;
+                        changed = true;
+                    }
+                }
+
+                changed
+            },
+        }
+    }
+
+    fn change(&mut self, _props: Self::Properties) -> ShouldRender {
+        false
+    }
+
+    fn view(&self) -> Html {
+        let line = |text: &str, class: &str| {
+            if text.is_empty() {
+                return html!{};
+            }
+
+            html! {
+                <p class=("bg-black bg-opacity-75 rounded px-2 py-1 mt-1 text-center whitespace-pre-line", class.to_string())>
+                    { text }
+                </p>
+            }
+        };
+
+        html! {
+            <div class="absolute bottom-0 inset-x-0 flex flex-col items-center pointer-events-none z-10 mb-12 px-8">
+                { line(&self.lines[1], "text-yellow-300 text-base italic") }
+                { line(&self.lines[0], "text-white text-xl font-semibold") }
+            </div>
+        }
+    }
+}
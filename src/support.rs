@@ -0,0 +1,272 @@
+use yew::prelude::*;
+
+use reqwest::Client;
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+
+use crate::binder;
+use crate::perf::{self, OpcodeMetrics};
+use crate::settings;
+use crate::utils::send_future;
+use crate::video::VideoElementHandle;
+
+
+/// A problem report sent to the support endpoint.
+#[derive(Serialize)]
+struct SupportReport {
+    room_id: String,
+    description: String,
+
+    /// The most recent console output of the page.
+    logs: Value,
+
+    /// The browser, page and player state.
+    diagnostics: Value,
+
+    /// The websocket traffic per opcode.
+    opcodes: Vec<OpcodeMetrics>,
+
+    /// A jpeg data url of the player's current frame.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    screenshot: Option<String>,
+}
+
+
+#[derive(Deserialize)]
+struct SupportTicket {
+    ticket_id: String,
+}
+
+
+/// Submits the report, resolving to the ticket id.
+async fn submit_report(report: SupportReport) -> SupportWidgetEvents {
+    let url = settings::get_support_url();
+
+    let resp = Client::new()
+        .post(&url)
+        .json(&report)
+        .send()
+        .await;
+
+    let ticket = match resp {
+        Ok(resp) if resp.status().is_success() => resp.json::<SupportTicket>().await.ok(),
+        _ => None,
+    };
+
+    SupportWidgetEvents::Submitted(ticket.map(|ticket| ticket.ticket_id))
+}
+
+
+#[derive(Properties, Clone)]
+pub struct SupportWidgetProperties {
+    /// The room id.
+    pub room_id: String,
+
+    /// The player the diagnostics and screenshot are taken from.
+    pub video: VideoElementHandle,
+}
+
+
+/// The events that can be invoked by callbacks for the SupportWidget.
+pub enum SupportWidgetEvents {
+    /// The form has been opened or closed.
+    Toggle,
+
+    /// The description has been edited.
+    Description(String),
+
+    /// Including a screenshot has been toggled.
+    ToggleScreenshot,
+
+    /// The user has submitted the report.
+    Submit,
+
+    /// The report has been submitted, with the ticket id or None if it
+    /// failed.
+    Submitted(Option<String>),
+}
+
+
+/// A "report a problem" form sending a description along with recent logs
+/// and diagnostics to support, the user is given a ticket id to mention
+/// on the support server.
+pub struct SupportWidget {
+    link: ComponentLink<Self>,
+    room_id: String,
+    video: VideoElementHandle,
+    open: bool,
+    description: String,
+    include_screenshot: bool,
+    submitting: bool,
+
+    /// The ticket id of the last report.
+    ticket: Option<String>,
+    error: Option<&'static str>,
+}
+
+impl SupportWidget {
+    /// Collects the report, parsing what the js side gives us.
+    fn report(&self) -> SupportReport {
+        let screenshot = if self.include_screenshot {
+            self.video.capture_frame()
+        } else {
+            None
+        };
+
+        SupportReport {
+            room_id: self.room_id.clone(),
+            description: self.description.trim().to_string(),
+            logs: serde_json::from_str(&binder::recent_logs()).unwrap_or(Value::Null),
+            diagnostics: serde_json::from_str(&self.video.diagnostic_summary()).unwrap_or(Value::Null),
+            opcodes: perf::opcode_metrics(),
+            screenshot,
+        }
+    }
+
+    fn view_form(&self) -> Html {
+        let close_cb = self.link.callback(|_| SupportWidgetEvents::Toggle);
+
+        if let Some(ticket) = self.ticket.as_ref() {
+            return html! {
+                <div class="bg-discord-dark rounded-lg p-6 w-96">
+                    <h1 class="text-white font-bold text-lg mb-2">{ "Thanks for the report" }</h1>
+                    <p class="text-gray-300 text-sm mb-4">
+                        { "Mention this ticket id on the support server so we can find your report: " }
+                        <span class="text-white font-mono font-bold">{ ticket }</span>
+                    </p>
+                    <div class="flex justify-end">
+                        <button onclick=close_cb class="text-white border-2 rounded-lg px-4 py-1 focus:outline-none">
+                            { "Close" }
+                        </button>
+                    </div>
+                </div>
+            };
+        }
+
+        let description_cb = self.link.callback(|e: InputData| SupportWidgetEvents::Description(e.value));
+        let screenshot_cb = self.link.callback(|_| SupportWidgetEvents::ToggleScreenshot);
+        let submit_cb = self.link.callback(|_| SupportWidgetEvents::Submit);
+
+        let error = match self.error {
+            Some(error) => html! { <p class="text-red-400 text-xs mb-2">{ error }</p> },
+            None => html!{},
+        };
+
+        let disabled = self.submitting || self.description.trim().is_empty();
+
+        html! {
+            <div class="bg-discord-dark rounded-lg p-6 w-96">
+                <h1 class="text-white font-bold text-lg mb-2">{ "Report a problem" }</h1>
+                <p class="text-gray-400 text-xs mb-2">
+                    { "Recent logs and details about your browser and player are sent along with your report." }
+                </p>
+                <textarea
+                    value=self.description.clone()
+                    oninput=description_cb
+                    placeholder="What went wrong?"
+                    class="bg-gray-900 text-white text-sm rounded-lg focus:outline-none w-full h-32 p-2 mb-2"
+                />
+                <label class="text-white text-sm flex items-center mb-2">
+                    <input type="checkbox" class="mr-2" checked=self.include_screenshot onclick=screenshot_cb />
+                    { "Include a screenshot of the player" }
+                </label>
+                { error }
+                <div class="flex justify-end">
+                    <button onclick=close_cb class="text-white border-2 rounded-lg px-4 py-1 mx-2 focus:outline-none">
+                        { "Cancel" }
+                    </button>
+                    <button onclick=submit_cb disabled=disabled class="bg-accent text-white font-semibold rounded-lg px-4 py-1 focus:outline-none">
+                        { if self.submitting { "Sending..." } else { "Send" } }
+                    </button>
+                </div>
+            </div>
+        }
+    }
+}
+
+impl Component for SupportWidget {
+    type Message = SupportWidgetEvents;
+    type Properties = SupportWidgetProperties;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        Self {
+            link,
+            room_id: props.room_id,
+            video: props.video,
+            open: false,
+            description: String::new(),
+            include_screenshot: false,
+            submitting: false,
+            ticket: None,
+            error: None,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            SupportWidgetEvents::Toggle => {
+                self.open = !self.open;
+
+                // Start a fresh report once the last one has been sent.
+                if self.ticket.take().is_some() {
+                    self.description.clear();
+                    self.include_screenshot = false;
+                }
+                self.error = None;
+            },
+            SupportWidgetEvents::Description(description) => {
+                self.description = description;
+                return false;
+            },
+            SupportWidgetEvents::ToggleScreenshot => {
+                self.include_screenshot = !self.include_screenshot;
+            },
+            SupportWidgetEvents::Submit => {
+                if self.submitting || self.description.trim().is_empty() {
+                    return false;
+                }
+
+                self.submitting = true;
+                self.error = None;
+                send_future(self.link.clone(), submit_report(self.report()));
+            },
+            SupportWidgetEvents::Submitted(ticket) => {
+                self.submitting = false;
+                match ticket {
+                    Some(ticket) => self.ticket = Some(ticket),
+                    None => self.error = Some("Failed to send the report, please try again later."),
+                }
+            },
+        }
+
+        true
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        self.room_id = props.room_id;
+        false
+    }
+
+    fn view(&self) -> Html {
+        let toggle_cb = self.link.callback(|_| SupportWidgetEvents::Toggle);
+
+        let form = if self.open {
+            html! {
+                <div class="fixed inset-0 flex justify-center items-center bg-black bg-opacity-50 z-40">
+                    { self.view_form() }
+                </div>
+            }
+        } else {
+            html!{}
+        };
+
+        html! {
+            <div class="flex items-center mx-2">
+                <button onclick=toggle_cb class="text-white text-sm border-2 rounded-lg px-2 focus:outline-none" title="Report a problem">
+                    { "Report" }
+                </button>
+                { form }
+            </div>
+        }
+    }
+}
@@ -0,0 +1,910 @@
+use wasm_bindgen::prelude::*;
+use yew::prelude::*;
+use yew::services::{ConsoleService, IntervalService, TimeoutService};
+use yew::services::interval::IntervalTask;
+use yew::services::timeout::TimeoutTask;
+use yew::web_sys::Element;
+
+use rustc_hash::FxHashSet;
+use serde::{Serialize, Deserialize};
+use std::time::Duration;
+
+use crate::binder;
+use crate::controls::PlayerState;
+use crate::opcodes::{self, OpCode};
+use crate::prefs::Preferences;
+use crate::settings;
+use crate::utils::{start_future, emit_event, random_id};
+use crate::video::VideoElementHandle;
+use crate::websocket::{WsHandler, WebsocketMessage, WebsocketStatus, WrappingWsMessage};
+
+
+/// The longest a member waits before answering a state request, each
+/// member waits a random amount up to this so only one of them answers.
+const MAX_SNAPSHOT_DELAY_MS: f64 = 1_500.0;
+
+/// How often the player's position is checked against the room's.
+const TIME_CHECK_INTERVAL_MS: u64 = 15_000;
+
+/// Drift below this is ignored entirely, it is within what the player
+/// itself wobbles by.
+const MIN_DRIFT_SECS: f64 = 0.25;
+
+/// How much faster or slower the player runs while catching up with
+/// the room.
+const NUDGE_RATE: f64 = 0.05;
+
+/// The longest the room waits for a buffering member before carrying on
+/// without them, in case they left or crashed while buffering.
+const MAX_BUFFER_WAIT_MS: u64 = 30_000;
+
+/// The countdown before the room resumes once enough buffering members
+/// have recovered.
+const RESUME_COUNTDOWN_SECS: u32 = 3;
+
+/// How often the start countdown overlay is refreshed.
+const COUNTDOWN_TICK_MS: u64 = 100;
+
+
+#[derive(Properties, Clone)]
+pub struct PlaybackSyncProperties {
+    /// The room websocket handle.
+    pub ws: WsHandler,
+
+    /// The room's player, only it is synced.
+    pub video: VideoElementHandle,
+
+    /// The room id.
+    pub room_id: String,
+
+    /// The url of the stream currently playing, empty if there is none.
+    pub stream_url: String,
+
+    /// If the active user controls playback for the room.
+    pub is_leader: bool,
+
+    /// If the room pauses for everyone while any member is buffering.
+    pub wait_for_everyone: bool,
+
+    /// The fraction of members that must be buffering for the leader to
+    /// pause the room, None if the room has no such policy.
+    pub buffer_pause_fraction: Option<f64>,
+
+    /// The members in the room.
+    pub members: usize,
+
+    /// If the user is watching privately, the player neither follows nor
+    /// drives the room until they re-sync.
+    pub detached: bool,
+
+    /// Called with the stream url of the snapshot answering our own state
+    /// request, for late joiners who missed the stream going live.
+    pub on_stream: Callback<String>,
+}
+
+
+/// The payload of a play, pause or seek event.
+#[derive(Serialize, Deserialize)]
+struct SyncEvent {
+    /// The position of the player in seconds.
+    position: f64,
+}
+
+
+/// A member's buffering state.
+#[derive(Serialize, Deserialize)]
+struct BufferEvent {
+    /// The random id of the member for this session.
+    member: String,
+    buffering: bool,
+}
+
+
+/// The payload of a playback rate change.
+#[derive(Serialize, Deserialize)]
+struct RateEvent {
+    rate: f64,
+}
+
+
+fn default_rate() -> f64 {
+    1.0
+}
+
+
+/// A coordinated start, every member starts playing from the position
+/// at the same server time.
+#[derive(Serialize, Deserialize)]
+pub struct CountdownEvent {
+    /// When to start playing in server milliseconds since the epoch.
+    pub start_at: f64,
+
+    /// The position to start from in seconds.
+    pub position: f64,
+}
+
+
+/// A request for the room's current playback state.
+#[derive(Serialize, Deserialize)]
+struct StateRequest {
+    request_id: String,
+}
+
+
+/// A time check sent to the gateway, the gateway answers with the same
+/// `sent_at` and the room's authoritative position.
+#[derive(Serialize, Deserialize)]
+struct TimeCheck {
+    /// When the check was sent in milliseconds, used to account for the
+    /// round trip.
+    sent_at: f64,
+
+    /// The room's position in seconds, only set in the answer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    position: Option<f64>,
+
+    #[serde(default)]
+    paused: bool,
+
+    /// The server's time in milliseconds when it answered, only set in
+    /// the answer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    server_time: Option<f64>,
+}
+
+
+/// The room's playback state in answer to a `StateRequest`.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct StateSnapshot {
+    /// The id of the request this answers.
+    request_id: String,
+
+    /// The url of the stream currently playing.
+    pub stream_url: Option<String>,
+
+    /// The position of the player in seconds.
+    position: f64,
+
+    paused: bool,
+
+    /// The room's playback rate.
+    #[serde(default = "default_rate")]
+    rate: f64,
+}
+
+
+/// The events that can be invoked by callbacks for the PlaybackSync.
+pub enum PlaybackSyncEvents {
+    /// The local user interacted with the player.
+    Local(OpCode, f64),
+
+    /// Another member interacted with their player.
+    Remote(OpCode, WebsocketMessage),
+
+    /// The websocket status has changed.
+    Status(WebsocketStatus),
+
+    /// The gateway has accepted our join code.
+    AuthAccepted,
+
+    /// The local player has been created.
+    PlayerReady,
+
+    /// A member is asking for the room's state.
+    StateRequested(WebsocketMessage),
+
+    /// A member has answered a state request.
+    Snapshot(WebsocketMessage),
+
+    /// Our random wait to answer a state request has elapsed.
+    Answer(String),
+
+    /// It is time to check our position against the room's.
+    SendTimeCheck,
+
+    /// The gateway has answered a time check.
+    TimeCheck(WebsocketMessage),
+
+    /// The player has caught up with the room, stop nudging it.
+    NudgeDone,
+
+    /// The host has changed the room's playback rate.
+    Rate(WebsocketMessage),
+
+    /// The local player has stalled or recovered.
+    LocalBuffering(bool),
+
+    /// A member's player has stalled or recovered.
+    Buffer(WebsocketMessage),
+
+    /// We have waited too long for the buffering members.
+    BufferTimeout,
+
+    /// A second of the resume countdown has passed.
+    Countdown,
+
+    /// The host has started a coordinated start countdown.
+    StartCountdown(WebsocketMessage),
+
+    /// Refresh the start countdown, starting playback once it is over.
+    StartTick,
+}
+
+
+/// Keeps the local player in sync with the rest of the room.
+///
+/// Local play, pause and seek interactions are emitted to the room and the
+/// same events from other members are applied to the local player, applied
+/// events are not reported back so the room never ends up in a loop.
+/// Only the room leader's interactions are emitted.
+///
+/// When joining, the room is asked for its current state and the first
+/// snapshot answering that request is applied once the player is ready.
+///
+/// While playing, the position is periodically checked against the room's,
+/// small drifts are corrected by running the player slightly faster or
+/// slower and drifts past the user's threshold are seeked away.
+///
+/// In wait for everyone mode a member that stalls tells the room and every
+/// member pauses until all of them report they have recovered.
+///
+/// The host can start playback with a countdown, every member shows the
+/// countdown and starts at the same server time using the clock offset
+/// measured by the time checks.
+///
+/// Rooms can instead set a buffering policy where the leader pauses the
+/// room once more than a fraction of the members are buffering, resuming
+/// after a short countdown once they recover.
+///
+/// Members can detach to watch privately, nothing is applied or emitted
+/// while detached and re-attaching asks the room for its state again.
+pub struct PlaybackSync {
+    link: ComponentLink<Self>,
+    ws: WsHandler,
+    video: VideoElementHandle,
+    on_stream: Callback<String>,
+    room_id: String,
+    stream_url: String,
+    is_leader: bool,
+
+    /// The id of our own outstanding state request.
+    pending_request: Option<String>,
+
+    /// A snapshot received before the player was ready.
+    pending_snapshot: Option<StateSnapshot>,
+
+    /// If the local player has been created.
+    player_ready: bool,
+
+    /// Our scheduled answer to another member's state request.
+    answer: Option<(String, TimeoutTask)>,
+
+    _time_check: IntervalTask,
+
+    /// Resets the playback rate once a nudge has caught the player up.
+    nudge: Option<TimeoutTask>,
+
+    /// The room's playback rate, nudges are relative to this.
+    rate: f64,
+
+    wait_for_everyone: bool,
+
+    /// Our random id used for buffer events.
+    member_id: String,
+
+    /// The members currently buffering.
+    buffering: FxHashSet<String>,
+
+    /// If we paused the player to wait for buffering members.
+    auto_paused: bool,
+    buffer_timeout: Option<TimeoutTask>,
+
+    buffer_pause_fraction: Option<f64>,
+    members: usize,
+
+    /// If the room is paused by the buffering policy.
+    policy_paused: bool,
+
+    /// The seconds left before the room resumes and the task counting
+    /// them down.
+    countdown: Option<(u32, IntervalTask)>,
+
+    /// The running start countdown and the task refreshing it.
+    start: Option<(CountdownEvent, IntervalTask)>,
+
+    detached: bool,
+
+    _js_play: Closure<dyn FnMut(f64)>,
+    _js_pause: Closure<dyn FnMut(f64)>,
+    _js_seek: Closure<dyn FnMut(f64)>,
+    _js_ready: Closure<dyn FnMut()>,
+    _js_buffering: Closure<dyn FnMut(bool)>,
+    _js_player: Closure<dyn FnMut() -> Option<Element>>,
+}
+
+impl PlaybackSync {
+    /// Converts a local interaction into a js closure.
+    fn local_closure(
+        link: &ComponentLink<Self>,
+        opcode: OpCode,
+    ) -> Closure<dyn FnMut(f64)> {
+        let cb = link.callback(move |pos| PlaybackSyncEvents::Local(opcode, pos));
+        Closure::wrap(Box::new(move |pos: f64| cb.emit(pos)) as Box<dyn FnMut(f64)>)
+    }
+
+    /// The name of the action `VideoElementHandle::apply_remote` expects.
+    fn action_name(opcode: OpCode) -> &'static str {
+        match opcode {
+            opcodes::OP_PLAY => "play",
+            opcodes::OP_PAUSE => "pause",
+            _ => "seek",
+        }
+    }
+
+    /// Emits the opcode and payload to the room.
+    fn emit<T: Serialize>(&self, opcode: OpCode, payload: T) {
+        let payload = serde_json::to_value(payload).unwrap();
+        let msg = WrappingWsMessage {
+            opcode,
+            payload: Some(payload),
+            mirror: false,
+        };
+
+        start_future(emit_event(self.room_id.clone(), msg));
+    }
+
+    /// Asks the room for its current state.
+    fn request_state(&mut self) {
+        let request_id = random_id();
+        self.emit(opcodes::OP_REQUEST_STATE, StateRequest { request_id: request_id.clone() });
+        self.pending_request = Some(request_id);
+    }
+
+    fn apply_snapshot(&mut self, snapshot: &StateSnapshot) {
+        let action = if snapshot.paused { "pause" } else { "play" };
+        self.video.apply_remote(action, snapshot.position);
+
+        self.rate = snapshot.rate;
+        self.video.set_rate(self.rate);
+    }
+
+    fn on_time_check(&mut self, msg: WebsocketMessage) {
+        let check: TimeCheck = match msg.unwrap_and_into() {
+            Some(check) => check,
+            None => return,
+        };
+
+        if let Some(server_time) = check.server_time {
+            let now = js_sys::Date::now();
+            let round_trip = (now - check.sent_at).max(0.0);
+            binder::set_clock_offset(server_time + round_trip / 2.0 - now);
+        }
+
+        let (position, state) = match (check.position, PlayerState::read(&self.video)) {
+            (Some(position), Some(state)) => (position, state),
+            _ => return,
+        };
+
+        if self.detached || state.paused || check.paused {
+            return;
+        }
+
+        let latency = self.rate * (js_sys::Date::now() - check.sent_at).max(0.0) / 2_000.0;
+        let expected = position + latency;
+        let drift = state.position - expected;
+
+        if drift.abs() < MIN_DRIFT_SECS {
+            return;
+        }
+
+        let threshold = Preferences::load().drift_threshold_secs;
+        if drift.abs() > threshold {
+            self.nudge = None;
+            self.video.set_rate(self.rate);
+            self.video.apply_remote("seek", expected);
+            return;
+        }
+
+        let nudge = if drift > 0.0 { -NUDGE_RATE } else { NUDGE_RATE };
+        let catch_up = Duration::from_secs_f64(drift.abs() / NUDGE_RATE);
+        self.video.set_rate(self.rate + nudge);
+
+        let task = TimeoutService::spawn(
+            catch_up,
+            self.link.callback(|_| PlaybackSyncEvents::NudgeDone),
+        );
+        self.nudge = Some(task);
+    }
+
+    /// Pauses while any member is buffering and resumes once they have all
+    /// recovered, only resuming if we were the ones to pause.
+    fn update_buffer_wait(&mut self) -> ShouldRender {
+        let position = PlayerState::read(&self.video).map(|s| s.position).unwrap_or(0.0);
+
+        if !self.buffering.is_empty() && !self.auto_paused && self.video.is_playing() {
+            self.auto_paused = true;
+            self.video.apply_remote("pause", position);
+
+            let task = TimeoutService::spawn(
+                Duration::from_millis(MAX_BUFFER_WAIT_MS),
+                self.link.callback(|_| PlaybackSyncEvents::BufferTimeout),
+            );
+            self.buffer_timeout = Some(task);
+            return true;
+        }
+
+        if self.buffering.is_empty() && self.auto_paused {
+            self.auto_paused = false;
+            self.buffer_timeout = None;
+            self.video.apply_remote("play", position);
+            return true;
+        }
+
+        self.auto_paused
+    }
+
+    /// If buffering is reported to the room under either buffering mode.
+    fn reports_buffering(&self) -> bool {
+        self.wait_for_everyone || self.buffer_pause_fraction.is_some()
+    }
+
+    /// Applies the room's buffering policy, every member tracks it to show
+    /// the overlay but only the leader pauses and resumes the room.
+    fn update_buffer_policy(&mut self) -> ShouldRender {
+        let threshold = match self.buffer_pause_fraction {
+            Some(threshold) if !self.wait_for_everyone => threshold,
+            _ => return false,
+        };
+
+        let fraction = self.buffering.len() as f64 / self.members.max(1) as f64;
+        let over = !self.buffering.is_empty() && fraction > threshold;
+
+        if over && !self.policy_paused {
+            if !self.video.is_playing() {
+                return false;
+            }
+
+            self.policy_paused = true;
+            self.countdown = None;
+
+            if self.is_leader {
+                let position = PlayerState::read(&self.video).map(|s| s.position).unwrap_or(0.0);
+                self.emit(opcodes::OP_PAUSE, SyncEvent { position });
+            }
+
+            let task = TimeoutService::spawn(
+                Duration::from_millis(MAX_BUFFER_WAIT_MS),
+                self.link.callback(|_| PlaybackSyncEvents::BufferTimeout),
+            );
+            self.buffer_timeout = Some(task);
+            return true;
+        }
+
+        if over && self.countdown.is_some() {
+            self.countdown = None;
+            return true;
+        }
+
+        if !over && self.policy_paused && self.countdown.is_none() {
+            let task = IntervalService::spawn(
+                Duration::from_secs(1),
+                self.link.callback(|_| PlaybackSyncEvents::Countdown),
+            );
+            self.countdown = Some((RESUME_COUNTDOWN_SECS, task));
+            return true;
+        }
+
+        false
+    }
+
+    /// Counts down to resuming the room after a policy pause.
+    fn tick_countdown(&mut self) -> ShouldRender {
+        let remaining = match self.countdown.as_mut() {
+            Some((remaining, _)) => remaining,
+            None => return false,
+        };
+
+        *remaining = remaining.saturating_sub(1);
+        if *remaining > 0 {
+            return true;
+        }
+
+        self.countdown = None;
+        self.policy_paused = false;
+        self.buffer_timeout = None;
+
+        if self.is_leader {
+            let position = PlayerState::read(&self.video).map(|s| s.position).unwrap_or(0.0);
+            self.emit(opcodes::OP_PLAY, SyncEvent { position });
+        }
+
+        true
+    }
+
+    fn on_snapshot(&mut self, msg: WebsocketMessage) {
+        let snapshot: StateSnapshot = match msg.unwrap_and_into() {
+            Some(snapshot) => snapshot,
+            None => return,
+        };
+
+        // Someone else got there first, no need for us to answer as well.
+        let answered = self.answer
+            .as_ref()
+            .map(|(id, _)| id == &snapshot.request_id)
+            .unwrap_or(false);
+        if answered {
+            self.answer = None;
+        }
+
+        if self.pending_request.as_ref() != Some(&snapshot.request_id) {
+            return;
+        }
+        self.pending_request = None;
+
+        if let Some(url) = snapshot.stream_url.clone() {
+            self.on_stream.emit(url);
+        }
+
+        if self.detached {
+            return;
+        }
+
+        if self.player_ready {
+            self.apply_snapshot(&snapshot);
+        } else {
+            self.pending_snapshot = Some(snapshot);
+        }
+    }
+}
+
+impl Component for PlaybackSync {
+    type Message = PlaybackSyncEvents;
+    type Properties = PlaybackSyncProperties;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let ws = props.ws;
+        for opcode in [opcodes::OP_PLAY, opcodes::OP_PAUSE, opcodes::OP_SEEK].iter().copied() {
+            let cb = link.callback(move |msg| PlaybackSyncEvents::Remote(opcode, msg));
+            ws.subscribe_to_message(settings::SYNC_ID, opcode, cb);
+        }
+
+        let request_cb = link.callback(PlaybackSyncEvents::StateRequested);
+        let snapshot_cb = link.callback(PlaybackSyncEvents::Snapshot);
+        ws.subscribe_to_message(settings::SYNC_ID, opcodes::OP_REQUEST_STATE, request_cb);
+        ws.subscribe_to_message(settings::SYNC_ID, opcodes::OP_STATE_SNAPSHOT, snapshot_cb);
+        ws.subscribe_to_status(settings::SYNC_ID, link.callback(PlaybackSyncEvents::Status));
+
+        // Rooms behind a join code drop anything sent before the code is
+        // accepted, including the state request made on connecting.
+        let accepted_cb = link.callback(|_| PlaybackSyncEvents::AuthAccepted);
+        ws.subscribe_to_message(settings::SYNC_ID, opcodes::OP_AUTH_ACCEPTED, accepted_cb);
+
+        let rate_cb = link.callback(PlaybackSyncEvents::Rate);
+        ws.subscribe_to_message(settings::SYNC_ID, opcodes::OP_SET_RATE, rate_cb);
+
+        let countdown_cb = link.callback(PlaybackSyncEvents::StartCountdown);
+        ws.subscribe_to_message(settings::SYNC_ID, opcodes::OP_COUNTDOWN, countdown_cb);
+
+        let time_check_cb = link.callback(PlaybackSyncEvents::TimeCheck);
+        ws.subscribe_to_message(settings::SYNC_ID, opcodes::OP_TIME_CHECK, time_check_cb);
+        let time_check = IntervalService::spawn(
+            Duration::from_millis(TIME_CHECK_INTERVAL_MS),
+            link.callback(|_| PlaybackSyncEvents::SendTimeCheck),
+        );
+
+        let js_play = Self::local_closure(&link, opcodes::OP_PLAY);
+        let js_pause = Self::local_closure(&link, opcodes::OP_PAUSE);
+        let js_seek = Self::local_closure(&link, opcodes::OP_SEEK);
+        let js_ready = {
+            let cb = link.callback(|_| PlaybackSyncEvents::PlayerReady);
+            Closure::wrap(Box::new(move || cb.emit(())) as Box<dyn FnMut()>)
+        };
+
+        let js_buffering = {
+            let cb = link.callback(PlaybackSyncEvents::LocalBuffering);
+            Closure::wrap(Box::new(move |buffering: bool| cb.emit(buffering)) as Box<dyn FnMut(bool)>)
+        };
+
+        let buffer_cb = link.callback(PlaybackSyncEvents::Buffer);
+        ws.subscribe_to_message(settings::SYNC_ID, opcodes::OP_BUFFER, buffer_cb);
+
+        let js_player = props.video.element_getter();
+        if !binder::set_sync_listeners(&js_player, &js_play, &js_pause, &js_seek, &js_ready, &js_buffering) {
+            ConsoleService::warn("Player is not loaded, playback will not be synced");
+        }
+
+        Self {
+            link,
+            ws,
+            video: props.video,
+            on_stream: props.on_stream,
+            room_id: props.room_id,
+            stream_url: props.stream_url,
+            is_leader: props.is_leader,
+            pending_request: None,
+            pending_snapshot: None,
+            player_ready: false,
+            answer: None,
+            _time_check: time_check,
+            nudge: None,
+            rate: 1.0,
+            wait_for_everyone: props.wait_for_everyone,
+            member_id: random_id(),
+            buffering: FxHashSet::default(),
+            auto_paused: false,
+            buffer_timeout: None,
+            buffer_pause_fraction: props.buffer_pause_fraction,
+            members: props.members,
+            policy_paused: false,
+            countdown: None,
+            start: None,
+            detached: props.detached,
+            _js_play: js_play,
+            _js_pause: js_pause,
+            _js_seek: js_seek,
+            _js_ready: js_ready,
+            _js_buffering: js_buffering,
+            _js_player: js_player,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            PlaybackSyncEvents::Local(opcode, position) => {
+                if self.is_leader && !self.detached {
+                    self.emit(opcode, SyncEvent { position });
+                }
+            },
+            PlaybackSyncEvents::Remote(_, _) if self.detached => {},
+            PlaybackSyncEvents::Remote(opcode, msg) => {
+                if let Some(event) = msg.unwrap_and_into::<SyncEvent>() {
+                    self.video.apply_remote(Self::action_name(opcode), event.position);
+                } else {
+                    ConsoleService::warn("Received sync event without a position");
+                }
+            },
+            PlaybackSyncEvents::Status(WebsocketStatus::Connect) => {
+                self.request_state();
+            },
+            PlaybackSyncEvents::AuthAccepted => {
+                self.request_state();
+            },
+            PlaybackSyncEvents::Status(_) => {},
+            PlaybackSyncEvents::PlayerReady => {
+                self.player_ready = true;
+                self.link.send_message(PlaybackSyncEvents::SendTimeCheck);
+                if let Some(snapshot) = self.pending_snapshot.take() {
+                    self.apply_snapshot(&snapshot);
+                }
+            },
+            PlaybackSyncEvents::StateRequested(msg) => {
+                let request: StateRequest = match msg.unwrap_and_into() {
+                    Some(request) => request,
+                    None => return false,
+                };
+
+                // A detached player's position is our own, not the room's.
+                let ours = self.pending_request.as_ref() == Some(&request.request_id);
+                if ours || !self.player_ready || self.detached {
+                    return false;
+                }
+
+                let delay = js_sys::Math::random() * MAX_SNAPSHOT_DELAY_MS;
+                let request_id = request.request_id.clone();
+                let task = TimeoutService::spawn(
+                    Duration::from_millis(delay as u64),
+                    self.link.callback(move |_| PlaybackSyncEvents::Answer(request_id.clone())),
+                );
+                self.answer = Some((request.request_id, task));
+            },
+            PlaybackSyncEvents::Snapshot(msg) => self.on_snapshot(msg),
+            PlaybackSyncEvents::Answer(request_id) => {
+                self.answer = None;
+
+                if let Some(state) = PlayerState::read(&self.video) {
+                    let stream_url = Some(self.stream_url.clone())
+                        .filter(|url| !url.is_empty());
+
+                    let snapshot = StateSnapshot {
+                        request_id,
+                        stream_url,
+                        position: state.position,
+                        paused: state.paused,
+                        rate: self.rate,
+                    };
+                    self.emit(opcodes::OP_STATE_SNAPSHOT, snapshot);
+                }
+            },
+            PlaybackSyncEvents::SendTimeCheck => {
+                // Checks are sent while paused as well to keep the clock
+                // offset fresh for countdowns.
+                if !self.player_ready {
+                    return false;
+                }
+
+                let check = TimeCheck {
+                    sent_at: js_sys::Date::now(),
+                    position: None,
+                    paused: false,
+                    server_time: None,
+                };
+                self.ws.send(opcodes::OP_TIME_CHECK, serde_json::to_value(check).ok());
+            },
+            PlaybackSyncEvents::TimeCheck(msg) => self.on_time_check(msg),
+            PlaybackSyncEvents::NudgeDone => {
+                self.nudge = None;
+                self.video.set_rate(self.rate);
+            },
+            PlaybackSyncEvents::LocalBuffering(buffering) => {
+                // Stalls caused by our own pause aren't worth reporting.
+                let paused_for_us = self.auto_paused || self.policy_paused;
+                if !self.reports_buffering() || self.detached || (buffering && paused_for_us) {
+                    return false;
+                }
+
+                let event = BufferEvent {
+                    member: self.member_id.clone(),
+                    buffering,
+                };
+                self.emit(opcodes::OP_BUFFER, event);
+            },
+            PlaybackSyncEvents::Buffer(msg) => {
+                let event: BufferEvent = match msg.unwrap_and_into() {
+                    Some(event) => event,
+                    None => return false,
+                };
+
+                if !self.reports_buffering() {
+                    return false;
+                }
+
+                if event.buffering {
+                    self.buffering.insert(event.member);
+                } else {
+                    self.buffering.remove(&event.member);
+                }
+
+                if self.detached {
+                    return false;
+                }
+
+                if self.wait_for_everyone {
+                    return self.update_buffer_wait();
+                }
+                return self.update_buffer_policy();
+            },
+            PlaybackSyncEvents::BufferTimeout => {
+                ConsoleService::warn("Gave up waiting for buffering members");
+                self.buffering.clear();
+
+                if self.wait_for_everyone {
+                    return self.update_buffer_wait();
+                }
+                return self.update_buffer_policy();
+            },
+            PlaybackSyncEvents::Countdown => return self.tick_countdown(),
+            PlaybackSyncEvents::StartCountdown(_) if self.detached => {},
+            PlaybackSyncEvents::StartCountdown(msg) => {
+                let event: CountdownEvent = match msg.unwrap_and_into() {
+                    Some(event) => event,
+                    None => return false,
+                };
+
+                // Pausing at the position gives the player the countdown
+                // to buffer it.
+                self.video.apply_remote("pause", event.position);
+
+                let task = IntervalService::spawn(
+                    Duration::from_millis(COUNTDOWN_TICK_MS),
+                    self.link.callback(|_| PlaybackSyncEvents::StartTick),
+                );
+                self.start = Some((event, task));
+                return true;
+            },
+            PlaybackSyncEvents::StartTick => {
+                let (event, _) = match self.start.as_ref() {
+                    Some(start) => start,
+                    None => return false,
+                };
+
+                let remaining_ms = event.start_at - binder::server_now();
+                if remaining_ms > 0.0 {
+                    return true;
+                }
+
+                // Make up for however late this tick fired.
+                let position = event.position - remaining_ms * self.rate / 1_000.0;
+                self.video.apply_remote("play", position);
+                self.start = None;
+                return true;
+            },
+            PlaybackSyncEvents::Rate(msg) => {
+                if let Some(event) = msg.unwrap_and_into::<RateEvent>() {
+                    self.rate = event.rate.clamp(0.5, 2.0);
+                    self.nudge = None;
+                    self.video.set_rate(self.rate);
+                }
+            },
+        }
+
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        self.stream_url = props.stream_url;
+        self.is_leader = props.is_leader;
+
+        self.members = props.members;
+
+        if self.wait_for_everyone && !props.wait_for_everyone {
+            self.buffering.clear();
+            self.update_buffer_wait();
+        }
+        self.wait_for_everyone = props.wait_for_everyone;
+
+        if self.buffer_pause_fraction.is_some() && props.buffer_pause_fraction.is_none() {
+            self.policy_paused = false;
+            self.countdown = None;
+            self.buffer_timeout = None;
+        }
+        self.buffer_pause_fraction = props.buffer_pause_fraction;
+
+        if !self.reports_buffering() {
+            self.buffering.clear();
+        }
+
+        if props.detached && !self.detached {
+            self.nudge = None;
+            self.start = None;
+            self.answer = None;
+            self.video.set_rate(self.rate);
+        } else if !props.detached && self.detached && self.player_ready {
+            self.auto_paused = false;
+            self.request_state();
+        }
+        self.detached = props.detached;
+
+        true
+    }
+
+    fn view(&self) -> Html {
+        if let Some((event, _)) = self.start.as_ref() {
+            let remaining = ((event.start_at - binder::server_now()) / 1_000.0).ceil().max(1.0);
+
+            return html! {
+                <div class="absolute inset-0 flex justify-center items-center pointer-events-none z-20">
+                    <span class="text-white font-bold text-8xl animate-pulse">{ remaining }</span>
+                </div>
+            };
+        }
+
+        if self.detached {
+            return html!{};
+        }
+
+        let waiting = if let Some((remaining, _)) = self.countdown.as_ref() {
+            format!("Resuming in {}...", remaining)
+        } else if self.policy_paused {
+            format!(
+                "Paused while {} of {} members buffer...",
+                self.buffering.len(),
+                self.members,
+            )
+        } else if self.auto_paused {
+            match self.buffering.len() {
+                1 => "Waiting for 1 member to buffer...".to_string(),
+                n => format!("Waiting for {} members to buffer...", n),
+            }
+        } else {
+            return html!{};
+        };
+
+        html! {
+            <div class="absolute bottom-0 inset-x-0 flex justify-center pointer-events-none z-20 mb-8">
+                <div class="bg-gray-900 bg-opacity-75 text-white text-sm font-semibold rounded-lg px-4 py-2">
+                    { waiting }
+                </div>
+            </div>
+        }
+    }
+}
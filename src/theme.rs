@@ -0,0 +1,355 @@
+use yew::prelude::*;
+use yew::services::ConsoleService;
+
+use reqwest::Client;
+use serde::{Serialize, Deserialize};
+
+use crate::analytics::AnalyticsDashboard;
+use crate::onboarding::OwnerChecklist;
+use crate::settings;
+use crate::utils::send_future;
+
+
+/// The CSS variable the accent colour is exposed as, see the `accent`
+/// classes in `index.html`.
+const ACCENT_VARIABLE: &str = "--room-accent";
+
+
+/// The owner set branding of a room.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoomTheme {
+    /// The accent colour as a `#rrggbb` hex string.
+    #[serde(default)]
+    accent_colour: Option<String>,
+
+    /// The full url of the banner image.
+    #[serde(default)]
+    banner_url: Option<String>,
+
+    /// The message shown to everyone joining the room.
+    #[serde(default)]
+    welcome_message: Option<String>,
+
+    /// The rules new members acknowledge before the player loads.
+    #[serde(default)]
+    rules: Option<String>,
+
+    /// If the requesting user owns the room and can edit the theme, this
+    /// is decided by the api and never sent back.
+    #[serde(default, skip_serializing)]
+    editable: bool,
+}
+
+impl RoomTheme {
+    /// Applies the accent colour to the document, invalid colours are
+    /// ignored as they end up inside a style attribute.
+    fn apply(&self) {
+        let colour = match self.accent_colour.as_ref() {
+            Some(colour) if is_hex_colour(colour) => colour,
+            _ => return,
+        };
+
+        let root = yew::utils::document().document_element();
+        if let Some(root) = root {
+            let style = format!("{}: {};", ACCENT_VARIABLE, colour);
+            let _ = root.set_attribute("style", &style);
+        }
+    }
+}
+
+
+/// If the string is a `#rrggbb` hex colour.
+pub(crate) fn is_hex_colour(colour: &str) -> bool {
+    colour.len() == 7
+        && colour.starts_with('#')
+        && colour[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+
+/// Fetches the room's theme.
+async fn fetch_theme(room_id: String) -> RoomBrandingEvents {
+    let url = settings::get_room_theme_url(&room_id);
+
+    let resp = Client::new()
+        .get(&url)
+        .send()
+        .await;
+
+    match resp {
+        Ok(resp) => {
+            let theme = resp.json::<RoomTheme>().await.unwrap_or_default();
+            RoomBrandingEvents::Theme(theme)
+        },
+        Err(_) => RoomBrandingEvents::RequestError,
+    }
+}
+
+
+/// Saves the room's theme, only the owner is allowed to do this.
+async fn save_theme(room_id: String, theme: RoomTheme) -> RoomBrandingEvents {
+    let url = settings::get_room_theme_url(&room_id);
+
+    let resp = Client::new()
+        .put(&url)
+        .json(&theme)
+        .send()
+        .await;
+
+    match resp {
+        Ok(resp) if resp.status().is_success() => RoomBrandingEvents::Saved(theme),
+        _ => RoomBrandingEvents::RequestError,
+    }
+}
+
+
+#[derive(Properties, Clone)]
+pub struct RoomBrandingProperties {
+    /// The room id.
+    pub room_id: String,
+}
+
+
+/// The events that can be invoked by callbacks for the RoomBranding.
+pub enum RoomBrandingEvents {
+    /// The theme has been fetched.
+    Theme(RoomTheme),
+
+    /// The edited theme has been saved.
+    Saved(RoomTheme),
+
+    /// The request failed.
+    RequestError,
+
+    /// The editor has been opened or closed.
+    ToggleEditor,
+
+    /// The accent colour input has changed.
+    Accent(String),
+
+    /// The banner url input has changed.
+    Banner(String),
+
+    /// The welcome message input has changed.
+    Welcome(String),
+
+    /// The rules input has changed.
+    Rules(String),
+
+    /// The save button has been pressed.
+    Save,
+
+    /// The welcome banner has been dismissed.
+    Dismiss,
+
+    /// The owner's analytics have been opened or closed.
+    ToggleAnalytics,
+}
+
+
+/// The room's banner and welcome message along with the owner's
+/// branding editor and setup checklist.
+///
+/// The accent colour is applied as a CSS variable on the document so any
+/// element using the `accent` classes picks it up.
+pub struct RoomBranding {
+    link: ComponentLink<Self>,
+    room_id: String,
+
+    theme: RoomTheme,
+
+    /// The theme being edited, Some while the editor is open.
+    draft: Option<RoomTheme>,
+
+    dismissed: bool,
+
+    /// If the owner's analytics are open.
+    analytics_open: bool,
+}
+
+impl Component for RoomBranding {
+    type Message = RoomBrandingEvents;
+    type Properties = RoomBrandingProperties;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        send_future(
+            link.clone(),
+            fetch_theme(props.room_id.clone())
+        );
+
+        Self {
+            link,
+            room_id: props.room_id,
+            theme: RoomTheme::default(),
+            draft: None,
+            dismissed: false,
+            analytics_open: false,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            RoomBrandingEvents::Theme(theme) => {
+                theme.apply();
+                self.theme = theme;
+            },
+            RoomBrandingEvents::Saved(mut theme) => {
+                theme.editable = self.theme.editable;
+                theme.apply();
+                self.theme = theme;
+                self.draft = None;
+            },
+            RoomBrandingEvents::RequestError => {
+                ConsoleService::error("Failed to get or save the room theme");
+                return false;
+            },
+            RoomBrandingEvents::ToggleEditor => {
+                self.draft = match self.draft {
+                    Some(_) => None,
+                    None => Some(self.theme.clone()),
+                };
+            },
+            RoomBrandingEvents::Accent(colour) => {
+                if let Some(draft) = self.draft.as_mut() {
+                    draft.accent_colour = Some(colour);
+                }
+            },
+            RoomBrandingEvents::Banner(url) => {
+                if let Some(draft) = self.draft.as_mut() {
+                    draft.banner_url = Some(url).filter(|url| !url.is_empty());
+                }
+            },
+            RoomBrandingEvents::Welcome(msg) => {
+                if let Some(draft) = self.draft.as_mut() {
+                    draft.welcome_message = Some(msg).filter(|msg| !msg.is_empty());
+                }
+            },
+            RoomBrandingEvents::Rules(rules) => {
+                if let Some(draft) = self.draft.as_mut() {
+                    draft.rules = Some(rules).filter(|rules| !rules.trim().is_empty());
+                }
+            },
+            RoomBrandingEvents::Save => {
+                if let Some(draft) = self.draft.clone() {
+                    send_future(
+                        self.link.clone(),
+                        save_theme(self.room_id.clone(), draft)
+                    );
+                }
+                return false;
+            },
+            RoomBrandingEvents::Dismiss => {
+                self.dismissed = true;
+            },
+            RoomBrandingEvents::ToggleAnalytics => {
+                self.analytics_open = !self.analytics_open;
+            },
+        }
+
+        true
+    }
+
+    fn change(&mut self, _props: Self::Properties) -> ShouldRender {
+        false
+    }
+
+    fn view(&self) -> Html {
+        let edit_button = if self.theme.editable {
+            let edit_cb = self.link.callback(|_| RoomBrandingEvents::ToggleEditor);
+            let analytics_cb = self.link.callback(|_| RoomBrandingEvents::ToggleAnalytics);
+
+            html! {
+                <>
+                    <button onclick=analytics_cb class="text-white text-sm border-2 rounded-lg px-2 focus:outline-none ml-4">
+                        { "Analytics" }
+                    </button>
+                    <button onclick=edit_cb class="text-white text-sm border-2 rounded-lg px-2 focus:outline-none ml-4">
+                        { "Edit branding" }
+                    </button>
+                </>
+            }
+        } else {
+            html!{}
+        };
+
+        let banner = if self.dismissed {
+            html!{}
+        } else if let Some(msg) = self.theme.welcome_message.as_ref() {
+            let dismiss_cb = self.link.callback(|_| RoomBrandingEvents::Dismiss);
+            let style = self.theme.banner_url
+                .as_ref()
+                .map(|url| format!("background-image: url({:?}); background-size: cover;", url))
+                .unwrap_or_default();
+
+            html! {
+                <div class="flex justify-between items-center border-b-4 border-accent rounded-lg bg-discord-dark py-4 px-8" data-style=style>
+                    <h1 class="text-white font-bold text-xl">{ msg }</h1>
+                    <div class="flex items-center">
+                        { edit_button }
+                        <button onclick=dismiss_cb class="text-white border-2 rounded-lg w-8 h-8 focus:outline-none ml-4">
+                            { "✕" }
+                        </button>
+                    </div>
+                </div>
+            }
+        } else {
+            html! {
+                <div class="flex justify-end">{ edit_button }</div>
+            }
+        };
+
+        html! {
+            <div class="px-8 pt-4">
+                { banner }
+                { if self.theme.editable { html! { <OwnerChecklist room_id=self.room_id.clone() /> } } else { html!{} } }
+                { self.view_editor() }
+                { if self.analytics_open { html! { <AnalyticsDashboard room_id=self.room_id.clone() /> } } else { html!{} } }
+            </div>
+        }
+    }
+}
+
+impl RoomBranding {
+    /// Renders the owner's branding editor if it is open.
+    fn view_editor(&self) -> Html {
+        let draft = match self.draft.as_ref() {
+            Some(draft) => draft,
+            None => return html!{},
+        };
+
+        let accent_cb = self.link.callback(|e: InputData| RoomBrandingEvents::Accent(e.value));
+        let banner_cb = self.link.callback(|e: InputData| RoomBrandingEvents::Banner(e.value));
+        let welcome_cb = self.link.callback(|e: InputData| RoomBrandingEvents::Welcome(e.value));
+        let rules_cb = self.link.callback(|e: InputData| RoomBrandingEvents::Rules(e.value));
+        let save_cb = self.link.callback(|_| RoomBrandingEvents::Save);
+        let cancel_cb = self.link.callback(|_| RoomBrandingEvents::ToggleEditor);
+
+        let accent = draft.accent_colour.clone().unwrap_or_else(|| "#7161ef".to_string());
+        let banner = draft.banner_url.clone().unwrap_or_default();
+        let welcome = draft.welcome_message.clone().unwrap_or_default();
+        let rules = draft.rules.clone().unwrap_or_default();
+
+        html! {
+            <div class="bg-discord-dark rounded-lg p-4 mt-4">
+                <label class="text-white text-sm block mb-1">{ "Accent colour" }</label>
+                <input type="color" oninput=accent_cb value=accent class="mb-3" />
+                <label class="text-white text-sm block mb-1">{ "Banner image url" }</label>
+                <input type="text" oninput=banner_cb value=banner
+                    class="bg-gray-800 text-white rounded-lg focus:outline-none w-full h-8 px-2 mb-3" />
+                <label class="text-white text-sm block mb-1">{ "Welcome message" }</label>
+                <textarea oninput=welcome_cb value=welcome
+                    class="bg-gray-800 text-white rounded-lg focus:outline-none w-full px-2 mb-3" />
+                <label class="text-white text-sm block mb-1">{ "Rules, shown to new members before the player loads" }</label>
+                <textarea oninput=rules_cb value=rules rows="4"
+                    class="bg-gray-800 text-white rounded-lg focus:outline-none w-full px-2 mb-3" />
+                <div class="flex justify-end">
+                    <button onclick=cancel_cb class="text-white border-2 rounded-lg px-4 py-1 mx-2 focus:outline-none">
+                        { "Cancel" }
+                    </button>
+                    <button onclick=save_cb class="bg-accent text-white font-semibold rounded-lg px-4 py-1 focus:outline-none">
+                        { "Save" }
+                    </button>
+                </div>
+            </div>
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use wasm_bindgen::JsValue;
+
+/// Formats seconds as `H:MM:SS` or `M:SS` if under an hour.
+pub fn format_clock(secs: f64) -> String {
+    let total = secs.max(0.0) as u64;
+    let (hours, mins, secs) = (total / 3600, (total / 60) % 60, total % 60);
+
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, mins, secs)
+    } else {
+        format!("{}:{:02}", mins, secs)
+    }
+}
+
+
+/// Formats seconds as a rounded down duration e.g. `1h 25m`, `12m` or
+/// `40s` if under a minute.
+pub fn format_duration(secs: f64) -> String {
+    let total = secs.max(0.0) as u64;
+    let mins = total / 60;
+
+    if mins >= 60 {
+        format!("{}h {}m", mins / 60, mins % 60)
+    } else if mins > 0 {
+        format!("{}m", mins)
+    } else {
+        format!("{}s", total)
+    }
+}
+
+
+/// Formats a timestamp in milliseconds as a local time of day e.g.
+/// `9:05 PM` or `21:05:30`.
+pub fn format_time_of_day(ms: f64, clock_24h: bool, seconds: bool) -> String {
+    let date = js_sys::Date::new(&JsValue::from_f64(ms));
+    let (hours, mins, secs) = (date.get_hours(), date.get_minutes(), date.get_seconds());
+
+    let (hours, suffix) = if clock_24h {
+        (hours, "")
+    } else if hours < 12 {
+        (if hours == 0 { 12 } else { hours }, " AM")
+    } else {
+        (if hours == 12 { 12 } else { hours - 12 }, " PM")
+    };
+
+    let mut formatted = if clock_24h {
+        format!("{:02}:{:02}", hours, mins)
+    } else {
+        format!("{}:{:02}", hours, mins)
+    };
+    if seconds {
+        formatted.push_str(&format!(":{:02}", secs));
+    }
+    formatted.push_str(suffix);
+    formatted
+}
+
+
+/// Formats a timestamp in milliseconds relative to now e.g. `5m ago`,
+/// timestamps in the future are clamped to `just now`.
+pub fn format_ago(ms: f64) -> String {
+    let secs = (js_sys::Date::now() - ms) / 1_000.0;
+    if secs < 60.0 {
+        return "just now".to_string();
+    }
+
+    format!("{} ago", format_duration(secs))
+}
+
+
+/// Formats seconds with units e.g. `1h2m3s`, as used in links.
+pub fn format_units(secs: f64) -> String {
+    let total = secs.max(0.0) as u64;
+    let (hours, mins, secs) = (total / 3600, (total / 60) % 60, total % 60);
+
+    let mut formatted = String::new();
+    if hours > 0 {
+        formatted.push_str(&format!("{}h", hours));
+    }
+    if hours > 0 || mins > 0 {
+        formatted.push_str(&format!("{}m", mins));
+    }
+    formatted.push_str(&format!("{}s", secs));
+    formatted
+}
+
+
+/// Parses a time typed by the user into seconds.
+///
+/// Accepts clock times like `1:23:45` or `23:45`, plain seconds like `90`
+/// and units like `1h23m45s` or `5m`. None if the input is none of these
+/// or is too large to count.
+pub fn parse_clock(input: &str) -> Option<f64> {
+    let input = input.trim().to_ascii_lowercase();
+    if input.is_empty() {
+        return None;
+    }
+
+    if input.contains(':') {
+        let parts = input
+            .split(':')
+            .map(|part| part.trim().parse::<u64>().ok())
+            .collect::<Option<Vec<u64>>>()?;
+
+        let secs = match parts.as_slice() {
+            [hours, mins, secs] if *mins < 60 && *secs < 60 => {
+                hours.checked_mul(3600)?.checked_add(mins * 60 + secs)?
+            },
+            [mins, secs] if *secs < 60 => mins.checked_mul(60)?.checked_add(*secs)?,
+            _ => return None,
+        };
+        return Some(secs as f64);
+    }
+
+    if let Ok(secs) = input.parse::<f64>() {
+        return Some(secs).filter(|secs| secs.is_finite() && *secs >= 0.0);
+    }
+
+    let mut total: u64 = 0;
+    let mut number = String::new();
+    for c in input.chars() {
+        let scale = match c {
+            '0'..='9' => {
+                number.push(c);
+                continue;
+            },
+            'h' => 3600,
+            'm' => 60,
+            's' => 1,
+            _ => return None,
+        };
+
+        total = total.checked_add(number.parse::<u64>().ok()?.checked_mul(scale)?)?;
+        number.clear();
+    }
+
+    // A trailing number without a unit is seconds, e.g. `1m30`.
+    if !number.is_empty() {
+        total = total.checked_add(number.parse::<u64>().ok()?)?;
+    }
+
+    Some(total as f64)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{format_clock, format_duration, format_units, parse_clock};
+
+    #[test]
+    fn clocks_show_hours_only_when_needed() {
+        assert_eq!(format_clock(0.0), "0:00");
+        assert_eq!(format_clock(65.9), "1:05");
+        assert_eq!(format_clock(3_723.0), "1:02:03");
+        assert_eq!(format_clock(-5.0), "0:00");
+    }
+
+    #[test]
+    fn durations_are_rounded_down_to_the_largest_units() {
+        assert_eq!(format_duration(40.0), "40s");
+        assert_eq!(format_duration(12.0 * 60.0 + 59.0), "12m");
+        assert_eq!(format_duration(85.0 * 60.0), "1h 25m");
+    }
+
+    #[test]
+    fn units_skip_leading_zero_units() {
+        assert_eq!(format_units(3.0), "3s");
+        assert_eq!(format_units(63.0), "1m3s");
+        assert_eq!(format_units(3_603.0), "1h0m3s");
+    }
+
+    #[test]
+    fn clock_times_are_parsed() {
+        assert_eq!(parse_clock("1:23:45"), Some(5_025.0));
+        assert_eq!(parse_clock(" 23:45 "), Some(1_425.0));
+        assert_eq!(parse_clock("1:60"), None);
+        assert_eq!(parse_clock("1:2:3:4"), None);
+    }
+
+    #[test]
+    fn seconds_and_units_are_parsed() {
+        assert_eq!(parse_clock("90"), Some(90.0));
+        assert_eq!(parse_clock("1h23m45s"), Some(5_025.0));
+        assert_eq!(parse_clock("5M"), Some(300.0));
+        assert_eq!(parse_clock("1m30"), Some(90.0));
+        assert_eq!(parse_clock("-5"), None);
+        assert_eq!(parse_clock("5x"), None);
+        assert_eq!(parse_clock(""), None);
+    }
+
+    #[test]
+    fn overflowing_times_are_rejected() {
+        assert_eq!(parse_clock(&format!("{}:00:00", u64::MAX)), None);
+        assert_eq!(parse_clock(&format!("{}:00", u64::MAX)), None);
+        assert_eq!(parse_clock(&format!("{}h", u64::MAX)), None);
+        assert_eq!(parse_clock(&format!("{}s{}s", u64::MAX, 1)), None);
+    }
+
+    #[test]
+    fn formatted_units_parse_back() {
+        for secs in [0.0, 59.0, 61.0, 3_600.0, 86_399.0].iter().copied() {
+            assert_eq!(parse_clock(&format_units(secs)), Some(secs));
+        }
+    }
+}
@@ -0,0 +1,271 @@
+use yew::prelude::*;
+
+use serde::{Serialize, Deserialize};
+
+use crate::utils::{load_local, store_local};
+use crate::video::VideoElementHandle;
+
+
+/// The local storage key the transforms of every stream are stored under.
+const TRANSFORMS_KEY: &str = "spooderfy.transforms";
+
+/// How many streams' transforms are remembered, the oldest are dropped.
+const MAX_STORED_TRANSFORMS: usize = 50;
+
+/// The most the video can be zoomed in to crop out baked in borders.
+const MAX_ZOOM: f64 = 2.0;
+
+
+/// How the video is sized within the player.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum FitMode {
+    /// The whole video is shown, letterboxed if needed.
+    Fit,
+
+    /// The player is filled, cropping the edges of the video.
+    Fill,
+}
+
+impl FitMode {
+    fn object_fit(self) -> &'static str {
+        match self {
+            FitMode::Fit => "contain",
+            FitMode::Fill => "cover",
+        }
+    }
+}
+
+
+/// The transforms applied to the video to correct bad encodes.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct VideoTransform {
+    pub fit: FitMode,
+
+    /// The aspect ratio the video is stretched to, None keeps the
+    /// stream's own.
+    pub aspect: Option<(u32, u32)>,
+
+    /// The clockwise rotation in degrees, a multiple of 90.
+    pub rotation: u32,
+
+    /// How far the video is zoomed in, 1 being not at all.
+    pub zoom: f64,
+}
+
+impl Default for VideoTransform {
+    fn default() -> Self {
+        Self {
+            fit: FitMode::Fit,
+            aspect: None,
+            rotation: 0,
+            zoom: 1.0,
+        }
+    }
+}
+
+impl VideoTransform {
+    /// Loads the transform last used for the stream.
+    pub fn load(stream_url: &str) -> Self {
+        load_local::<Vec<StoredTransform>>(TRANSFORMS_KEY)
+            .unwrap_or_default()
+            .into_iter()
+            .find(|stored| stored.stream_url == stream_url)
+            .map(|stored| stored.transform)
+            .unwrap_or_default()
+    }
+
+    /// Remembers the transform for the stream, a default transform is
+    /// forgotten rather than stored.
+    pub fn save(&self, stream_url: &str) {
+        let mut stored: Vec<StoredTransform> = load_local(TRANSFORMS_KEY).unwrap_or_default();
+        stored.retain(|stored| stored.stream_url != stream_url);
+
+        if *self != Self::default() {
+            stored.push(StoredTransform {
+                stream_url: stream_url.to_string(),
+                transform: *self,
+            });
+        }
+
+        let excess = stored.len().saturating_sub(MAX_STORED_TRANSFORMS);
+        stored.drain(..excess);
+
+        store_local(TRANSFORMS_KEY, &stored);
+    }
+
+    /// Applies the transform to the player.
+    pub fn apply(&self, video: &VideoElementHandle) {
+        let aspect = self.aspect
+            .map(|(width, height)| f64::from(width) / f64::from(height))
+            .unwrap_or(0.0);
+
+        video.apply_transform(self.fit.object_fit(), aspect, self.rotation, self.zoom);
+    }
+}
+
+
+/// The transform of a single stream as kept in local storage.
+#[derive(Serialize, Deserialize)]
+struct StoredTransform {
+    stream_url: String,
+    transform: VideoTransform,
+}
+
+
+#[derive(Properties, Clone)]
+pub struct TransformMenuProperties {
+    /// The url of the stream being played, transforms are kept per stream.
+    pub stream_url: String,
+
+    /// The player being transformed.
+    pub video: VideoElementHandle,
+}
+
+
+/// The events that can be invoked by callbacks for the TransformMenu.
+pub enum TransformMenuEvents {
+    /// The menu has been opened or closed.
+    Toggle,
+
+    /// A fit mode has been picked, this clears any forced aspect ratio.
+    Fit(FitMode),
+
+    /// An aspect ratio has been picked.
+    Aspect((u32, u32)),
+
+    /// Rotate the video a further 90 degrees.
+    Rotate,
+
+    /// The zoom slider has been moved.
+    Zoom(f64),
+
+    /// Go back to the untransformed video.
+    Reset,
+}
+
+
+/// The menu for zooming, cropping, stretching and rotating the video,
+/// for encodes with baked in letterboxing or the wrong aspect flags.
+///
+/// The transforms are only applied locally and remembered per stream.
+pub struct TransformMenu {
+    link: ComponentLink<Self>,
+    stream_url: String,
+    video: VideoElementHandle,
+    transform: VideoTransform,
+    open: bool,
+}
+
+impl Component for TransformMenu {
+    type Message = TransformMenuEvents;
+    type Properties = TransformMenuProperties;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        Self {
+            link,
+            transform: VideoTransform::load(&props.stream_url),
+            stream_url: props.stream_url,
+            video: props.video,
+            open: false,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            TransformMenuEvents::Toggle => {
+                self.open = !self.open;
+                return true;
+            },
+            TransformMenuEvents::Fit(fit) => {
+                self.transform.fit = fit;
+                self.transform.aspect = None;
+            },
+            TransformMenuEvents::Aspect(aspect) => self.transform.aspect = Some(aspect),
+            TransformMenuEvents::Rotate => {
+                self.transform.rotation = (self.transform.rotation + 90) % 360;
+            },
+            TransformMenuEvents::Zoom(zoom) => self.transform.zoom = zoom.clamp(1.0, MAX_ZOOM),
+            TransformMenuEvents::Reset => self.transform = VideoTransform::default(),
+        }
+
+        self.transform.apply(&self.video);
+        self.transform.save(&self.stream_url);
+        true
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.stream_url == props.stream_url {
+            return false;
+        }
+
+        // The player applies the new stream's transform once it loads.
+        self.transform = VideoTransform::load(&props.stream_url);
+        self.stream_url = props.stream_url;
+        true
+    }
+
+    fn view(&self) -> Html {
+        let toggle_cb = self.link.callback(|_| TransformMenuEvents::Toggle);
+        let border = if self.transform == VideoTransform::default() { "" } else { "border-accent" };
+
+        let menu = if self.open {
+            let preset = |label: &str, active: bool, cb: Callback<MouseEvent>| {
+                let weight = if active { "font-bold bg-gray-700" } else { "" };
+                html! {
+                    <button onclick=cb class=("text-white text-sm rounded-lg px-2 py-1 hover:bg-gray-700 focus:outline-none", weight)>
+                        { label }
+                    </button>
+                }
+            };
+
+            let t = &self.transform;
+            let fit_cb = self.link.callback(|_| TransformMenuEvents::Fit(FitMode::Fit));
+            let fill_cb = self.link.callback(|_| TransformMenuEvents::Fit(FitMode::Fill));
+            let four_three_cb = self.link.callback(|_| TransformMenuEvents::Aspect((4, 3)));
+            let sixteen_nine_cb = self.link.callback(|_| TransformMenuEvents::Aspect((16, 9)));
+            let rotate_cb = self.link.callback(|_| TransformMenuEvents::Rotate);
+            let reset_cb = self.link.callback(|_| TransformMenuEvents::Reset);
+            let zoom_cb = self.link.callback(|e: InputData| {
+                TransformMenuEvents::Zoom(e.value.parse().unwrap_or(1.0))
+            });
+
+            html! {
+                <div class="absolute right-0 mt-2 w-56 bg-gray-800 border-2 border-gray-700 rounded-lg p-2 z-10">
+                    <div class="flex flex-wrap mb-2">
+                        { preset("Fit", t.fit == FitMode::Fit && t.aspect.is_none(), fit_cb) }
+                        { preset("Fill", t.fit == FitMode::Fill && t.aspect.is_none(), fill_cb) }
+                        { preset("4:3", t.aspect == Some((4, 3)), four_three_cb) }
+                        { preset("16:9", t.aspect == Some((16, 9)), sixteen_nine_cb) }
+                        { preset(&format!("↻ {}°", t.rotation), t.rotation != 0, rotate_cb) }
+                    </div>
+                    <label class="block text-gray-400 text-xs px-2">
+                        { format!("Zoom {:.0}%", t.zoom * 100.0) }
+                        <input
+                            type="range"
+                            class="slider w-full"
+                            min="1"
+                            max=MAX_ZOOM.to_string()
+                            step="0.01"
+                            value=t.zoom.to_string()
+                            oninput=zoom_cb
+                        />
+                    </label>
+                    <div class="flex justify-end">
+                        <button onclick=reset_cb class="text-gray-400 text-xs px-2 focus:outline-none">{ "Reset" }</button>
+                    </div>
+                </div>
+            }
+        } else {
+            html!{}
+        };
+
+        html! {
+            <div class="relative flex items-center mx-2">
+                <button onclick=toggle_cb class=("text-white text-sm font-semibold border-2 rounded-lg px-2 focus:outline-none", border) title="Zoom, crop and rotate the video">
+                    { "⤢" }
+                </button>
+                { menu }
+            </div>
+        }
+    }
+}
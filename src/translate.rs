@@ -0,0 +1,65 @@
+use yew::services::ConsoleService;
+
+use reqwest::Client;
+use serde::{Serialize, Deserialize};
+
+use crate::binder;
+use crate::settings;
+
+
+/// A chat message's text translated into the user's language.
+#[derive(Clone, Deserialize)]
+pub struct Translation {
+    /// The translated text.
+    pub text: String,
+
+    /// The language detected for the original text e.g. `fr`.
+    #[serde(default)]
+    pub source_language: Option<String>,
+}
+
+impl Translation {
+    /// If the original was in another language, messages already in the
+    /// user's language are left as they are.
+    pub fn is_needed(&self) -> bool {
+        self.source_language.as_deref() != Some(binder::browser_language().as_str())
+    }
+}
+
+
+#[derive(Serialize)]
+struct TranslateRequest<'a> {
+    text: &'a str,
+
+    /// The language to translate into, the source is detected.
+    target_language: String,
+}
+
+
+/// Translates the text into the user's browser language, None if the
+/// request failed.
+pub async fn translate(room_id: String, text: String) -> Option<Translation> {
+    let url = settings::get_translate_url(&room_id);
+    let body = TranslateRequest {
+        text: &text,
+        target_language: binder::browser_language(),
+    };
+
+    let resp = Client::new()
+        .post(&url)
+        .json(&body)
+        .send()
+        .await
+        .and_then(|resp| resp.error_for_status());
+
+    let translation = match resp {
+        Ok(resp) => resp.json::<Translation>().await.ok(),
+        Err(_) => None,
+    };
+
+    if translation.is_none() {
+        ConsoleService::warn("Failed to translate message");
+    }
+
+    translation
+}
@@ -1,22 +1,78 @@
-use yew::utils::document;
+use yew::utils::window;
 use yew::{Component, ComponentLink};
+use yew::format::Json;
+use yew::services::ConsoleService;
+use yew::services::storage::{StorageService, Area};
 use std::future::Future;
 use serde::Serialize;
-use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
 use wasm_bindgen_futures::spawn_local;
 
-use crate::settings;
+use crate::binder;
+use crate::opcodes::OpCode;
+use crate::outbox;
+use crate::perf;
+use crate::timefmt::parse_clock;
 
 
-/// Extracts the room id from the expecting url.
-pub fn get_room_id() -> String {
-    let doc = document();
-    let mut url = doc.url().unwrap();
+/// The parts of the room url the page was opened with, e.g.
+/// `/room/1234?t=1h23m45s`.
+pub struct RoomLocation {
+    pub room_id: String,
 
-    let split_at = settings::get_room_url().len();
-    let room_id = url.split_off(split_at + 1);
+    /// Where a shared link asks playback to start in seconds.
+    pub start_at: Option<f64>,
+}
+
+impl RoomLocation {
+    /// Reads the room from the page's url.
+    pub fn current() -> Self {
+        let path = window().location().pathname().unwrap_or_default();
+        let room_id = path
+            .trim_end_matches('/')
+            .rsplit('/')
+            .next()
+            .unwrap_or_default()
+            .to_string();
 
-    room_id
+        Self {
+            room_id,
+            start_at: start_at_param(),
+        }
+    }
+}
+
+
+/// The decoded key value pairs of the page's query string.
+pub fn query_params() -> Vec<(String, String)> {
+    let search = window().location().search().unwrap_or_default();
+
+    search
+        .trim_start_matches('?')
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let mut parts = pair.splitn(2, '=');
+            let key = parts.next().unwrap_or_default().to_string();
+            let value = js_sys::decode_uri_component(parts.next().unwrap_or_default())
+                .ok()
+                .and_then(|value| value.as_string())
+                .unwrap_or_default();
+
+            (key, value)
+        })
+        .collect()
+}
+
+
+/// The start time of a shared link from its `t` query parameter, either
+/// seconds or a time like `1h23m45s` or `1:23:45`.
+pub fn start_at_param() -> Option<f64> {
+    query_params()
+        .into_iter()
+        .find(|(key, _)| key == "t")
+        .and_then(|(_, value)| parse_clock(&value))
 }
 
 /// Starts a future with a completion callback of a given component link.
@@ -30,6 +86,13 @@ where
 }
 
 
+/// A random hex id for correlating events between clients, these are
+/// used to target edits and drop duplicates so they must not collide.
+pub fn random_id() -> String {
+    binder::random_id()
+}
+
+
 /// Starts a future which returns nothing.
 pub fn start_future<F>(future: F)
 where
@@ -39,13 +102,43 @@ where
 }
 
 
+/// Emits the event to the room through the room's outbox, events are
+/// sent in order and retried if the api can't be reached.
 pub async fn emit_event<T: Serialize>(room_id: String, payload: T) {
-    let url = settings::get_emit_url(&room_id);
+    match serde_json::to_value(payload) {
+        Ok(event) => {
+            if let Some(opcode) = event.get("opcode").and_then(Value::as_u64) {
+                perf::record_sent(opcode as OpCode);
+            }
+            outbox::push(room_id, event)
+        },
+        Err(_) => ConsoleService::error("Failed to serialize event for the room"),
+    }
+}
+
+
+/// Loads a value from local storage, returning None if it is missing,
+/// can't be parsed or storage is unavailable.
+pub fn load_local<T: DeserializeOwned>(key: &str) -> Option<T> {
+    let storage = StorageService::new(Area::Local).ok()?;
+    let Json(value) = storage.restore::<Json<anyhow::Result<T>>>(key);
 
-    let _ = Client::new()
-        .put(&url)
-        .json(&payload)
-        .send()
-        .await;
+    value.ok()
 }
 
+
+/// Stores a value in local storage, silently doing nothing if storage
+/// is unavailable e.g. in private browsing.
+pub fn store_local<T: Serialize>(key: &str, value: &T) {
+    if let Ok(mut storage) = StorageService::new(Area::Local) {
+        storage.store(key, Json(value));
+    }
+}
+
+
+/// Removes a value from local storage if storage is available.
+pub fn remove_local(key: &str) {
+    if let Ok(mut storage) = StorageService::new(Area::Local) {
+        storage.remove(key);
+    }
+}
@@ -0,0 +1,276 @@
+use wasm_bindgen::closure::Closure;
+use yew::NodeRef;
+use yew::web_sys::Element;
+
+use crate::binder;
+
+
+/// A handle to a video.js player, holding the refs to its element and to
+/// the container around it with our controls and overlays.
+///
+/// Clones share the same refs, the component rendering the player binds
+/// them and every other component drives that player through its own
+/// clone. Until the player is on the page every call does nothing and
+/// the getters return their empty value.
+#[derive(Clone, Default, PartialEq)]
+pub struct VideoElementHandle {
+    element: NodeRef,
+    container: NodeRef,
+}
+
+impl VideoElementHandle {
+    /// The ref to bind to the player's `<video-js>` element.
+    pub fn element_ref(&self) -> NodeRef {
+        self.element.clone()
+    }
+
+    /// The ref to bind to the element around the player that is made
+    /// fullscreen and keeps its place while docked.
+    pub fn container_ref(&self) -> NodeRef {
+        self.container.clone()
+    }
+
+    pub fn element(&self) -> Option<Element> {
+        self.element.cast::<Element>()
+    }
+
+    pub fn container(&self) -> Option<Element> {
+        self.container.cast::<Element>()
+    }
+
+    /// A js function giving the player's element or null, for shims set
+    /// up before the player is created.
+    pub fn element_getter(&self) -> Closure<dyn FnMut() -> Option<Element>> {
+        let handle = self.clone();
+        Closure::wrap(Box::new(move || handle.element()) as Box<dyn FnMut() -> Option<Element>>)
+    }
+
+    pub fn create(
+        &self,
+        src: &str,
+        mime_type: &str,
+        on_error: &Closure<dyn FnMut(u32, String, u32)>,
+    ) -> bool {
+        self.element()
+            .map(|element| binder::create_stream_player(&element, src, mime_type, on_error))
+            .unwrap_or(false)
+    }
+
+    pub fn create_secondary(&self, src: &str, mime_type: &str) -> bool {
+        self.element()
+            .map(|element| binder::create_secondary_player(&element, src, mime_type))
+            .unwrap_or(false)
+    }
+
+    pub fn destroy(&self) {
+        if let Some(element) = self.element() {
+            binder::destroy_stream_player(&element);
+        }
+    }
+
+    pub fn state(&self) -> Option<String> {
+        binder::get_player_state(&self.element()?)
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.element()
+            .map(|element| binder::is_video_playing(&element))
+            .unwrap_or(false)
+    }
+
+    pub fn toggle_play(&self) {
+        if let Some(element) = self.element() {
+            binder::player_toggle_play(&element);
+        }
+    }
+
+    pub fn set_paused(&self, paused: bool) {
+        if let Some(element) = self.element() {
+            binder::player_set_paused(&element, paused);
+        }
+    }
+
+    pub fn seek(&self, position: f64) {
+        if let Some(element) = self.element() {
+            binder::player_seek(&element, position);
+        }
+    }
+
+    pub fn set_volume(&self, volume: f64) {
+        if let Some(element) = self.element() {
+            binder::player_set_volume(&element, volume);
+        }
+    }
+
+    pub fn set_muted(&self, muted: bool) {
+        if let Some(element) = self.element() {
+            binder::set_video_muted(&element, muted);
+        }
+    }
+
+    pub fn set_rate(&self, rate: f64) {
+        if let Some(element) = self.element() {
+            binder::set_rate(&element, rate);
+        }
+    }
+
+    pub fn fade_out(&self, duration_ms: u32) {
+        if let Some(element) = self.element() {
+            binder::fade_out_video(&element, duration_ms);
+        }
+    }
+
+    pub fn toggle_fullscreen(&self) {
+        if let Some(element) = self.element() {
+            binder::player_toggle_fullscreen(self.container(), &element);
+        }
+    }
+
+    pub fn resume_at(&self, position: f64, play: bool) {
+        if let Some(element) = self.element() {
+            binder::resume_at(&element, position, play);
+        }
+    }
+
+    pub fn renditions(&self) -> String {
+        self.element()
+            .map(|element| binder::get_renditions(&element))
+            .unwrap_or_else(|| "[]".to_string())
+    }
+
+    pub fn set_rendition(&self, height: u32) {
+        if let Some(element) = self.element() {
+            binder::set_rendition(&element, height);
+        }
+    }
+
+    pub fn set_data_saver(&self, enabled: bool) {
+        if let Some(element) = self.element() {
+            binder::set_data_saver(&element, enabled);
+        }
+    }
+
+    pub fn playback_stats(&self) -> Option<String> {
+        binder::get_playback_stats(&self.element()?)
+    }
+
+    pub fn set_audio_delay(&self, delay_ms: u32) -> bool {
+        self.element()
+            .map(|element| binder::set_audio_delay(&element, delay_ms))
+            .unwrap_or(false)
+    }
+
+    pub fn set_audio_gain(&self, gain: f64) -> bool {
+        self.element()
+            .map(|element| binder::set_audio_gain(&element, gain))
+            .unwrap_or(false)
+    }
+
+    pub fn set_stream_loudness(&self, lufs: Option<f64>, enabled: bool) -> bool {
+        self.element()
+            .map(|element| binder::set_stream_loudness(&element, lufs, enabled))
+            .unwrap_or(false)
+    }
+
+    pub fn set_loudness_normalization(&self, enabled: bool) -> bool {
+        self.element()
+            .map(|element| binder::set_loudness_normalization(&element, enabled))
+            .unwrap_or(false)
+    }
+
+    pub fn set_ambient_mode(&self, enabled: bool, interval_ms: u32) -> bool {
+        self.element()
+            .map(|element| binder::set_ambient_mode(&element, enabled, interval_ms))
+            .unwrap_or(false)
+    }
+
+    pub fn apply_transform(&self, fit: &str, aspect: f64, rotation: u32, zoom: f64) {
+        if let Some(element) = self.element() {
+            binder::apply_video_transform(&element, fit, aspect, rotation, zoom);
+        }
+    }
+
+    pub fn apply_remote(&self, action: &str, position: f64) {
+        if let Some(element) = self.element() {
+            binder::apply_remote(&element, action, position);
+        }
+    }
+
+    pub fn seek_locally(&self, position: f64) {
+        if let Some(element) = self.element() {
+            binder::seek_locally(&element, position);
+        }
+    }
+
+    pub fn select_subtitle(&self, slot: u32, index: i32) -> bool {
+        self.element()
+            .map(|element| binder::select_subtitle(&element, slot, index))
+            .unwrap_or(false)
+    }
+
+    pub fn set_subtitle_offset(&self, slot: u32, offset_secs: f64) {
+        if let Some(element) = self.element() {
+            binder::set_subtitle_offset(&element, slot, offset_secs);
+        }
+    }
+
+    pub fn active_cue_text(&self, slot: u32) -> String {
+        self.element()
+            .map(|element| binder::active_cue_text(&element, slot))
+            .unwrap_or_default()
+    }
+
+    pub fn add_subtitle_track(&self, label: &str, vtt: &str) -> i32 {
+        self.element()
+            .map(|element| binder::add_subtitle_track(&element, label, vtt))
+            .unwrap_or(-1)
+    }
+
+    pub fn capture_thumbnail(&self, interval_secs: f64) -> bool {
+        self.element()
+            .map(|element| binder::capture_thumbnail(&element, interval_secs))
+            .unwrap_or(false)
+    }
+
+    pub fn thumbnail_at(&self, position: f64, interval_secs: f64) -> Option<String> {
+        binder::thumbnail_at(&self.element()?, position, interval_secs)
+    }
+
+    pub fn capture_frame(&self) -> Option<String> {
+        binder::capture_frame(&self.element()?)
+    }
+
+    pub fn download_frame(&self, filename: &str) -> bool {
+        self.element()
+            .map(|element| binder::download_frame(&element, filename))
+            .unwrap_or(false)
+    }
+
+    pub async fn copy_frame(&self) -> bool {
+        match self.element() {
+            Some(element) => binder::copy_frame(&element).await.as_bool().unwrap_or(false),
+            None => false,
+        }
+    }
+
+    pub fn diagnostic_summary(&self) -> String {
+        binder::diagnostic_summary(self.element())
+    }
+
+    pub fn speak_as(&self, voice_seed: u32, text: String, rate: f32) -> bool {
+        binder::speak_as(self.element(), voice_seed, text, rate)
+    }
+
+    pub fn watch_remote_playback(&self, on_state: &Closure<dyn FnMut(String)>) -> bool {
+        self.element()
+            .map(|element| binder::watch_remote_playback(&element, on_state))
+            .unwrap_or(false)
+    }
+
+    pub fn prompt_remote_playback(&self) {
+        if let Some(element) = self.element() {
+            binder::prompt_remote_playback(&element);
+        }
+    }
+
+}
@@ -0,0 +1,330 @@
+use yew::prelude::*;
+use yew::services::IntervalService;
+use yew::services::interval::IntervalTask;
+
+use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::binder;
+use crate::controls::PlayerState;
+use crate::opcodes;
+use crate::settings;
+use crate::timefmt::format_duration;
+use crate::utils::{start_future, emit_event};
+use crate::video::VideoElementHandle;
+use crate::websocket::{WsHandler, WebsocketMessage, WrappingWsMessage};
+
+
+/// How often the player is checked, every check counts as this long.
+const TICK_INTERVAL_MS: u64 = 1000;
+
+/// How many ticks between reporting to the host.
+const REPORT_EVERY_TICKS: u32 = 30;
+
+/// How long the host keeps a viewer listed without a new report, in ms.
+const REPORT_EXPIRY_MS: f64 = 90_000.0;
+
+/// How far apart positions can be and still count as in sync.
+const IN_SYNC_SECS: f64 = 1.0;
+
+
+/// How long this session has spent in each playback state, in seconds.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WatchTotals {
+    pub watching_secs: f64,
+    pub buffering_secs: f64,
+    pub paused_secs: f64,
+}
+
+impl WatchTotals {
+    /// Counts the time since the last check towards the player's state.
+    fn record(&mut self, state: &PlayerState, secs: f64) {
+        if state.buffering {
+            self.buffering_secs += secs;
+        } else if state.paused {
+            self.paused_secs += secs;
+        } else {
+            self.watching_secs += secs;
+        }
+    }
+}
+
+
+/// A viewer's watch time and position, sent to the host every so often.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchReport {
+    #[serde(flatten)]
+    pub totals: WatchTotals,
+
+    /// The viewer's playback position in seconds.
+    pub position: f64,
+
+    /// The server time the report was made at in ms.
+    pub sent_at: f64,
+
+    /// The name of the viewer, attached by the gateway.
+    #[serde(default, skip_serializing)]
+    pub username: String,
+}
+
+
+#[derive(Properties, Clone)]
+pub struct WatchStatsProperties {
+    pub ws: WsHandler,
+
+    /// The room id.
+    pub room_id: String,
+
+    /// If the active user controls playback, they are shown every
+    /// viewer's numbers.
+    pub is_leader: bool,
+
+    /// The player whose time is counted.
+    pub video: VideoElementHandle,
+}
+
+
+/// The events that can be invoked by callbacks for the WatchStats.
+pub enum WatchStatsEvents {
+    /// Count the time since the last tick.
+    Tick,
+
+    /// A viewer has reported their numbers.
+    Report(WebsocketMessage),
+
+    /// The panel has been opened or closed.
+    Toggle,
+}
+
+
+/// Tracks how long the user has been watching, buffering and paused
+/// this session and shows it in a small panel.
+///
+/// Every viewer reports their numbers and position to the room, the host
+/// is shown them all along with how far behind each viewer is.
+pub struct WatchStats {
+    link: ComponentLink<Self>,
+    _ws: WsHandler,
+    _tick: IntervalTask,
+    video: VideoElementHandle,
+    room_id: String,
+    is_leader: bool,
+    open: bool,
+
+    totals: WatchTotals,
+    ticks: u32,
+
+    /// The latest report of every viewer, by name.
+    reports: HashMap<String, WatchReport>,
+}
+
+impl WatchStats {
+    fn report(&self, state: &PlayerState) {
+        let report = WatchReport {
+            totals: self.totals.clone(),
+            position: state.position,
+            sent_at: binder::server_now(),
+            username: String::new(),
+        };
+
+        let payload = WrappingWsMessage {
+            opcode: opcodes::OP_WATCH_STATS,
+            payload: serde_json::to_value(report).ok(),
+            mirror: false,
+        };
+        start_future(emit_event(self.room_id.clone(), payload));
+    }
+
+    /// How far behind the host the viewer was when they reported, from
+    /// where the host's player would have been at the time.
+    fn behind(report: &WatchReport, host: &PlayerState) -> f64 {
+        let elapsed = if host.paused {
+            0.0
+        } else {
+            (binder::server_now() - report.sent_at).max(0.0) / 1000.0 * host.rate
+        };
+
+        host.position - elapsed - report.position
+    }
+
+    fn totals_html(totals: &WatchTotals) -> Html {
+        html! {
+            <>
+                <td class="pr-2">{ format_duration(totals.watching_secs) }</td>
+                <td class="pr-2">{ format_duration(totals.buffering_secs) }</td>
+                <td class="pr-2">{ format_duration(totals.paused_secs) }</td>
+            </>
+        }
+    }
+
+    fn viewers_html(&self) -> Html {
+        if !self.is_leader {
+            return html!{};
+        }
+
+        let host = match PlayerState::read(&self.video) {
+            Some(state) => state,
+            None => return html!{},
+        };
+
+        let mut names: Vec<&String> = self.reports.keys().collect();
+        names.sort();
+
+        let rows = names.into_iter().map(|name| {
+            let report = &self.reports[name];
+            let behind = Self::behind(report, &host);
+            let (sync, colour) = if behind.abs() < IN_SYNC_SECS {
+                ("in sync".to_string(), "text-green-400")
+            } else if behind > 0.0 {
+                (format!("{} behind", format_duration(behind)), "text-yellow-400")
+            } else {
+                (format!("{} ahead", format_duration(-behind)), "text-yellow-400")
+            };
+
+            html! {
+                <tr>
+                    <td class="pr-2 truncate">{ name }</td>
+                    { Self::totals_html(&report.totals) }
+                    <td class=colour>{ sync }</td>
+                </tr>
+            }
+        });
+
+        html! {
+            <>
+                <h2 class="text-white text-sm font-bold mt-3 mb-1">{ "Viewers" }</h2>
+                {
+                    if self.reports.is_empty() {
+                        html! { <p class="text-gray-400 text-xs">{ "No viewers have reported yet" }</p> }
+                    } else {
+                        html! {
+                            <table class="text-gray-300 text-xs w-full">
+                                <tr class="text-gray-400">
+                                    <th class="text-left">{ "Name" }</th>
+                                    <th class="text-left">{ "Watching" }</th>
+                                    <th class="text-left">{ "Buffering" }</th>
+                                    <th class="text-left">{ "Paused" }</th>
+                                    <th class="text-left">{ "Sync" }</th>
+                                </tr>
+                                { for rows }
+                            </table>
+                        }
+                    }
+                }
+            </>
+        }
+    }
+}
+
+impl Component for WatchStats {
+    type Message = WatchStatsEvents;
+    type Properties = WatchStatsProperties;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let tick = IntervalService::spawn(
+            Duration::from_millis(TICK_INTERVAL_MS),
+            link.callback(|_| WatchStatsEvents::Tick),
+        );
+
+        let ws = props.ws;
+        let report_cb = link.callback(WatchStatsEvents::Report);
+        ws.subscribe_to_message(settings::WATCH_STATS_ID, opcodes::OP_WATCH_STATS, report_cb);
+
+        Self {
+            link,
+            _ws: ws,
+            _tick: tick,
+            video: props.video,
+            room_id: props.room_id,
+            is_leader: props.is_leader,
+            open: false,
+            totals: WatchTotals::default(),
+            ticks: 0,
+            reports: HashMap::new(),
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            WatchStatsEvents::Tick => {
+                let state = match PlayerState::read(&self.video) {
+                    Some(state) => state,
+                    None => return false,
+                };
+
+                self.totals.record(&state, TICK_INTERVAL_MS as f64 / 1000.0);
+
+                self.ticks += 1;
+                if self.ticks >= REPORT_EVERY_TICKS {
+                    self.ticks = 0;
+
+                    // The host has nobody to be behind.
+                    if !self.is_leader {
+                        self.report(&state);
+                    }
+
+                    let now = binder::server_now();
+                    self.reports.retain(|_, report| now - report.sent_at < REPORT_EXPIRY_MS);
+                }
+
+                self.open
+            },
+            WatchStatsEvents::Report(msg) => {
+                if !self.is_leader {
+                    return false;
+                }
+
+                match msg.unwrap_and_into::<WatchReport>() {
+                    Some(report) if !report.username.is_empty() => {
+                        self.reports.insert(report.username.clone(), report);
+                        self.open
+                    },
+                    _ => false,
+                }
+            },
+            WatchStatsEvents::Toggle => {
+                self.open = !self.open;
+                true
+            },
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        let changed = self.is_leader != props.is_leader;
+        self.is_leader = props.is_leader;
+        changed
+    }
+
+    fn view(&self) -> Html {
+        let toggle_cb = self.link.callback(|_| WatchStatsEvents::Toggle);
+
+        let panel = if self.open {
+            html! {
+                <div class="absolute right-0 mt-2 w-80 bg-gray-800 border-2 border-gray-700 rounded-lg p-4 z-10">
+                    <h1 class="text-white font-bold mb-2">{ "Watch time" }</h1>
+                    <table class="text-gray-300 text-xs w-full">
+                        <tr class="text-gray-400">
+                            <th class="text-left">{ "Watching" }</th>
+                            <th class="text-left">{ "Buffering" }</th>
+                            <th class="text-left">{ "Paused" }</th>
+                        </tr>
+                        <tr>{ Self::totals_html(&self.totals) }</tr>
+                    </table>
+                    { self.viewers_html() }
+                </div>
+            }
+        } else {
+            html!{}
+        };
+
+        html! {
+            <div class="relative flex items-center mx-2">
+                <button onclick=toggle_cb class="text-white text-lg focus:outline-none" title="Watch time this session">
+                    { "⏱" }
+                </button>
+                { panel }
+            </div>
+        }
+    }
+}
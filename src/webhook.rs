@@ -0,0 +1,217 @@
+use reqwest::Client;
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+use std::ops::Range;
+
+
+/// Discord's limit on the length of a message's content.
+const CONTENT_LIMIT: usize = 2000;
+
+/// Discord's limit on the length of a webhook username override.
+const USERNAME_LIMIT: usize = 80;
+
+/// Words Discord refuses to have in a webhook username override.
+const FORBIDDEN_USERNAME_WORDS: [&str; 2] = ["discord", "clyde"];
+
+
+#[derive(Serialize)]
+struct WebhookMessage<'a> {
+    username: &'a str,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    avatar_url: Option<&'a str>,
+
+    content: &'a str,
+}
+
+
+/// The error body Discord responds with when rejecting a payload, the
+/// keys of `errors` are the rejected fields.
+#[derive(Deserialize)]
+struct WebhookError {
+    #[serde(default)]
+    message: String,
+
+    #[serde(default)]
+    errors: Option<Value>,
+}
+
+impl WebhookError {
+    fn rejected(&self, field: &str) -> bool {
+        self.errors
+            .as_ref()
+            .and_then(|errors| errors.get(field))
+            .is_some()
+    }
+}
+
+
+/// Truncates the string to at most `limit` characters.
+fn truncate(s: &str, limit: usize) -> &str {
+    match s.char_indices().nth(limit) {
+        Some((idx, _)) => &s[..idx],
+        None => s,
+    }
+}
+
+
+/// The byte range of the first case insensitive occurrence of the
+/// lowercase word in the string, on the string's own char boundaries as
+/// lowercasing can change the length of other characters.
+fn find_ignore_case(s: &str, word: &str) -> Option<Range<usize>> {
+    s.char_indices().find_map(|(start, _)| {
+        let mut chars = s[start..].char_indices();
+        for expected in word.chars() {
+            let (_, c) = chars.next()?;
+            if !c.to_lowercase().eq(std::iter::once(expected)) {
+                return None;
+            }
+        }
+
+        let end = chars.next().map_or(s.len(), |(idx, _)| start + idx);
+        Some(start..end)
+    })
+}
+
+
+/// Makes the username acceptable to Discord by removing forbidden words
+/// and truncating it.
+fn sanitize_username(username: &str) -> String {
+    let mut sanitized = username.to_string();
+    for word in FORBIDDEN_USERNAME_WORDS.iter() {
+        while let Some(range) = find_ignore_case(&sanitized, word) {
+            sanitized.replace_range(range, "***");
+        }
+    }
+
+    let sanitized = truncate(sanitized.trim(), USERNAME_LIMIT).to_string();
+    if sanitized.is_empty() {
+        "Spooderfy User".to_string()
+    } else {
+        sanitized
+    }
+}
+
+
+/// Posts a raw payload such as an embed to the Discord webhook, returning
+/// if Discord accepted it.
+pub async fn post_embed(url: &str, payload: Value) -> bool {
+    let resp = Client::new()
+        .post(url)
+        .json(&payload)
+        .send()
+        .await;
+
+    matches!(resp, Ok(resp) if resp.status().is_success())
+}
+
+
+/// Posts a message to the Discord webhook.
+///
+/// If Discord rejects the payload the reason is mapped to some guidance
+/// for the user and the message is retried once with the rejected fields
+/// sanitized. The guidance is returned so it can be shown to the user,
+/// None means the message went through untouched.
+pub async fn post_message(
+    url: &str,
+    username: &str,
+    avatar_url: &str,
+    content: &str,
+) -> Option<String> {
+    let payload = WebhookMessage {
+        username,
+        avatar_url: Some(avatar_url),
+        content,
+    };
+
+    let resp = Client::new()
+        .post(url)
+        .json(&payload)
+        .send()
+        .await;
+
+    let resp = match resp {
+        Ok(resp) if resp.status().is_success() => return None,
+        Ok(resp) => resp,
+        Err(_) => return Some("Failed to reach Discord, your message was only sent to the room.".to_string()),
+    };
+
+    let error = match resp.json::<WebhookError>().await {
+        Ok(error) => error,
+        Err(_) => return Some("Discord rejected your message, it was only sent to the room.".to_string()),
+    };
+
+    let mut guidance = vec![];
+
+    let content = if error.rejected("content") || content.chars().count() > CONTENT_LIMIT {
+        guidance.push(format!("Message too long for Discord, trimmed to {} chars.", CONTENT_LIMIT));
+        truncate(content, CONTENT_LIMIT)
+    } else {
+        content
+    };
+
+    let avatar_url = if error.rejected("avatar_url") {
+        guidance.push("Your avatar was rejected by Discord, sent without it.".to_string());
+        None
+    } else {
+        Some(avatar_url)
+    };
+
+    let sanitized_username;
+    let username = if error.rejected("username") {
+        guidance.push("Your name was rejected by Discord, sent with a cleaned up name.".to_string());
+        sanitized_username = sanitize_username(username);
+        &sanitized_username
+    } else {
+        username
+    };
+
+    if guidance.is_empty() {
+        return Some(format!("Discord rejected your message: {}", error.message));
+    }
+
+    let retry = WebhookMessage {
+        username,
+        avatar_url,
+        content,
+    };
+
+    let resp = Client::new()
+        .post(url)
+        .json(&retry)
+        .send()
+        .await;
+
+    match resp {
+        Ok(resp) if resp.status().is_success() => Some(guidance.join(" ")),
+        _ => Some("Discord rejected your message, it was only sent to the room.".to_string()),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::sanitize_username;
+
+    #[test]
+    fn forbidden_words_are_removed_in_any_case() {
+        assert_eq!(sanitize_username("DiScOrD fan"), "*** fan");
+        assert_eq!(sanitize_username("clyde"), "***");
+    }
+
+    #[test]
+    fn every_occurrence_is_removed() {
+        assert_eq!(sanitize_username("discord and discord"), "*** and ***");
+    }
+
+    #[test]
+    fn characters_that_change_length_when_lowercased_dont_panic() {
+        assert_eq!(sanitize_username("İİ discord"), "İİ ***");
+        assert_eq!(sanitize_username("İdiscord"), "İ***");
+    }
+
+    #[test]
+    fn empty_names_fall_back() {
+        assert_eq!(sanitize_username("  "), "Spooderfy User");
+    }
+}
@@ -3,6 +3,9 @@ use wasm_bindgen::prelude::*;
 // wasm-bindgen will automatically take care of including this script
 #[wasm_bindgen(module = "/src/websocket/js/handle_ws.js")]
 extern "C" {
+    /// The browser's WebSocket, only ever handled through these bindings.
+    pub type JsWebSocket;
+
     #[wasm_bindgen(js_name = "startWs")]
     pub fn start_websocket(
         url: String,
@@ -10,5 +13,12 @@ extern "C" {
         on_close: &Closure<dyn FnMut()>,
         on_error: &Closure<dyn FnMut()>,
         on_message: &Closure<dyn FnMut(String)>,
-    ) -> JsValue;
+    ) -> JsWebSocket;
+
+    #[wasm_bindgen(js_name = "sendWs")]
+    pub fn send_websocket(socket: &JsWebSocket, data: String) -> bool;
+
+    /// Closes the socket for good, it isn't reconnected.
+    #[wasm_bindgen(js_name = "closeWs")]
+    pub fn close_websocket(socket: &JsWebSocket);
 }
\ No newline at end of file
@@ -38,11 +38,12 @@ pub enum WebsocketMessage {
 
 impl WebsocketMessage {
     /// Consumes the payload value returning it's converted value.
-    /// Panics is the value is not able to be serialized,
-    /// and returns None if it is not a Payload type enum.
+    /// Returns None if it is not a Payload type enum or the value
+    /// is not able to be deserialized, as payloads are relayed from
+    /// other members and can't be trusted to be well formed.
     pub fn unwrap_and_into<T: DeserializeOwned>(self) -> Option<T> {
         if let Self::Payload(value) = self {
-            Some(serde_json::from_value::<T>(value).unwrap())
+            serde_json::from_value::<T>(value).ok()
         } else {
             None
         }
@@ -8,9 +8,10 @@ export function startWs(
 ) {
     let socket = new WebSocket(url);
 
-    setInterval(() => {
-        socket.send("ping")
+    let ping = setInterval(() => {
+        if (socket.readyState === WebSocket.OPEN) { socket.send("ping") }
     }, 5000)
+    socket.addEventListener("close", () => clearInterval(ping));
 
     socket.onopen = on_open;
     socket.onclose = on_close;
@@ -21,4 +22,17 @@ export function startWs(
     };
 
     return socket
+}
+
+export function sendWs(socket, data) {
+    if (socket.readyState !== WebSocket.OPEN) { return false }
+
+    socket.send(data);
+    return true
+}
+
+export function closeWs(socket) {
+    // Without the close handler the socket isn't reconnected.
+    socket.onclose = null;
+    socket.close();
 }
\ No newline at end of file
@@ -17,12 +17,18 @@ use crate::websocket::identifiers::{
     WebsocketStatus
 };
 use crate::opcodes::OpCode;
+use crate::perf::{self, PerfSpan};
+use crate::binder;
 
 
 /// The internal websocket wrapped in a Rc and RefCell to make it
 /// cheap to clone.
 type InternalHandle = Rc<RefCell<InternalWebSocket>>;
 
+/// The current js socket, kept separate from the internal websocket so
+/// it can be sent to while the internal handle is borrowed by a callback.
+type SocketHandle = Rc<RefCell<Option<bind::JsWebSocket>>>;
+
 
 /// The base message for all websocket messages, giving the op code
 /// that is used to send the payload to their relevant events.
@@ -33,6 +39,12 @@ pub struct WrappingWsMessage {
 
     /// The payload / data for the given opcode.
     pub(crate) payload: Option<Value>,
+
+    /// Asks the gateway to mirror this message into the room's Discord
+    /// channel, only set on messages that are not already sent via the
+    /// webhook.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) mirror: bool,
 }
 
 
@@ -40,7 +52,10 @@ pub struct WrappingWsMessage {
 /// adding and removing subscribers of a given id.
 #[derive(Clone)]
 pub struct WsHandler {
-    internal: InternalHandle,
+    /// Keeps the internal socket and its js closures alive for as long
+    /// as any handle exists.
+    _internal: InternalHandle,
+    socket: SocketHandle,
     status_queue: StatusUpdateQueue,
     message_queue: MessageUpdateQueue,
 }
@@ -49,9 +64,11 @@ impl WsHandler {
     /// Connects to a given websocket returning a handle.
     pub fn connect(url: impl Into<String>) -> WsHandler {
         let (internal, status, message) = InternalWebSocket::connect(url.into());
+        let socket = internal.borrow().socket.clone();
 
         Self {
-            internal,
+            _internal: internal,
+            socket,
             status_queue: status,
             message_queue: message,
         }
@@ -73,6 +90,32 @@ impl WsHandler {
     ) {
         self.message_queue.push((id, opcode, cb));
     }
+
+    /// Sends a message directly to the gateway rather than emitting it to
+    /// the room, returns false if the socket is not currently open.
+    pub fn send(&self, opcode: OpCode, payload: Option<Value>) -> bool {
+        let msg = WrappingWsMessage {
+            opcode,
+            payload,
+            mirror: false,
+        };
+        let data = serde_json::to_string(&msg).unwrap();
+
+        if let Some(socket) = self.socket.borrow().as_ref() {
+            perf::record_sent(opcode);
+            bind::send_websocket(socket, data)
+        } else {
+            false
+        }
+    }
+
+    /// Closes the connection for good, e.g. once the room has closed.
+    /// Subscribers are not told as the socket doesn't reconnect.
+    pub fn close(&self) {
+        if let Some(socket) = self.socket.borrow_mut().take() {
+            bind::close_websocket(&socket);
+        }
+    }
 }
 
 type MessageUpdateQueue = Rc<SegQueue<(usize, OpCode, Callback<WebsocketMessage>)>>;
@@ -86,7 +129,7 @@ pub struct InternalWebSocket {
     url: String,
 
     /// The internal websocket value, used to keep it alive in the heap.
-    internal: Option<JsValue>,
+    socket: SocketHandle,
 
     /// Signals if the ws closed on us or we just arent conencted yet.
     connecting_first: bool,
@@ -121,7 +164,7 @@ impl InternalWebSocket {
 
         let ws = Rc::new(RefCell::new(InternalWebSocket {
             url: url.clone(),
-            internal: None,
+            socket: Rc::new(RefCell::new(None)),
             retry_attempt: 0,
             connecting_first: true,
 
@@ -174,7 +217,7 @@ impl InternalWebSocket {
 
         {
             let mut inst_mut = ws.borrow_mut();
-            inst_mut.internal = Some(socket);
+            inst_mut.socket.replace(Some(socket));
             inst_mut.js_open = Some(on_open);
             inst_mut.js_close = Some(on_close);
             inst_mut.js_error = Some(on_error);
@@ -213,10 +256,19 @@ impl InternalWebSocket {
 
     /// A message has been received by the websocket.
     fn on_message(&mut self, msg: String) {
+        let _span = PerfSpan::start("ws.dispatch");
+
         let maybe_success = serde_json::from_str::<WrappingWsMessage>(&msg);
         let msg = if let Ok(msg) = maybe_success {
             msg
         } else {
+            // Attribute the failure to the opcode if the envelope is intact.
+            let opcode = serde_json::from_str::<Value>(&msg)
+                .ok()
+                .and_then(|value| value.get("opcode")?.as_u64())
+                .map(|opcode| opcode as OpCode);
+            perf::record_parse_failure(opcode);
+
             let msg = format!("Failed to parse incoming message! {:?}", &msg);
             ConsoleService::log(&msg);
             return;
@@ -229,10 +281,14 @@ impl InternalWebSocket {
             WebsocketMessage::Empty
         };
 
+        let started = binder::perf_now();
+
         self.check_message_updates();
         for (_, sub) in self.subscribers.iter() {
             sub.emit_message(opcode, msg.clone())
         }
+
+        perf::record_received(opcode, binder::perf_now() - started);
     }
 
     /// Attempts to reconnect to the socket.
@@ -243,13 +299,13 @@ impl InternalWebSocket {
 
         let socket = bind::start_websocket(
             self.url.clone(),
-            &self.js_open.as_ref().unwrap(),
-            &self.js_close.as_ref().unwrap(),
-            &self.js_error.as_ref().unwrap(),
-            &self.js_message.as_ref().unwrap(),
+            self.js_open.as_ref().unwrap(),
+            self.js_close.as_ref().unwrap(),
+            self.js_error.as_ref().unwrap(),
+            self.js_message.as_ref().unwrap(),
         );
 
-        self.internal = Some(socket);
+        self.socket.replace(Some(socket));
     }
 
     fn check_status_updates(&mut self) {
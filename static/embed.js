@@ -0,0 +1,3 @@
+import init, { run_embed } from "/static/wasm.js"
+
+init().then(() => run_embed())
\ No newline at end of file
@@ -0,0 +1,3 @@
+import init from "/static/wasm.js"
+
+init()
\ No newline at end of file
@@ -0,0 +1,10 @@
+// Toggles the nav on small screens.
+const toggle = document.getElementById("navToggle");
+const nav = document.getElementById("nav");
+
+toggle.addEventListener("click", () => {
+    const open = nav.classList.toggle("hidden") === false;
+    nav.classList.toggle("flex", open);
+    document.getElementById("navOpenIcon").classList.toggle("hidden", open);
+    document.getElementById("navCloseIcon").classList.toggle("hidden", !open);
+});
\ No newline at end of file
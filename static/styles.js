@@ -0,0 +1,33 @@
+// style-src-attr blocks the style attributes yew would render, so the
+// dynamic styles are rendered as data-style and applied here through the
+// CSSOM, which the policy doesn't restrict.
+function applyStyle(el) {
+    el.style.cssText = el.getAttribute("data-style") || "";
+}
+
+function applyStyles(root) {
+    if (root.hasAttribute("data-style")) {
+        applyStyle(root);
+    }
+    root.querySelectorAll("[data-style]").forEach(applyStyle);
+}
+
+new MutationObserver((mutations) => {
+    for (const mutation of mutations) {
+        if (mutation.type === "attributes") {
+            applyStyle(mutation.target);
+            continue;
+        }
+
+        for (const node of mutation.addedNodes) {
+            if (node.nodeType === Node.ELEMENT_NODE) {
+                applyStyles(node);
+            }
+        }
+    }
+}).observe(document.documentElement, {
+    subtree: true,
+    childList: true,
+    attributes: true,
+    attributeFilter: ["data-style"],
+});
\ No newline at end of file
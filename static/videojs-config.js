@@ -0,0 +1,3 @@
+// Stops video.js injecting its default styles in a <style> tag, which a
+// strict style-src blocks. video-js.css already has everything we need.
+window.VIDEOJS_NO_DYNAMIC_STYLE = true;
\ No newline at end of file